@@ -64,20 +64,45 @@ typedef enum ImFormat {
     IMSZ_JPEG    =  4u, ///< Joint Photographic Experts Group files.
     IMSZ_WEBP    =  5u, ///< WebP files. Supported sub-formats: `VP8 `, `VP8L`, `VP8X`.
     IMSZ_QOI     =  6u, ///< Quite OK Image format files.
-    IMSZ_PSD     =  7u, ///< Adobe Photoshop files.
+    IMSZ_PSD     =  7u, ///< Adobe Photoshop files, including Large Document Format (PSB) files.
     IMSZ_XCF     =  8u, ///< GIMP files.
-    IMSZ_ICO     =  9u, ///< ICO files can contain multiple images. This returns the dimensions of the biggest image in the file.
+    IMSZ_ICO     =  9u, ///< ICO files can contain multiple images. This returns the dimensions of the biggest image in the file. A width or height of 0 in the directory means 256.
     IMSZ_AVIF    = 10u, ///< AV1 Image File Format.
-    IMSZ_TIFF    = 11u, ///< Tag Image File Format. Supports big endian and little endian TIFF files.
+    IMSZ_TIFF    = 11u, ///< Tag Image File Format. Supports big endian and little endian TIFF and BigTIFF files.
     IMSZ_OpenEXR = 12u, ///< OpenEXR files.
     IMSZ_PCX     = 13u, ///< PiCture eXchange files.
     IMSZ_TGA     = 14u, ///< TARGA (Truevision Advanced Raster Graphics Adapter) files.
     IMSZ_DDS     = 15u, ///< DirectDraw Surface files.
     IMSZ_HEIF    = 16u, ///< HEIC/HEIF files.
-    IMSZ_JP2K    = 17u, ///< JPEG 2000 files.
+    IMSZ_JP2K    = 17u, ///< JPEG 2000 files, including JPX and JPM files and bare codestreams.
     IMSZ_DIB     = 18u, ///< Device-Independent bitmap files.
     IMSZ_VTF     = 19u, ///< Valve Texture Format.
     IMSZ_ILBM    = 20u, ///< Interleaved Bitmap files, including Planar Bitmap variant.
+    IMSZ_CUR     = 21u, ///< Windows cursor files. Same as ICO, but the directory entries contain the hotspot instead of the bit count.
+    IMSZ_DNG     = 22u, ///< Adobe Digital Negative files. The size of the raw sensor data is reported.
+    IMSZ_CR2     = 23u, ///< Canon RAW version 2 files.
+    IMSZ_NEF     = 24u, ///< Nikon Electronic Format files.
+    IMSZ_ARW     = 25u, ///< Sony Alpha RAW files.
+    IMSZ_ORF     = 26u, ///< Olympus RAW Format files.
+    IMSZ_RW2     = 27u, ///< Panasonic RAW files. The size is the sensor size.
+    IMSZ_PEF     = 28u, ///< Pentax Electronic File files.
+    IMSZ_RAF     = 29u, ///< Fujifilm RAW files.
+    IMSZ_CR3     = 30u, ///< Canon RAW version 3 files.
+    IMSZ_JXL     = 31u, ///< JPEG XL files, both bare codestreams and ISO-BMFF containers.
+    IMSZ_PBM     = 32u, ///< Portable Bitmap files, ASCII (`P1`) and binary (`P4`).
+    IMSZ_PGM     = 33u, ///< Portable Graymap files, ASCII (`P2`) and binary (`P5`).
+    IMSZ_PPM     = 34u, ///< Portable Pixmap files, ASCII (`P3`) and binary (`P6`).
+    IMSZ_PAM     = 35u, ///< Portable Arbitrary Map files (`P7`).
+    IMSZ_PFM     = 36u, ///< Portable Float Map files, color (`PF`) and grayscale (`Pf`).
+    IMSZ_SVG     = 37u, ///< Scalable Vector Graphics files. The size is resolved from the `width`, `height` and `viewBox` attributes of the root element, 0 x 0 if not possible.
+    IMSZ_KTX     = 38u, ///< Khronos Texture files, version 1.
+    IMSZ_KTX2    = 39u, ///< Khronos Texture files, version 2.
+    IMSZ_PVR     = 40u, ///< PowerVR texture files, version 3.
+    IMSZ_ASTC    = 41u, ///< ASTC compressed texture files.
+    IMSZ_BASIS   = 42u, ///< Basis Universal texture files.
+    IMSZ_HDR     = 43u, ///< Radiance RGBE files.
+    IMSZ_ICNS    = 44u, ///< Apple icon files. Returns the dimensions of the biggest icon.
+    IMSZ_ANI     = 45u, ///< Windows animated cursors.
 } ImFormat;
 
 /// Initialize an ImInfo variable with all 0 values.
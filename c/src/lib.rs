@@ -160,6 +160,31 @@ const FORMAT_NAMES: &'static [&'static [u8]] = &[
     b"DIB\0",
     b"VTF\0",
     b"ILBM\0",
+    b"CUR\0",
+    b"DNG\0",
+    b"CR2\0",
+    b"NEF\0",
+    b"ARW\0",
+    b"ORF\0",
+    b"RW2\0",
+    b"PEF\0",
+    b"RAF\0",
+    b"CR3\0",
+    b"JPEG XL\0",
+    b"PBM\0",
+    b"PGM\0",
+    b"PPM\0",
+    b"PAM\0",
+    b"PFM\0",
+    b"SVG\0",
+    b"KTX\0",
+    b"KTX2\0",
+    b"PVR\0",
+    b"ASTC\0",
+    b"Basis\0",
+    b"HDR\0",
+    b"ICNS\0",
+    b"ANI\0",
 ];
 
 #[cfg(target_family="windows")]
@@ -198,6 +223,31 @@ const FORMAT_NAMESW: &'static [&'static [u16]] = &[
     &w(b"DIB\0"),
     &w(b"VTF\0"),
     &w(b"ILBM\0"),
+    &w(b"CUR\0"),
+    &w(b"DNG\0"),
+    &w(b"CR2\0"),
+    &w(b"NEF\0"),
+    &w(b"ARW\0"),
+    &w(b"ORF\0"),
+    &w(b"RW2\0"),
+    &w(b"PEF\0"),
+    &w(b"RAF\0"),
+    &w(b"CR3\0"),
+    &w(b"JPEG XL\0"),
+    &w(b"PBM\0"),
+    &w(b"PGM\0"),
+    &w(b"PPM\0"),
+    &w(b"PAM\0"),
+    &w(b"PFM\0"),
+    &w(b"SVG\0"),
+    &w(b"KTX\0"),
+    &w(b"KTX2\0"),
+    &w(b"PVR\0"),
+    &w(b"ASTC\0"),
+    &w(b"Basis\0"),
+    &w(b"HDR\0"),
+    &w(b"ICNS\0"),
+    &w(b"ANI\0"),
 ];
 
 #[no_mangle]
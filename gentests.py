@@ -11,6 +11,7 @@ files = os.listdir(joinpath(base_dir, "testdata"))
 
 buf: List[str] = [
 """\
+#[allow(clippy::needless_return)]
 fn get_testdata(fname: &str) -> std::path::PathBuf {
     let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     path.push("testdata");
@@ -24,7 +25,9 @@ funcs: List[Tuple[str, str]] = []
 
 format_map = {
     '.exr': 'OpenEXR',
+    '.j2k': 'JP2K',
     '.jp2': 'JP2K',
+    '.jpx': 'JP2K',
 }
 
 format_names = {
@@ -44,6 +47,7 @@ for fname in files:
 
     funcs.append((ident, f"""
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn {ident}() {{
     let info = imsz::imsz_from_path(get_testdata("{fname}"));
     match info {{
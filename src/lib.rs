@@ -32,39 +32,53 @@ pub enum ImFormat {
     /// Graphics Interchange Format files in version GIF87a or GIF89a.
     GIF     =  1,
 
-    /// Portable Network Graphics files. Requires the first chunk to be `IHDR`.
+    /// Portable Network Graphics files. Requires the first chunk to be `IHDR`,
+    /// or `CgBI` followed by `IHDR` for Apple's optimized iOS PNGs. Header
+    /// fields are reported as [ImDetails::PNG].
     PNG     =  2,
 
     /// Windows Bitmap, both for Windows 2.0 (BITMAPCOREHEADER) and for newer
     /// versions (BITMAPINFOHEADER).
     BMP     =  3,
 
-    /// Joint Photographic Experts Group files.
+    /// Joint Photographic Experts Group files. All start-of-frame markers
+    /// (baseline, extended, progressive, lossless, arithmetic, hierarchical)
+    /// are supported. The coding process is reported as [ImDetails::JPEG].
     JPEG    =  4,
 
-    /// WebP files. Supported sub-formats: `VP8 `, `VP8L`, `VP8X`.
+    /// WebP files. Supported sub-formats: `VP8 `, `VP8L`, `VP8X`. The
+    /// feature flags and the frames of animations are reported as
+    /// [ImDetails::WebP].
     WEBP    =  5,
 
     /// Quite OK Image format files.
     QOI     =  6,
 
-    /// Adobe Photoshop files.
+    /// Adobe Photoshop files, including Large Document Format (PSB) files.
+    /// The header fields are reported as [ImDetails::PSD].
     PSD     =  7,
 
     /// GIMP files.
     XCF     =  8,
 
     /// ICO files can contain multiple images. This returns the dimensions of
-    /// the biggest image in the file.
+    /// the biggest image in the file. A width or height of 0 in the directory
+    /// means 256. See [ImOptions::embedded_headers].
     ICO     =  9,
 
-    /// AV1 Image File Format.
+    /// AV1 Image File Format. Detected by the `avif`, `avis` or `avio` brand.
+    /// The size is the size of the primary item, cropped by its `clap`
+    /// property.
     AVIF    = 10,
 
-    /// Tag Image File Format. Supports big endian and little endian TIFF files.
+    /// Tag Image File Format. Supports big endian and little endian TIFF and
+    /// BigTIFF files. Reports the first full resolution image, reduced
+    /// resolution images (thumbnails) and masks in front of it are skipped.
     TIFF    = 11,
 
-    /// OpenEXR files.
+    /// OpenEXR files. The data window, the tiled and deep flags and the
+    /// headers of all parts of multi-part files are reported as
+    /// [ImDetails::OpenEXR].
     OpenEXR = 12,
 
     /// PiCture eXchange files.
@@ -78,14 +92,17 @@ pub enum ImFormat {
     /// by this library.
     TGA     = 14,
 
-    /// DirectDraw Surface files.
+    /// DirectDraw Surface files. The texture layout (cube maps, volume
+    /// textures, arrays and mipmap levels) is reported as [ImDetails::Texture].
     DDS     = 15,
 
     /// HEIC/HEIF files. These are extremely similar to AVIF and use the same
-    /// parsing code.
+    /// parsing code. Detected by the HEVC brands (`heic`, `heix`, `hevc` etc.)
+    /// or the generic `mif1` and `msf1` brands.
     HEIF    = 16,
 
-    /// JPEG 2000 files.
+    /// JPEG 2000 files, including JPX and JPM files and bare codestreams
+    /// (`.j2k`, `.j2c`). For JPM files the size of the first page is reported.
     JP2K    = 17,
 
     /// Device-Independent Bitmap files.
@@ -96,6 +113,100 @@ pub enum ImFormat {
 
     /// Interleaved Bitmap files, including Planar Bitmap variant.
     ILBM    = 20,
+
+    /// Windows cursor files. Same as ICO, but the directory entries contain
+    /// the hotspot instead of the bit count. The hotspot of the biggest image
+    /// is reported as [ImDetails::CUR].
+    CUR     = 21,
+
+    /// Adobe Digital Negative files. Like all camera RAW formats the size of
+    /// the raw sensor data is reported, the size of the biggest embedded
+    /// preview is reported as [ImDetails::RAW].
+    DNG     = 22,
+
+    /// Canon RAW version 2 files (TIFF based).
+    CR2     = 23,
+
+    /// Nikon Electronic Format files (TIFF based).
+    NEF     = 24,
+
+    /// Sony Alpha RAW files (TIFF based).
+    ARW     = 25,
+
+    /// Olympus RAW Format files (TIFF based with their own magic).
+    ORF     = 26,
+
+    /// Panasonic RAW files (TIFF based with their own magic). The size is
+    /// the sensor size given in the Panasonic specific tags.
+    RW2     = 27,
+
+    /// Pentax Electronic File files (TIFF based).
+    PEF     = 28,
+
+    /// Fujifilm RAW files.
+    RAF     = 29,
+
+    /// Canon RAW version 3 files (ISO base media file format based).
+    CR3     = 30,
+
+    /// JPEG XL files, both bare codestreams and ISO-BMFF containers.
+    JXL     = 31,
+
+    /// Portable Bitmap files, ASCII (`P1`) and binary (`P4`).
+    PBM     = 32,
+
+    /// Portable Graymap files, ASCII (`P2`) and binary (`P5`).
+    PGM     = 33,
+
+    /// Portable Pixmap files, ASCII (`P3`) and binary (`P6`).
+    PPM     = 34,
+
+    /// Portable Arbitrary Map files (`P7`).
+    PAM     = 35,
+
+    /// Portable Float Map files, color (`PF`) and grayscale (`Pf`).
+    PFM     = 36,
+
+    /// Scalable Vector Graphics files. Only the prolog and the start tag of
+    /// the root element are read. The size given by the `width`, `height` and
+    /// `viewBox` attributes is resolved to pixels if possible, otherwise it
+    /// is reported as 0 x 0. The attribute values are reported as
+    /// [ImDetails::SVG]. Gzip compressed files (`.svgz`) are supported with
+    /// the `svgz` feature.
+    SVG     = 37,
+
+    /// Khronos Texture files, version 1. The texture layout is reported as
+    /// [ImDetails::Texture].
+    KTX     = 38,
+
+    /// Khronos Texture files, version 2.
+    KTX2    = 39,
+
+    /// PowerVR texture files, version 3.
+    PVR     = 40,
+
+    /// ASTC compressed texture files.
+    ASTC    = 41,
+
+    /// Basis Universal texture files.
+    BASIS   = 42,
+
+    /// Radiance RGBE files (`.hdr`, `.pic`). The resolution string defines
+    /// the scanline order, which is reported as the orientation.
+    HDR     = 43,
+
+    /// Apple icon files (`.icns`). Like with ICO this returns the dimensions
+    /// of the biggest icon, in pixels, as given by its element type. Embedded
+    /// PNG and JPEG 2000 images are only sized from their own headers if
+    /// requested via [ImOptions::embedded_headers]. The icons are reported
+    /// as [ImDetails::ICNS].
+    ICNS    = 44,
+
+    /// Windows animated cursors (`.ani`). The size is the one of the `anih`
+    /// header or, if that is 0, the one of the first frame. The hotspot of a
+    /// cursor frame is reported as [ImDetails::CUR] and the frame count as
+    /// the animation.
+    ANI     = 45,
 }
 
 impl ImFormat {
@@ -121,6 +232,31 @@ impl ImFormat {
             Self::DIB     => "DIB",
             Self::VTF     => "VTF",
             Self::ILBM    => "ILBM",
+            Self::CUR     => "CUR",
+            Self::DNG     => "DNG",
+            Self::CR2     => "CR2",
+            Self::NEF     => "NEF",
+            Self::ARW     => "ARW",
+            Self::ORF     => "ORF",
+            Self::RW2     => "RW2",
+            Self::PEF     => "PEF",
+            Self::RAF     => "RAF",
+            Self::CR3     => "CR3",
+            Self::JXL     => "JPEG XL",
+            Self::PBM     => "PBM",
+            Self::PGM     => "PGM",
+            Self::PPM     => "PPM",
+            Self::PAM     => "PAM",
+            Self::PFM     => "PFM",
+            Self::SVG     => "SVG",
+            Self::KTX     => "KTX",
+            Self::KTX2    => "KTX2",
+            Self::PVR     => "PVR",
+            Self::ASTC    => "ASTC",
+            Self::BASIS   => "Basis",
+            Self::HDR     => "HDR",
+            Self::ICNS    => "ICNS",
+            Self::ANI     => "ANI",
         }
     }
 }
@@ -132,396 +268,5115 @@ impl std::fmt::Display for ImFormat {
     }
 }
 
-/// The width, height and format of an image.
-#[derive(Debug, Clone)]
-pub struct ImInfo {
-    pub width:  u64,
-    pub height: u64,
-    pub format: ImFormat,
+/// The coding process of a JPEG file, as given by its start-of-frame marker.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImJpegProcess {
+    /// Baseline sequential DCT (`SOF0`).
+    Baseline,
+
+    /// Extended sequential DCT (`SOF1`, `SOF5`, `SOF9`, `SOF13`).
+    Extended,
+
+    /// Progressive DCT (`SOF2`, `SOF6`, `SOF10`, `SOF14`).
+    Progressive,
+
+    /// Lossless (`SOF3`, `SOF7`, `SOF11`, `SOF15`).
+    Lossless,
 }
 
-#[derive(Debug)]
-pub enum ImError {
-    /// If there was an IO error reading the image file this error is returend.
-    IO(std::io::Error),
+/// JPEG specific information.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImJpegInfo {
+    pub process: ImJpegProcess,
 
-    /// If the image format couldn't be detected this error is returend.
-    UnknownFormat,
+    /// `true` for arithmetic coding, `false` for Huffman coding.
+    pub arithmetic: bool,
 
-    /// If the image format was detected, but then something went wrong parsing
-    /// the file this error is returned.
-    ParserError(ImFormat),
+    /// `true` for hierarchical JPEG files, i.e. files with a `DHP` segment
+    /// or differential frames.
+    pub hierarchical: bool,
 }
 
-impl std::fmt::Display for ImError {
-    #[inline]
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::IO(error) => error.fmt(f),
-            Self::UnknownFormat => "Unknown Format".fmt(f),
-            Self::ParserError(format) => write!(f, "Error parsing {format} image")
+impl ImJpegInfo {
+    /// Returns `None` for markers that aren't start-of-frame markers.
+    #[allow(clippy::needless_return)]
+    pub const fn from_marker(marker: u8) -> Option<Self> {
+        if marker < 0xc0 || marker > 0xcf || marker == 0xc4 || marker == 0xc8 || marker == 0xcc {
+            return None;
         }
+
+        let process = match marker & 3 {
+            0 => ImJpegProcess::Baseline,
+            1 => ImJpegProcess::Extended,
+            2 => ImJpegProcess::Progressive,
+            _ => ImJpegProcess::Lossless,
+        };
+
+        return Some(ImJpegInfo {
+            process,
+            arithmetic:   marker & 8 != 0,
+            hierarchical: marker & 4 != 0,
+        });
     }
 }
 
-impl std::error::Error for ImError {
-    #[inline]
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match self {
-            Self::IO(error) => Some(error),
-            _ => None
-        }
-    }
+/// How the color of a pixel is represented.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImColorModel {
+    /// Grayscale, including bilevel images.
+    Gray,
+    RGB,
+
+    /// Palette based images. The bits per channel are the bits per index.
+    Indexed,
+    CMYK,
+    YCbCr,
+    YCCK,
+
+    /// CIE L\*a\*b\*.
+    Lab,
+
+    /// Anything else, e.g. multichannel Photoshop files.
+    Other,
 }
 
-impl From<std::io::Error> for ImError {
+/// Pixel format information as found in the file header.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImPixelFormat {
+    pub color_model: ImColorModel,
+
+    /// Number of channels including the alpha channel.
+    pub channels: u16,
+
+    /// Bits per channel. For packed formats with differently sized channels
+    /// (e.g. RGB 565) this is the size of the biggest channel.
+    pub bits_per_channel: u16,
+
+    pub has_alpha: bool,
+}
+
+impl ImPixelFormat {
     #[inline]
-    fn from(error: std::io::Error) -> Self {
-        ImError::IO(error)
+    #[allow(clippy::needless_return)]
+    pub const fn new(color_model: ImColorModel, channels: u16, bits_per_channel: u16, has_alpha: bool) -> Self {
+        return ImPixelFormat {
+            color_model,
+            channels,
+            bits_per_channel,
+            has_alpha,
+        };
     }
 }
 
-pub type ImResult<T> = std::result::Result<T, ImError>;
+/// Animation information of GIF, PNG (APNG), WebP and ANI files.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImAnimation {
+    /// Number of frames. Still images have 1 frame.
+    pub frames: u64,
 
-trait Ratio<T: Sized> {
-    fn value<R>(&self) -> R::Output
-    where R: Sized, R: std::ops::Div, R: From<T>;
+    /// How often the animation is played as stored in the file, `0` meaning
+    /// infinitely. `None` if the file doesn't specify it.
+    pub loop_count: Option<u32>,
 }
 
-impl Ratio<u32> for (u32, u32) {
+impl ImAnimation {
     #[inline]
-    fn value<R>(&self) -> R::Output
-    where R: Sized, R: std::ops::Div, R: From<u32> {
-        let (a, b) = *self;
-        let x: R = a.into();
-        let y: R = b.into();
-        x / y
+    #[allow(clippy::needless_return)]
+    pub const fn is_animated(&self) -> bool {
+        return self.frames > 1;
     }
 }
 
-impl Ratio<i32> for (i32, i32) {
-    #[inline]
-    fn value<R>(&self) -> R::Output
-    where R: Sized, R: std::ops::Div, R: From<i32> {
-        let (a, b) = *self;
-        let x: R = a.into();
-        let y: R = b.into();
-        x / y
-    }
+/// How the stored image has to be transformed for display. The values are
+/// the same as the ones of the EXIF/TIFF `Orientation` tag, the names give
+/// the position of the 0th row and the 0th column of the stored image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImOrientation {
+    /// Normal, no transformation needed.
+    TopLeft     = 1,
+
+    /// Mirrored horizontally.
+    TopRight    = 2,
+
+    /// Rotated by 180 degrees.
+    BottomRight = 3,
+
+    /// Mirrored vertically.
+    BottomLeft  = 4,
+
+    /// Mirrored horizontally and then needs rotating by 270 degrees clockwise.
+    LeftTop     = 5,
+
+    /// Needs rotating by 90 degrees clockwise.
+    RightTop    = 6,
+
+    /// Mirrored horizontally and then needs rotating by 90 degrees clockwise.
+    RightBottom = 7,
+
+    /// Needs rotating by 270 degrees clockwise.
+    LeftBottom  = 8,
 }
 
-trait BinaryReader {
-    #[inline]
-    fn read_u8(reader: &mut impl Read) -> std::io::Result<u8> {
-        let mut buf = [0u8];
-        reader.read_exact(&mut buf)?;
-        return Ok(buf[0]);
+impl ImOrientation {
+    /// Returns `None` for values outside of 1 to 8.
+    #[allow(clippy::needless_return)]
+    pub const fn from_exif(value: u16) -> Option<Self> {
+        return match value {
+            1 => Some(Self::TopLeft),
+            2 => Some(Self::TopRight),
+            3 => Some(Self::BottomRight),
+            4 => Some(Self::BottomLeft),
+            5 => Some(Self::LeftTop),
+            6 => Some(Self::RightTop),
+            7 => Some(Self::RightBottom),
+            8 => Some(Self::LeftBottom),
+            _ => None
+        };
     }
 
+    /// `true` if width and height are swapped for display.
     #[inline]
-    fn read_uchar(reader: &mut impl Read) -> std::io::Result<u8> {
-        let mut buf = [0u8];
-        reader.read_exact(&mut buf)?;
-        return Ok(buf[0]);
+    pub const fn swaps_dimensions(&self) -> bool {
+        matches!(self, Self::LeftTop | Self::RightTop | Self::RightBottom | Self::LeftBottom)
     }
 
-    #[inline]
-    fn read_i8(reader: &mut impl Read) -> std::io::Result<i8> {
-        let mut buf = [0u8];
-        reader.read_exact(&mut buf)?;
-        return Ok(buf[0] as i8);
+    /// Splits into mirroring horizontally and the clockwise rotation that
+    /// follows, in multiples of 90 degrees.
+    #[allow(clippy::needless_return)]
+    const fn parts(&self) -> (bool, u8) {
+        return match self {
+            Self::TopLeft     => (false, 0),
+            Self::TopRight    => (true,  0),
+            Self::BottomRight => (false, 2),
+            Self::BottomLeft  => (true,  2),
+            Self::LeftTop     => (true,  3),
+            Self::RightTop    => (false, 1),
+            Self::RightBottom => (true,  1),
+            Self::LeftBottom  => (false, 3),
+        };
     }
 
-    #[inline]
-    fn read_ichar(reader: &mut impl Read) -> std::io::Result<i8> {
-        let mut buf = [0u8];
-        reader.read_exact(&mut buf)?;
-        return Ok(buf[0] as i8);
+    #[allow(clippy::needless_return)]
+    const fn from_parts(mirror: bool, rotation: u8) -> Self {
+        return match (mirror, rotation & 3) {
+            (false, 0) => Self::TopLeft,
+            (true,  0) => Self::TopRight,
+            (false, 2) => Self::BottomRight,
+            (true,  2) => Self::BottomLeft,
+            (true,  3) => Self::LeftTop,
+            (false, 1) => Self::RightTop,
+            (true,  1) => Self::RightBottom,
+            _          => Self::LeftBottom,
+        };
     }
 
-    fn get_u32(buf: [u8; 4]) -> u32;
-
-    fn read_u16(reader: &mut impl Read) -> std::io::Result<u16>;
-    fn read_u32(reader: &mut impl Read) -> std::io::Result<u32>;
-    fn read_uratio(reader: &mut impl Read) -> std::io::Result<(u32, u32)>;
-
-    fn read_i16(reader: &mut impl Read) -> std::io::Result<i16>;
-    fn read_i32(reader: &mut impl Read) -> std::io::Result<i32>;
-    fn read_iratio(reader: &mut impl Read) -> std::io::Result<(i32, i32)>;
+    /// Adds a clockwise rotation by `quarters` times 90 degrees.
+    #[allow(clippy::needless_return)]
+    const fn rotate_cw(&self, quarters: u8) -> Self {
+        let (mirror, rotation) = self.parts();
+        return Self::from_parts(mirror, rotation + quarters);
+    }
 
-    fn read_f32(reader: &mut impl Read) -> std::io::Result<f32>;
-    fn read_f64(reader: &mut impl Read) -> std::io::Result<f64>;
+    /// Adds mirroring horizontally.
+    #[allow(clippy::needless_return)]
+    const fn mirror_horizontal(&self) -> Self {
+        let (mirror, rotation) = self.parts();
+        return Self::from_parts(!mirror, 4 - rotation);
+    }
 }
 
-struct LittleEndianReader;
-struct BigEndianReader;
+/// The unit of an [ImResolution].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImResolutionUnit {
+    /// No absolute unit, only the aspect ratio of the pixels is known.
+    Unknown,
+    Inch,
+    Centimeter,
+    Meter,
+}
 
-impl BinaryReader for LittleEndianReader {
+impl ImResolutionUnit {
+    /// How many of this unit make an inch.
     #[inline]
-    fn get_u32(buf: [u8; 4]) -> u32 {
-        return u32::from_le_bytes(buf);
+    #[allow(clippy::needless_return)]
+    const fn per_inch(&self) -> Option<f64> {
+        return match self {
+            Self::Unknown    => None,
+            Self::Inch       => Some(1.0),
+            Self::Centimeter => Some(2.54),
+            Self::Meter      => Some(0.0254),
+        };
     }
+}
 
-    #[inline]
-    fn read_u16(reader: &mut impl Read) -> std::io::Result<u16> {
-        let mut buf = [0u8; 2];
-        reader.read_exact(&mut buf)?;
-        return Ok(u16::from_le_bytes(buf));
-    }
+/// The physical resolution of an image in pixels (dots) per unit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImResolution {
+    pub x: f64,
+    pub y: f64,
+    pub unit: ImResolutionUnit,
+}
 
+impl ImResolution {
     #[inline]
-    fn read_u32(reader: &mut impl Read) -> std::io::Result<u32> {
-        let mut buf = [0u8; 4];
-        reader.read_exact(&mut buf)?;
-        return Ok(u32::from_le_bytes(buf));
+    #[allow(clippy::needless_return)]
+    pub const fn new(x: f64, y: f64, unit: ImResolutionUnit) -> Self {
+        return ImResolution { x, y, unit };
     }
 
-    #[inline]
-    fn read_uratio(reader: &mut impl Read) -> std::io::Result<(u32, u32)> {
-        let mut buf = [0u8; 8];
-        reader.read_exact(&mut buf)?;
-        return Ok((
-            u32::from_le_bytes([ buf[0], buf[1], buf[2], buf[3] ]),
-            u32::from_le_bytes([ buf[4], buf[5], buf[6], buf[7] ]),
-        ));
+    /// Horizontal and vertical pixels per `unit`. `None` if either unit is
+    /// [ImResolutionUnit::Unknown].
+    #[allow(clippy::needless_return)]
+    pub fn per_unit(&self, unit: ImResolutionUnit) -> Option<(f64, f64)> {
+        let factor = self.unit.per_inch()? / unit.per_inch()?;
+        return Some((self.x * factor, self.y * factor));
     }
 
+    /// Horizontal and vertical dots per inch.
     #[inline]
-    fn read_i16(reader: &mut impl Read) -> std::io::Result<i16> {
-        let mut buf = [0u8; 2];
-        reader.read_exact(&mut buf)?;
-        return Ok(i16::from_le_bytes(buf));
+    #[allow(clippy::needless_return)]
+    pub fn dpi(&self) -> Option<(f64, f64)> {
+        return self.per_unit(ImResolutionUnit::Inch);
     }
+}
+
+/// What an image in a multi-image file is used for.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImRole {
+    /// The main image of the file.
+    Primary,
+
+    /// A page of a multi-page file.
+    Page,
+
+    /// A reduced resolution version of another image.
+    Thumbnail,
+
+    /// One of the sizes of an icon.
+    Icon,
+
+    /// A mipmap level of a texture. Level 0 is the full resolution image.
+    /// `layer` counts array layers and cube map faces.
+    MipLevel { layer: u32, level: u32 },
+
+    /// A tile of a bigger image.
+    Tile,
+
+    /// Alpha planes, depth maps and the like.
+    Auxiliary,
+}
+
+/// An image in a multi-image file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImImage {
+    pub index:  usize,
+    pub width:  u64,
+    pub height: u64,
+
+    /// The format of the image data. This might differ from the format of
+    /// the whole file, e.g. ICO files may contain PNG images.
+    pub format: ImFormat,
+    pub role:   ImRole,
+}
 
+/// The hotspot of a cursor, relative to the top left corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImHotspot {
+    pub x: u16,
+    pub y: u16,
+}
+
+/// Information about a camera RAW file. The size of the raw sensor data is
+/// the width and height of the [ImInfo] itself.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImRawInfo {
+    /// Width and height of the biggest embedded preview image, if there is
+    /// any.
+    pub preview_size: Option<(u64, u64)>,
+}
+
+/// The unit of an SVG length.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImSvgUnit {
+    /// A plain number, i.e. user units.
+    None,
+    Px,
+    Pt,
+    Pc,
+    Mm,
+    Cm,
+    In,
+    Em,
+    Ex,
+    Percent,
+}
+
+/// A length as given in an attribute of an SVG file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImSvgLength {
+    pub value: f64,
+    pub unit:  ImSvgUnit,
+}
+
+impl ImSvgLength {
     #[inline]
-    fn read_i32(reader: &mut impl Read) -> std::io::Result<i32> {
-        let mut buf = [0u8; 4];
-        reader.read_exact(&mut buf)?;
-        return Ok(i32::from_le_bytes(buf));
+    #[allow(clippy::needless_return)]
+    pub fn new(value: f64, unit: ImSvgUnit) -> Self {
+        return Self { value, unit };
     }
 
-    #[inline]
-    fn read_iratio(reader: &mut impl Read) -> std::io::Result<(i32, i32)> {
-        let mut buf = [0u8; 8];
-        reader.read_exact(&mut buf)?;
-        return Ok((
-            i32::from_le_bytes([ buf[0], buf[1], buf[2], buf[3] ]),
-            i32::from_le_bytes([ buf[4], buf[5], buf[6], buf[7] ]),
-        ));
+    /// The length in CSS pixels (96 per inch). `None` for relative units,
+    /// since they depend on the context.
+    #[allow(clippy::needless_return)]
+    pub fn to_pixels(&self) -> Option<f64> {
+        let factor = match self.unit {
+            ImSvgUnit::None | ImSvgUnit::Px => 1.0,
+            ImSvgUnit::Pt => 96.0 / 72.0,
+            ImSvgUnit::Pc => 16.0,
+            ImSvgUnit::Mm => 96.0 / 25.4,
+            ImSvgUnit::Cm => 96.0 / 2.54,
+            ImSvgUnit::In => 96.0,
+            ImSvgUnit::Em | ImSvgUnit::Ex | ImSvgUnit::Percent => return None,
+        };
+        return Some(self.value * factor);
     }
+}
 
-    #[inline]
-    fn read_f32(reader: &mut impl Read) -> std::io::Result<f32> {
-        let mut buf = [0u8; 4];
-        reader.read_exact(&mut buf)?;
-        return Ok(f32::from_le_bytes(buf));
+/// The `viewBox` attribute of an SVG file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImSvgViewBox {
+    pub x: f64,
+    pub y: f64,
+    pub width:  f64,
+    pub height: f64,
+}
+
+/// The size attributes of the root element of an SVG file. Missing or
+/// invalid attributes are `None`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImSvgInfo {
+    pub width:  Option<ImSvgLength>,
+    pub height: Option<ImSvgLength>,
+    pub view_box: Option<ImSvgViewBox>,
+}
+
+impl ImSvgInfo {
+    /// The size in pixels. A missing or relative width or height is derived
+    /// from the other one and the aspect ratio of the `viewBox`, if both are
+    /// missing the size of the `viewBox` is used.
+    #[allow(clippy::needless_return)]
+    pub fn pixel_size(&self) -> Option<(f64, f64)> {
+        let view_box = self.view_box.filter(|view_box| view_box.width > 0.0 && view_box.height > 0.0);
+        let width  = self.width.and_then(|width| width.to_pixels());
+        let height = self.height.and_then(|height| height.to_pixels());
+        return match (width, height, view_box) {
+            (Some(width), Some(height), _) => Some((width, height)),
+            (Some(width), None, Some(view_box)) => Some((width, width * view_box.height / view_box.width)),
+            (None, Some(height), Some(view_box)) => Some((height * view_box.width / view_box.height, height)),
+            (None, None, Some(view_box)) => Some((view_box.width, view_box.height)),
+            _ => None,
+        };
     }
+}
+
+/// The layout of a GPU texture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImTextureInfo {
+    /// Depth of volume textures, 1 otherwise.
+    pub depth: u64,
+
+    /// Number of array layers, 1 if the texture is no array.
+    pub layers: u32,
+
+    /// Number of faces, 6 for cube maps, 1 otherwise.
+    pub faces: u32,
+
+    /// Number of mipmap levels, including the base level.
+    pub mip_levels: u32,
+}
 
+impl ImTextureInfo {
     #[inline]
-    fn read_f64(reader: &mut impl Read) -> std::io::Result<f64> {
-        let mut buf = [0u8; 8];
-        reader.read_exact(&mut buf)?;
-        return Ok(f64::from_le_bytes(buf));
+    #[allow(clippy::needless_return)]
+    pub fn new(depth: u64, layers: u32, faces: u32, mip_levels: u32) -> Self {
+        return Self { depth, layers, faces, mip_levels };
     }
 }
 
-impl BinaryReader for BigEndianReader {
+/// A pixel rectangle of an OpenEXR file. Both corners are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImExrBox {
+    pub x_min: i32,
+    pub y_min: i32,
+    pub x_max: i32,
+    pub y_max: i32,
+}
+
+impl ImExrBox {
     #[inline]
-    fn get_u32(buf: [u8; 4]) -> u32 {
-        return u32::from_be_bytes(buf);
+    #[allow(clippy::needless_return)]
+    pub fn new(x_min: i32, y_min: i32, x_max: i32, y_max: i32) -> Self {
+        return Self { x_min, y_min, x_max, y_max };
     }
 
     #[inline]
-    fn read_u16(reader: &mut impl Read) -> std::io::Result<u16> {
-        let mut buf = [0u8; 2];
-        reader.read_exact(&mut buf)?;
-        return Ok(u16::from_be_bytes(buf));
+    #[allow(clippy::needless_return)]
+    pub fn width(&self) -> u64 {
+        return (self.x_max as i64 - self.x_min as i64 + 1).max(0) as u64;
     }
 
     #[inline]
-    fn read_u32(reader: &mut impl Read) -> std::io::Result<u32> {
-        let mut buf = [0u8; 4];
-        reader.read_exact(&mut buf)?;
-        return Ok(u32::from_be_bytes(buf));
+    #[allow(clippy::needless_return)]
+    pub fn height(&self) -> u64 {
+        return (self.y_max as i64 - self.y_min as i64 + 1).max(0) as u64;
     }
+}
 
+/// An ancillary chunk of a PNG file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImPngChunk {
+    /// The chunk type, e.g. `iCCP` or `tEXt`.
+    pub chunk_type: [u8; 4],
+
+    /// The size of the chunk data.
+    pub size: u32,
+}
+
+/// Information about a PNG file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImPngInfo {
+    /// Adam7 interlacing (interlace method 1).
+    pub interlaced: bool,
+
+    /// The file is an Apple optimized iOS PNG, starting with a `CgBI` chunk.
+    /// The image data of such files can't be read by standard decoders.
+    pub cgbi: bool,
+
+    /// The ancillary chunks in file order. Only filled in if requested via
+    /// [ImOptions::chunks].
+    pub chunks: Vec<ImPngChunk>,
+}
+
+/// A frame of an animated WebP file (`ANMF` chunk).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImWebpFrame {
+    /// Position of the frame on the canvas.
+    pub x: u32,
+    pub y: u32,
+
+    pub width:  u32,
+    pub height: u32,
+}
+
+/// Information about a WebP file. The size of the [ImInfo] itself is the
+/// canvas size for files with a `VP8X` chunk.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImWebpInfo {
+    /// The image has transparent pixels. For `VP8L` images this is only a
+    /// hint of the encoder.
+    pub alpha: bool,
+
+    /// The file has an ICC profile.
+    pub icc: bool,
+
+    /// The file has EXIF metadata.
+    pub exif: bool,
+
+    /// The file has XMP metadata.
+    pub xmp: bool,
+
+    /// The file is an animation.
+    pub animation: bool,
+
+    /// Upscaling of a `VP8 ` image for display as stored in the frame header:
+    /// 0 means none, 1 means 5/4, 2 means 5/3 and 3 means 2.
+    pub horizontal_scale: u8,
+    pub vertical_scale:   u8,
+
+    /// The frames of an animation. Only filled in if requested via
+    /// [ImOptions::animation].
+    pub frames: Vec<ImWebpFrame>,
+}
+
+/// An icon of an Apple icon (ICNS) file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImIcnsIcon {
+    /// The element type, e.g. `ic07` or `is32`.
+    pub element_type: [u8; 4],
+
+    /// The size in pixels, not in points.
+    pub width:  u64,
+    pub height: u64,
+
+    /// 2 for retina (`@2x`) icons, otherwise 1.
+    pub scale: u8,
+
+    /// [ImFormat::ICNS] for the classic image data, [ImFormat::PNG] or
+    /// [ImFormat::JP2K] for embedded images if their headers were read via
+    /// [ImOptions::embedded_headers].
+    pub format: ImFormat,
+}
+
+/// Information about an Apple icon file. Mask elements and elements that
+/// aren't icons (like the table of contents) aren't listed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImIcnsInfo {
+    pub icons: Vec<ImIcnsIcon>,
+}
+
+/// The `type` attribute of an OpenEXR part.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImExrPartType {
+    Scanline,
+    Tiled,
+    DeepScanline,
+    DeepTiled,
+}
+
+/// The header of one part of an OpenEXR file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImExrPart {
+    /// The `name` attribute, only required for multi-part files.
+    pub name: Option<String>,
+
+    /// The `type` attribute or, for single-part files without it, the type
+    /// implied by the version field. `None` for unknown types.
+    pub part_type: Option<ImExrPartType>,
+
+    /// The area of the image that is meant to be shown.
+    pub display_window: ImExrBox,
+
+    /// The area of the image that actually contains pixel data. This might be
+    /// smaller or bigger than the display window.
+    pub data_window: ImExrBox,
+}
+
+/// Information about an OpenEXR file. The size of the [ImInfo] itself is the
+/// display window of the first part.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImExrInfo {
+    /// Single-part file with tiles instead of scanlines.
+    pub tiled: bool,
+
+    /// The file contains deep data (the "non-image" flag).
+    pub deep: bool,
+
+    /// The file contains more than one part.
+    pub multipart: bool,
+
+    pub parts: Vec<ImExrPart>,
+}
+
+/// The color mode of a Photoshop file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u16)]
+pub enum ImPsdColorMode {
+    Bitmap       = 0,
+    Grayscale    = 1,
+    Indexed      = 2,
+    RGB          = 3,
+    CMYK         = 4,
+    Multichannel = 7,
+    Duotone      = 8,
+    Lab          = 9,
+}
+
+impl ImPsdColorMode {
+    /// Returns `None` for unknown color modes.
+    #[allow(clippy::needless_return)]
+    pub const fn from_u16(value: u16) -> Option<Self> {
+        return match value {
+            0 => Some(Self::Bitmap),
+            1 => Some(Self::Grayscale),
+            2 => Some(Self::Indexed),
+            3 => Some(Self::RGB),
+            4 => Some(Self::CMYK),
+            7 => Some(Self::Multichannel),
+            8 => Some(Self::Duotone),
+            9 => Some(Self::Lab),
+            _ => None,
+        };
+    }
+}
+
+/// The file header of a Photoshop file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImPsdInfo {
+    /// 1 for PSD, 2 for Large Document Format (PSB) files.
+    pub version: u16,
+
+    /// Number of channels, including alpha channels.
+    pub channels: u16,
+
+    /// Bits per channel: 1, 8, 16 or 32.
+    pub depth: u16,
+
+    /// `None` for unknown color modes.
+    pub color_mode: Option<ImPsdColorMode>,
+}
+
+/// Format specific information that doesn't fit anywhere else.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImDetails {
+    None,
+    JPEG(ImJpegInfo),
+    CUR(ImHotspot),
+    RAW(ImRawInfo),
+    SVG(ImSvgInfo),
+    Texture(ImTextureInfo),
+    OpenEXR(ImExrInfo),
+    PSD(ImPsdInfo),
+    WebP(ImWebpInfo),
+    PNG(ImPngInfo),
+    ICNS(ImIcnsInfo),
+}
+
+/// The width, height and format of an image.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ImInfo {
+    pub width:  u64,
+    pub height: u64,
+    pub format: ImFormat,
+
+    /// Bit depth, channel count and color model, if the file header has
+    /// that information. For some formats this is only filled in if
+    /// requested via [ImOptions::pixel_format].
+    pub pixel_format: Option<ImPixelFormat>,
+
+    /// Frame and loop count. Only filled in if requested via
+    /// [ImOptions::animation].
+    pub animation: Option<ImAnimation>,
+
+    /// How the image has to be rotated or mirrored for display. `width` and
+    /// `height` are always the stored dimensions, see [ImInfo::display_size].
+    /// Only filled in if requested via [ImOptions::orientation].
+    pub orientation: Option<ImOrientation>,
+
+    /// Physical resolution, see [ImInfo::physical_size]. Only filled in if
+    /// requested via [ImOptions::resolution].
+    pub resolution: Option<ImResolution>,
+
+    /// All images in the file, including the one described by this struct.
+    /// Only filled in if requested via [ImOptions::images]. The list is
+    /// truncated for files that claim an absurd number of images, e.g. the
+    /// mipmap levels of texture arrays are limited to 4096 entries.
+    pub images: Vec<ImImage>,
+
+    /// Format specific information.
+    pub details: ImDetails,
+}
+
+impl ImInfo {
+    #[inline]
+    #[allow(clippy::needless_return)]
+    pub fn new(format: ImFormat, width: u64, height: u64) -> Self {
+        return ImInfo {
+            width,
+            height,
+            format,
+            pixel_format: None,
+            animation: None,
+            orientation: None,
+            resolution: None,
+            images: Vec::new(),
+            details: ImDetails::None,
+        };
+    }
+
+    /// Width and height of the image as displayed, i.e. with the orientation
+    /// applied.
+    #[inline]
+    #[allow(clippy::needless_return)]
+    pub fn display_size(&self) -> (u64, u64) {
+        return match self.orientation {
+            Some(orientation) if orientation.swaps_dimensions() => (self.height, self.width),
+            _ => (self.width, self.height),
+        };
+    }
+
+    /// Physical width and height of the stored image in `unit`. `None` if
+    /// the resolution isn't known or has no absolute unit.
+    #[allow(clippy::needless_return)]
+    pub fn physical_size(&self, unit: ImResolutionUnit) -> Option<(f64, f64)> {
+        let (x, y) = self.resolution?.per_unit(unit)?;
+        if x <= 0.0 || y <= 0.0 {
+            return None;
+        }
+        return Some((self.width as f64 / x, self.height as f64 / y));
+    }
+}
+
+/// Options to request information that might need reading more than just the
+/// file header. Everything is turned off by default.
+///
+/// More options might be added in the future, so use the builder methods
+/// to set them:
+///
+/// ```
+/// let options = imsz::ImOptions::new().animation(true).orientation(true);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[non_exhaustive]
+pub struct ImOptions {
+    /// Detect animated GIF, PNG (APNG), WebP and ANI files and count their
+    /// frames. For GIF and WebP files this means walking through the whole
+    /// file.
+    pub animation: bool,
+
+    /// List all images in multi-image files like ICO, TIFF, DDS and HEIF,
+    /// including the MPO images and the thumbnails of JPEG files.
+    /// See [ImInfo::images].
+    pub images: bool,
+
+    /// Read the headers of the images embedded in ICO, CUR and ICNS files
+    /// instead of only trusting the directory entries or element types. This
+    /// needs one seek per image.
+    pub embedded_headers: bool,
+
+    /// Read the orientation of JPEG (EXIF), TIFF, HEIF/AVIF (`irot`/`imir`),
+    /// WebP (EXIF), JPEG XL and Radiance HDR files. See [ImInfo::orientation].
+    /// For WebP files this means walking through the chunks of the file.
+    pub orientation: bool,
+
+    /// Read the physical resolution of PNG (`pHYs`), JPEG (JFIF, EXIF), TIFF,
+    /// BMP, DIB, PSD, PCX and JPEG 2000 (`res `) files. See
+    /// [ImInfo::resolution]. This might mean reading beyond the first header.
+    pub resolution: bool,
+
+    /// Validate checksums of the file header. Currently this is the CRC of
+    /// the `IHDR` chunk of PNG files.
+    pub strict: bool,
+
+    /// List the ancillary chunks of PNG files. See [ImPngInfo::chunks]. This
+    /// means walking through the whole file.
+    pub chunks: bool,
+
+    /// Read the pixel format of BMP, PCX and VTF files, where it isn't part
+    /// of the first few bytes of the file. This needs an extra seek.
+    /// See [ImInfo::pixel_format].
+    pub pixel_format: bool,
+}
+
+impl ImOptions {
+    /// Options with everything turned off, same as [ImOptions::default].
+    #[inline]
+    #[allow(clippy::needless_return)]
+    pub const fn new() -> Self {
+        return ImOptions {
+            animation: false,
+            images: false,
+            embedded_headers: false,
+            orientation: false,
+            resolution: false,
+            strict: false,
+            chunks: false,
+            pixel_format: false,
+        };
+    }
+
+    /// Sets [ImOptions::animation].
+    #[inline]
+    #[allow(clippy::needless_return)]
+    pub const fn animation(mut self, value: bool) -> Self {
+        self.animation = value;
+        return self;
+    }
+
+    /// Sets [ImOptions::images].
+    #[inline]
+    #[allow(clippy::needless_return)]
+    pub const fn images(mut self, value: bool) -> Self {
+        self.images = value;
+        return self;
+    }
+
+    /// Sets [ImOptions::embedded_headers].
+    #[inline]
+    #[allow(clippy::needless_return)]
+    pub const fn embedded_headers(mut self, value: bool) -> Self {
+        self.embedded_headers = value;
+        return self;
+    }
+
+    /// Sets [ImOptions::orientation].
+    #[inline]
+    #[allow(clippy::needless_return)]
+    pub const fn orientation(mut self, value: bool) -> Self {
+        self.orientation = value;
+        return self;
+    }
+
+    /// Sets [ImOptions::resolution].
+    #[inline]
+    #[allow(clippy::needless_return)]
+    pub const fn resolution(mut self, value: bool) -> Self {
+        self.resolution = value;
+        return self;
+    }
+
+    /// Sets [ImOptions::strict].
+    #[inline]
+    #[allow(clippy::needless_return)]
+    pub const fn strict(mut self, value: bool) -> Self {
+        self.strict = value;
+        return self;
+    }
+
+    /// Sets [ImOptions::chunks].
+    #[inline]
+    #[allow(clippy::needless_return)]
+    pub const fn chunks(mut self, value: bool) -> Self {
+        self.chunks = value;
+        return self;
+    }
+
+    /// Sets [ImOptions::pixel_format].
+    #[inline]
+    #[allow(clippy::needless_return)]
+    pub const fn pixel_format(mut self, value: bool) -> Self {
+        self.pixel_format = value;
+        return self;
+    }
+}
+
+#[derive(Debug)]
+pub enum ImError {
+    /// If there was an IO error reading the image file this error is returend.
+    IO(std::io::Error),
+
+    /// If the image format couldn't be detected this error is returend.
+    UnknownFormat,
+
+    /// If the image format was detected, but then something went wrong parsing
+    /// the file this error is returned.
+    ParserError(ImFormat),
+}
+
+impl std::fmt::Display for ImError {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IO(error) => error.fmt(f),
+            Self::UnknownFormat => "Unknown Format".fmt(f),
+            Self::ParserError(format) => write!(f, "Error parsing {format} image")
+        }
+    }
+}
+
+impl std::error::Error for ImError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IO(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for ImError {
+    #[inline]
+    fn from(error: std::io::Error) -> Self {
+        ImError::IO(error)
+    }
+}
+
+pub type ImResult<T> = std::result::Result<T, ImError>;
+
+trait Ratio<T: Sized> {
+    fn value<R>(&self) -> R::Output
+    where R: Sized, R: std::ops::Div, R: From<T>;
+}
+
+impl Ratio<u32> for (u32, u32) {
+    #[inline]
+    fn value<R>(&self) -> R::Output
+    where R: Sized, R: std::ops::Div, R: From<u32> {
+        let (a, b) = *self;
+        let x: R = a.into();
+        let y: R = b.into();
+        x / y
+    }
+}
+
+impl Ratio<i32> for (i32, i32) {
+    #[inline]
+    fn value<R>(&self) -> R::Output
+    where R: Sized, R: std::ops::Div, R: From<i32> {
+        let (a, b) = *self;
+        let x: R = a.into();
+        let y: R = b.into();
+        x / y
+    }
+}
+
+trait BinaryReader {
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_u8(reader: &mut impl Read) -> std::io::Result<u8> {
+        let mut buf = [0u8];
+        reader.read_exact(&mut buf)?;
+        return Ok(buf[0]);
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_uchar(reader: &mut impl Read) -> std::io::Result<u8> {
+        let mut buf = [0u8];
+        reader.read_exact(&mut buf)?;
+        return Ok(buf[0]);
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_i8(reader: &mut impl Read) -> std::io::Result<i8> {
+        let mut buf = [0u8];
+        reader.read_exact(&mut buf)?;
+        return Ok(buf[0] as i8);
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_ichar(reader: &mut impl Read) -> std::io::Result<i8> {
+        let mut buf = [0u8];
+        reader.read_exact(&mut buf)?;
+        return Ok(buf[0] as i8);
+    }
+
+    fn get_u16(buf: [u8; 2]) -> u16;
+    fn get_u32(buf: [u8; 4]) -> u32;
+    fn get_u64(buf: [u8; 8]) -> u64;
+
+    fn read_u16(reader: &mut impl Read) -> std::io::Result<u16>;
+    fn read_u32(reader: &mut impl Read) -> std::io::Result<u32>;
+    fn read_u64(reader: &mut impl Read) -> std::io::Result<u64>;
+    fn read_uratio(reader: &mut impl Read) -> std::io::Result<(u32, u32)>;
+
+    fn read_i16(reader: &mut impl Read) -> std::io::Result<i16>;
+    fn read_i32(reader: &mut impl Read) -> std::io::Result<i32>;
+    fn read_i64(reader: &mut impl Read) -> std::io::Result<i64>;
+    fn read_iratio(reader: &mut impl Read) -> std::io::Result<(i32, i32)>;
+
+    fn read_f32(reader: &mut impl Read) -> std::io::Result<f32>;
+    fn read_f64(reader: &mut impl Read) -> std::io::Result<f64>;
+}
+
+struct LittleEndianReader;
+struct BigEndianReader;
+
+impl BinaryReader for LittleEndianReader {
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn get_u16(buf: [u8; 2]) -> u16 {
+        return u16::from_le_bytes(buf);
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn get_u32(buf: [u8; 4]) -> u32 {
+        return u32::from_le_bytes(buf);
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn get_u64(buf: [u8; 8]) -> u64 {
+        return u64::from_le_bytes(buf);
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_u16(reader: &mut impl Read) -> std::io::Result<u16> {
+        let mut buf = [0u8; 2];
+        reader.read_exact(&mut buf)?;
+        return Ok(u16::from_le_bytes(buf));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_u32(reader: &mut impl Read) -> std::io::Result<u32> {
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf)?;
+        return Ok(u32::from_le_bytes(buf));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_u64(reader: &mut impl Read) -> std::io::Result<u64> {
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+        return Ok(u64::from_le_bytes(buf));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_uratio(reader: &mut impl Read) -> std::io::Result<(u32, u32)> {
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+        return Ok((
+            u32::from_le_bytes([ buf[0], buf[1], buf[2], buf[3] ]),
+            u32::from_le_bytes([ buf[4], buf[5], buf[6], buf[7] ]),
+        ));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_i16(reader: &mut impl Read) -> std::io::Result<i16> {
+        let mut buf = [0u8; 2];
+        reader.read_exact(&mut buf)?;
+        return Ok(i16::from_le_bytes(buf));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_i32(reader: &mut impl Read) -> std::io::Result<i32> {
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf)?;
+        return Ok(i32::from_le_bytes(buf));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_i64(reader: &mut impl Read) -> std::io::Result<i64> {
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+        return Ok(i64::from_le_bytes(buf));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_iratio(reader: &mut impl Read) -> std::io::Result<(i32, i32)> {
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+        return Ok((
+            i32::from_le_bytes([ buf[0], buf[1], buf[2], buf[3] ]),
+            i32::from_le_bytes([ buf[4], buf[5], buf[6], buf[7] ]),
+        ));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_f32(reader: &mut impl Read) -> std::io::Result<f32> {
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf)?;
+        return Ok(f32::from_le_bytes(buf));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_f64(reader: &mut impl Read) -> std::io::Result<f64> {
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+        return Ok(f64::from_le_bytes(buf));
+    }
+}
+
+impl BinaryReader for BigEndianReader {
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn get_u16(buf: [u8; 2]) -> u16 {
+        return u16::from_be_bytes(buf);
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn get_u32(buf: [u8; 4]) -> u32 {
+        return u32::from_be_bytes(buf);
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn get_u64(buf: [u8; 8]) -> u64 {
+        return u64::from_be_bytes(buf);
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_u16(reader: &mut impl Read) -> std::io::Result<u16> {
+        let mut buf = [0u8; 2];
+        reader.read_exact(&mut buf)?;
+        return Ok(u16::from_be_bytes(buf));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_u32(reader: &mut impl Read) -> std::io::Result<u32> {
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf)?;
+        return Ok(u32::from_be_bytes(buf));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_u64(reader: &mut impl Read) -> std::io::Result<u64> {
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+        return Ok(u64::from_be_bytes(buf));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_uratio(reader: &mut impl Read) -> std::io::Result<(u32, u32)> {
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+        return Ok((
+            u32::from_be_bytes([ buf[0], buf[1], buf[2], buf[3] ]),
+            u32::from_be_bytes([ buf[4], buf[5], buf[6], buf[7] ]),
+        ));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_i16(reader: &mut impl Read) -> std::io::Result<i16> {
+        let mut buf = [0u8; 2];
+        reader.read_exact(&mut buf)?;
+        return Ok(i16::from_be_bytes(buf));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_i32(reader: &mut impl Read) -> std::io::Result<i32> {
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf)?;
+        return Ok(i32::from_be_bytes(buf));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_i64(reader: &mut impl Read) -> std::io::Result<i64> {
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+        return Ok(i64::from_be_bytes(buf));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_iratio(reader: &mut impl Read) -> std::io::Result<(i32, i32)> {
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+        return Ok((
+            i32::from_be_bytes([ buf[0], buf[1], buf[2], buf[3] ]),
+            i32::from_be_bytes([ buf[4], buf[5], buf[6], buf[7] ]),
+        ));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_f32(reader: &mut impl Read) -> std::io::Result<f32> {
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf)?;
+        return Ok(f32::from_be_bytes(buf));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read_f64(reader: &mut impl Read) -> std::io::Result<f64> {
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+        return Ok(f64::from_be_bytes(buf));
+    }
+}
+
+macro_rules! array2 {
+    ($data:expr, $offset:expr) => {
+        [ $data[$offset], $data[$offset + 1] ]
+    };
+}
+
+macro_rules! array4 {
+    ($data:expr, $offset:expr) => {
+        [ $data[$offset], $data[$offset + 1], $data[$offset + 2], $data[$offset + 3] ]
+    };
+}
+
+macro_rules! array8 {
+    ($data:expr, $offset:expr) => {
+        [
+            $data[$offset],     $data[$offset + 1], $data[$offset + 2], $data[$offset + 3],
+            $data[$offset + 4], $data[$offset + 5], $data[$offset + 6], $data[$offset + 7],
+        ]
+    };
+}
+
+macro_rules! map_err {
+    ($fmt:expr, $expr:expr) => {
+        if let Err(_) = $expr {
+            return Err(ImError::ParserError($fmt));
+        }
+    };
+
+    ($fmt:ident $expr:expr) => {
+        map_err!(ImFormat::$fmt, $expr);
+    };
+}
+
+macro_rules! map_expr {
+    ($fmt:expr, $expr:expr) => {
+        match $expr {
+            Err(_) => return Err(ImError::ParserError($fmt)),
+            Ok(value) => value
+        }
+    };
+
+    ($fmt:ident $expr:expr) => {
+        map_expr!(ImFormat::$fmt, $expr)
+    };
+}
+
+/// Skips ISO-BMFF boxes (big endian sizes) from the current position of the
+/// reader until a box of the given type, searching at most `chunk_size`
+/// bytes. Leaves the reader at the payload and returns the size of the box,
+/// including its header.
+#[allow(clippy::needless_return, clippy::redundant_pattern_matching)]
+fn skip_to_iso_box<R>(reader: &mut R, name: &[u8; 4], chunk_size: u64, format: ImFormat) -> ImResult<u64>
+where R: Read, R: Seek {
+    let mut sub_chunk_size;
+    let mut buf = [0u8; 8];
+    let mut offset = 0;
+
+    loop {
+        if offset > chunk_size {
+            return Err(ImError::ParserError(format));
+        }
+        if let Err(_) = reader.read_exact(&mut buf) {
+            return Err(ImError::ParserError(format));
+        }
+        sub_chunk_size = u32::from_be_bytes(array4!(&buf, 0)) as u64;
+        if sub_chunk_size < 8 {
+            return Err(ImError::ParserError(format));
+        }
+        if &buf[4..8] == name {
+            break;
+        }
+        offset += sub_chunk_size;
+        if let Err(_) = reader.seek(SeekFrom::Current(sub_chunk_size as i64 - 8)) {
+            return Err(ImError::ParserError(format));
+        }
+    }
+
+    return Ok(sub_chunk_size);
+}
+
+/// Seeks to the values of an IFD entry. `value_offset` is the file offset
+/// of the value/offset field of the entry, which is 8 bytes big in BigTIFF
+/// files and 4 bytes otherwise.
+#[allow(clippy::needless_return)]
+fn seek_tiff_values<BR, R>(reader: &mut R, ftype: u16, count: u64, value_offset: u64, bigtiff: bool) -> ImResult<()>
+where BR: BinaryReader, R: Read, R: Seek {
+    let type_size: u64 = match ftype {
+        1 | 2 | 6 | 7 => 1,
+        3 | 8 => 2,
+        4 | 9 | 11 | 13 => 4,
+        5 | 10 | 12 | 16 | 17 | 18 => 8,
+        _ => return Err(ImError::ParserError(ImFormat::TIFF))
+    };
+
+    map_err!(TIFF reader.seek(SeekFrom::Start(value_offset)));
+    // if type and count indicate that the values fit into the field, value
+    // offset is not an offset but the value itself
+    let field_size = if bigtiff { 8 } else { 4 };
+    if type_size.saturating_mul(count) > field_size {
+        let offset = if bigtiff {
+            map_expr!(TIFF BR::read_u64(reader))
+        } else {
+            map_expr!(TIFF BR::read_u32(reader)) as u64
+        };
+        map_err!(TIFF reader.seek(SeekFrom::Start(offset)));
+    }
+
+    return Ok(());
+}
+
+/// Reads the first value of an IFD entry. `value_offset` is the file offset
+/// of the value/offset field of the entry.
+#[allow(clippy::needless_return)]
+fn read_tiff_value<BR, R>(reader: &mut R, ftype: u16, count: u64, value_offset: u64, bigtiff: bool) -> ImResult<u64>
+where BR: BinaryReader, R: Read, R: Seek {
+    seek_tiff_values::<BR, R>(reader, ftype, count, value_offset, bigtiff)?;
+
+    let value: u64 = match ftype {
+         1 => map_expr!(TIFF BR::read_u8(reader)).into(),
+         2 => map_expr!(TIFF BR::read_uchar(reader)).into(),
+         3 => map_expr!(TIFF BR::read_u16(reader)).into(),
+         4 => map_expr!(TIFF BR::read_u32(reader)).into(),
+         5 => map_expr!(TIFF BR::read_uratio(reader)).value::<u64>(),
+         6 => map_expr!(TIFF BR::read_i8(reader)).max(0) as u64,
+         7 => map_expr!(TIFF BR::read_ichar(reader)).max(0) as u64,
+         8 => map_expr!(TIFF BR::read_i16(reader)).max(0) as u64,
+         9 => map_expr!(TIFF BR::read_i32(reader)).max(0) as u64,
+        10 => map_expr!(TIFF BR::read_iratio(reader)).value::<i64>().max(0) as u64,
+        11 => map_expr!(TIFF BR::read_f32(reader)) as u64,
+        12 => map_expr!(TIFF BR::read_f64(reader)) as u64,
+        // IFD
+        13 => map_expr!(TIFF BR::read_u32(reader)).into(),
+        // LONG8, SLONG8, IFD8
+        16 | 18 => map_expr!(TIFF BR::read_u64(reader)),
+        17 => map_expr!(TIFF BR::read_i64(reader)).max(0) as u64,
+        _ => return Err(ImError::ParserError(ImFormat::TIFF))
+    };
+
+    return Ok(value);
+}
+
+/// Reads all values of an IFD entry that holds offsets, e.g. SubIFDs.
+#[allow(clippy::needless_return)]
+fn read_tiff_offsets<BR, R>(reader: &mut R, ftype: u16, count: u64, value_offset: u64, bigtiff: bool) -> ImResult<Vec<u64>>
+where BR: BinaryReader, R: Read, R: Seek {
+    if count > TIFF_MAX_IFDS as u64 {
+        return Err(ImError::ParserError(ImFormat::TIFF));
+    }
+    seek_tiff_values::<BR, R>(reader, ftype, count, value_offset, bigtiff)?;
+
+    let mut offsets = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let offset = match ftype {
+            // LONG, IFD
+            4 | 13 => map_expr!(TIFF BR::read_u32(reader)) as u64,
+            // LONG8, IFD8
+            16 | 18 => map_expr!(TIFF BR::read_u64(reader)),
+            _ => return Err(ImError::ParserError(ImFormat::TIFF))
+        };
+        offsets.push(offset);
+    }
+
+    return Ok(offsets);
+}
+
+/// Like [read_tiff_value()], but keeps the fraction of rational values.
+#[allow(clippy::needless_return)]
+fn read_tiff_float<BR, R>(reader: &mut R, ftype: u16, count: u64, value_offset: u64, bigtiff: bool) -> ImResult<f64>
+where BR: BinaryReader, R: Read, R: Seek {
+    if ftype != 5 {
+        return Ok(read_tiff_value::<BR, R>(reader, ftype, count, value_offset, bigtiff)? as f64);
+    }
+
+    seek_tiff_values::<BR, R>(reader, ftype, count, value_offset, bigtiff)?;
+    let (numerator, denominator) = map_expr!(TIFF BR::read_uratio(reader));
+    if denominator == 0 {
+        return Ok(0.0);
+    }
+
+    return Ok((numerator, denominator).value::<f64>());
+}
+
+/// The tags of an IFD that are of interest.
+struct TiffIfd {
+    width:  Option<u64>,
+    height: Option<u64>,
+    bits_per_sample:   u64,
+    samples_per_pixel: u64,
+    photometric:  Option<u64>,
+    extra_sample: Option<u64>,
+    orientation:  Option<u64>,
+    x_resolution: Option<f64>,
+    y_resolution: Option<f64>,
+    resolution_unit: Option<u64>,
+    /// NewSubfileType, bit 0: reduced resolution, bit 1: page, bit 2: mask
+    subfile_type: u64,
+    sub_ifd_offsets: Vec<u64>,
+    next_ifd_offset: u64,
+    compression: Option<u64>,
+    /// Make, only the first few bytes
+    make: Vec<u8>,
+    /// DNGVersion is present
+    dng: bool,
+    /// Canon CR2 slices are present, i.e. this is the raw data of a CR2 file
+    cr2_slices: bool,
+    /// offset of the first strip
+    strip_offset: Option<u64>,
+    /// JPEGInterchangeFormat, the offset of an embedded JPEG image
+    jpeg_offset: Option<u64>,
+    /// Panasonic RW2: SensorWidth and SensorHeight
+    sensor_width:  Option<u64>,
+    sensor_height: Option<u64>,
+    /// Panasonic RW2: the offset of the embedded JpgFromRaw image
+    jpeg_from_raw_offset: Option<u64>,
+}
+
+impl TiffIfd {
+    #[allow(clippy::needless_return)]
+    fn pixel_format(&self) -> Option<ImPixelFormat> {
+        let color_model = match self.photometric? {
+            0 | 1  => ImColorModel::Gray,
+            2      => ImColorModel::RGB,
+            3      => ImColorModel::Indexed,
+            5 if self.samples_per_pixel >= 4 => ImColorModel::CMYK,
+            6      => ImColorModel::YCbCr,
+            8..=10 => ImColorModel::Lab,
+            _      => ImColorModel::Other,
+        };
+        // 1 ... associated alpha
+        // 2 ... unassociated alpha
+        let has_alpha = matches!(self.extra_sample, Some(1 | 2));
+        return Some(ImPixelFormat::new(
+            color_model, self.samples_per_pixel as u16, self.bits_per_sample as u16, has_alpha));
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn is_reduced(&self) -> bool {
+        return self.subfile_type & 1 != 0;
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn is_mask(&self) -> bool {
+        return self.subfile_type & 4 != 0;
+    }
+
+    #[allow(clippy::needless_return)]
+    fn resolution(&self) -> Option<ImResolution> {
+        let x = self.x_resolution?;
+        let y = self.y_resolution.unwrap_or(x);
+        if x <= 0.0 || y <= 0.0 {
+            return None;
+        }
+        let unit = match self.resolution_unit.unwrap_or(2) {
+            1 => ImResolutionUnit::Unknown,
+            2 => ImResolutionUnit::Inch,
+            3 => ImResolutionUnit::Centimeter,
+            _ => return None
+        };
+        return Some(ImResolution::new(x, y, unit));
+    }
+}
+
+/// Reads the tags of interest of the IFD at `ifd_offset`. The tags that are
+/// only needed to identify camera RAW files and to find their embedded
+/// images (Make, StripOffsets, JPEGInterchangeFormat and the Panasonic tags)
+/// are only read if `raw_tags` is set or the IFD itself looks like part of a
+/// RAW file, i.e. it has a DNGVersion, is a reduced resolution image or
+/// holds color filter array data.
+#[allow(clippy::needless_return)]
+fn parse_tiff_ifd<BR, R>(reader: &mut R, ifd_offset: u64, bigtiff: bool, raw_tags: bool) -> ImResult<TiffIfd>
+where BR: BinaryReader, R: Read, R: Seek {
+    map_err!(TIFF reader.seek(SeekFrom::Start(ifd_offset)));
+
+    // 2 bytes: TagId + 2 bytes: type + 4 bytes: count of values + 4
+    // bytes: value offset
+    // BigTIFF: 2 bytes: TagId + 2 bytes: type + 8 bytes: count of values + 8
+    // bytes: value offset
+    let (ifd_entry_count, count_size, entry_size) = if bigtiff {
+        (map_expr!(TIFF BR::read_u64(reader)), 8, 20)
+    } else {
+        (map_expr!(TIFF BR::read_u16(reader)) as u64, 2, 12)
+    };
+    let mut ifd = TiffIfd {
+        width:  None,
+        height: None,
+        bits_per_sample:   1,
+        samples_per_pixel: 1,
+        photometric:  None,
+        extra_sample: None,
+        orientation:  None,
+        x_resolution: None,
+        y_resolution: None,
+        resolution_unit: None,
+        subfile_type: 0,
+        sub_ifd_offsets: Vec::new(),
+        next_ifd_offset: 0,
+        compression: None,
+        make: Vec::new(),
+        dng: false,
+        cr2_slices: false,
+        strip_offset: None,
+        jpeg_offset:  None,
+        sensor_width:  None,
+        sensor_height: None,
+        jpeg_from_raw_offset: None,
+    };
+
+    // tag, type, count and value offset of the RAW specific tags
+    let mut raw_entries: Vec<(u16, u16, u64, u64)> = Vec::new();
+
+    for index in 0..ifd_entry_count {
+        let entry_offset = ifd_offset + count_size + index * entry_size;
+        map_err!(TIFF reader.seek(SeekFrom::Start(entry_offset)));
+        let tag   = map_expr!(TIFF BR::read_u16(reader));
+        let ftype = map_expr!(TIFF BR::read_u16(reader));
+        let count = if bigtiff {
+            map_expr!(TIFF BR::read_u64(reader))
+        } else {
+            map_expr!(TIFF BR::read_u32(reader)) as u64
+        };
+        let value_offset = entry_offset + if bigtiff { 12 } else { 8 };
+
+        // 256 ... ImageWidth
+        // 257 ... ImageLength
+        // 258 ... BitsPerSample
+        // 262 ... PhotometricInterpretation
+        // 274 ... Orientation
+        // 277 ... SamplesPerPixel
+        // 338 ... ExtraSamples
+        if matches!(tag, 256 | 257 | 258 | 262 | 274 | 277 | 338) {
+            let value = read_tiff_value::<BR, R>(reader, ftype, count, value_offset, bigtiff)?;
+            match tag {
+                256 => ifd.width  = Some(value),
+                257 => ifd.height = Some(value),
+                258 => ifd.bits_per_sample   = value,
+                262 => ifd.photometric       = Some(value),
+                274 => ifd.orientation       = Some(value),
+                277 => ifd.samples_per_pixel = value,
+                _   => ifd.extra_sample      = Some(value),
+            }
+        } else if matches!(tag, 282 | 283) {
+            // 282 ... XResolution
+            // 283 ... YResolution
+            let value = read_tiff_float::<BR, R>(reader, ftype, count, value_offset, bigtiff)?;
+            if tag == 282 {
+                ifd.x_resolution = Some(value);
+            } else {
+                ifd.y_resolution = Some(value);
+            }
+        } else if tag == 296 {
+            // 296 ... ResolutionUnit
+            ifd.resolution_unit = Some(read_tiff_value::<BR, R>(reader, ftype, count, value_offset, bigtiff)?);
+        } else if tag == 254 {
+            // 254 ... NewSubfileType
+            ifd.subfile_type = read_tiff_value::<BR, R>(reader, ftype, count, value_offset, bigtiff)?;
+        } else if tag == 255 {
+            // 255 ... SubfileType (deprecated), 2: reduced resolution, 3: page
+            ifd.subfile_type = match read_tiff_value::<BR, R>(reader, ftype, count, value_offset, bigtiff)? {
+                2 => 1,
+                3 => 2,
+                _ => 0,
+            };
+        } else if tag == 330 {
+            // 330 ... SubIFDs
+            ifd.sub_ifd_offsets = read_tiff_offsets::<BR, R>(reader, ftype, count, value_offset, bigtiff)?;
+        } else if tag == 259 {
+            // 259 ... Compression
+            ifd.compression = Some(read_tiff_value::<BR, R>(reader, ftype, count, value_offset, bigtiff)?);
+        } else if tag == 50706 {
+            // 50706 ... DNGVersion
+            ifd.dng = true;
+        } else if tag == 50752 {
+            // 50752 ... Canon CR2 slices
+            ifd.cr2_slices = true;
+        } else if matches!(tag, 2 | 3 | 46 | 271 | 273 | 513) {
+            // only the last entry of each tag counts
+            raw_entries.retain(|entry| entry.0 != tag);
+            raw_entries.push((tag, ftype, count, value_offset));
+        }
+    }
+
+    if raw_tags || ifd.dng || ifd.is_reduced() || matches!(ifd.photometric, Some(32803 | 34892)) {
+        for (tag, ftype, count, value_offset) in raw_entries {
+            match tag {
+                // Panasonic RW2: 2 ... SensorWidth, 3 ... SensorHeight
+                2 => ifd.sensor_width  = Some(read_tiff_value::<BR, R>(reader, ftype, count, value_offset, bigtiff)?),
+                3 => ifd.sensor_height = Some(read_tiff_value::<BR, R>(reader, ftype, count, value_offset, bigtiff)?),
+                46 => {
+                    // Panasonic RW2: 46 ... JpgFromRaw, the JPEG is the value itself
+                    seek_tiff_values::<BR, R>(reader, ftype, count, value_offset, bigtiff)?;
+                    ifd.jpeg_from_raw_offset = Some(map_expr!(TIFF reader.stream_position()));
+                }
+                271 if ftype == 2 => {
+                    // 271 ... Make
+                    seek_tiff_values::<BR, R>(reader, ftype, count, value_offset, bigtiff)?;
+                    let mut make = vec![0u8; count.min(TIFF_MAX_MAKE_SIZE) as usize];
+                    map_err!(TIFF reader.read_exact(&mut make));
+                    ifd.make = make;
+                }
+                // 273 ... StripOffsets
+                273 => ifd.strip_offset = Some(read_tiff_value::<BR, R>(reader, ftype, count, value_offset, bigtiff)?),
+                // 513 ... JPEGInterchangeFormat
+                513 => ifd.jpeg_offset  = Some(read_tiff_value::<BR, R>(reader, ftype, count, value_offset, bigtiff)?),
+                _ => {}
+            }
+        }
+    }
+
+    // be lenient about a missing next IFD offset at the end of the file
+    let next_offset = ifd_entry_count.checked_mul(entry_size).and_then(|size| size.checked_add(ifd_offset + count_size));
+    let next_offset = map_expr!(TIFF next_offset.ok_or(()));
+    map_err!(TIFF reader.seek(SeekFrom::Start(next_offset)));
+    ifd.next_ifd_offset = if bigtiff {
+        BR::read_u64(reader).unwrap_or(0)
+    } else {
+        BR::read_u32(reader).map(|offset| offset as u64).unwrap_or(0)
+    };
+
+    return Ok(ifd);
+}
+
+#[allow(clippy::needless_return)]
+fn parse_tiff<BR, R>(reader: &mut R, preamble: &[u8], options: &ImOptions) -> ImResult<ImInfo>
+where BR: BinaryReader, R: Read, R: Seek {
+    // ORF and RW2 only differ from TIFF in their magic number, CR2 files have
+    // their own signature after the IFD0 offset
+    let header_format = if preamble.starts_with(b"IIRO") || preamble.starts_with(b"IISR") || preamble.starts_with(b"MMOR") {
+        ImFormat::ORF
+    } else if preamble.starts_with(b"IIU\0") {
+        ImFormat::RW2
+    } else if preamble.len() >= 11 && &preamble[8..11] == b"CR\x02" {
+        ImFormat::CR2
+    } else {
+        ImFormat::TIFF
+    };
+
+    // 42 for TIFF, 43 for BigTIFF
+    let bigtiff = header_format == ImFormat::TIFF && BR::get_u16(array2!(preamble, 2)) == 43;
+    let ifd_offset = if bigtiff {
+        // offset byte size (always 8) and a reserved field
+        if preamble.len() < 16 || BR::get_u16(array2!(preamble, 4)) != 8 {
+            return Err(ImError::ParserError(ImFormat::TIFF));
+        }
+        BR::get_u64([
+            preamble[ 8], preamble[ 9], preamble[10], preamble[11],
+            preamble[12], preamble[13], preamble[14], preamble[15],
+        ])
+    } else {
+        BR::get_u32(array4!(preamble, 4)) as u64
+    };
+
+    // ORF, RW2 and CR2 are known to be RAW files from the header alone
+    let first = parse_tiff_ifd::<BR, R>(reader, ifd_offset, bigtiff, header_format != ImFormat::TIFF).map_err(|error| tiff_error(error, header_format))?;
+    let raw_format = if header_format != ImFormat::TIFF {
+        Some(header_format)
+    } else if first.dng {
+        Some(ImFormat::DNG)
+    } else {
+        tiff_raw_format_from_make(&first.make)
+    };
+
+    // unless all images are requested or the previews of a RAW file have to
+    // be found only walk the IFDs until the first one that is not a reduced
+    // resolution image or a mask
+    let stop_at_primary = !options.images && raw_format.is_none();
+    let ifds = walk_tiff_ifds::<BR, R>(reader, first, ifd_offset, bigtiff, stop_at_primary, raw_format.is_some());
+
+    let primary_index = ifds.iter()
+        .position(|ifd| !ifd.is_reduced() && !ifd.is_mask() && ifd.width.is_some() && ifd.height.is_some())
+        .unwrap_or(0);
+
+    if let Some(raw_format) = raw_format {
+        // the make alone doesn't make a RAW file, e.g. scanners write TIFF
+        // files too, so require color filter array (or linear raw) data
+        let is_raw = match raw_format {
+            ImFormat::NEF | ImFormat::ARW | ImFormat::PEF => matches!(ifds[primary_index].photometric, Some(32803 | 34892)),
+            _ => true,
+        };
+        if is_raw {
+            return parse_tiff_raw::<BR, R>(reader, raw_format, &ifds, primary_index, options)
+                .map_err(|error| tiff_error(error, raw_format));
+        }
+    }
+
+    let first   = &ifds[0];
+    let primary = &ifds[primary_index];
+
+    let (width, height) = match (primary.width, primary.height) {
+        (Some(width), Some(height)) => (width, height),
+        _ => return Err(ImError::ParserError(ImFormat::TIFF))
+    };
+
+    let mut info = ImInfo::new(ImFormat::TIFF, width, height);
+    info.pixel_format = primary.pixel_format();
+    if options.orientation {
+        // the orientation might only be given in IFD0
+        info.orientation = primary.orientation.or(first.orientation)
+            .and_then(|value| ImOrientation::from_exif(value as u16));
+    }
+    if options.resolution {
+        info.resolution = primary.resolution().or_else(|| first.resolution());
+    }
+
+    if options.images {
+        for (index, ifd) in ifds.iter().enumerate() {
+            if let (Some(width), Some(height)) = (ifd.width, ifd.height) {
+                let role = if index == primary_index {
+                    ImRole::Primary
+                } else if ifd.is_mask() {
+                    ImRole::Auxiliary
+                } else if ifd.is_reduced() {
+                    ImRole::Thumbnail
+                } else {
+                    ImRole::Page
+                };
+                info.images.push(ImImage {
+                    index: info.images.len(),
+                    width,
+                    height,
+                    format: ImFormat::TIFF,
+                    role,
+                });
+            }
+        }
+    }
+
+    return Ok(info);
+}
+
+/// Errors of the code shared by all TIFF based formats are reported as
+/// errors of the actual format.
+#[inline]
+#[allow(clippy::needless_return)]
+fn tiff_error(error: ImError, format: ImFormat) -> ImError {
+    return match error {
+        ImError::ParserError(ImFormat::TIFF) => ImError::ParserError(format),
+        error => error,
+    };
+}
+
+/// Camera RAW formats that are plain TIFF files can only be told apart by
+/// the camera make.
+#[allow(clippy::needless_return)]
+fn tiff_raw_format_from_make(make: &[u8]) -> Option<ImFormat> {
+    let make = make.to_ascii_uppercase();
+    if make.starts_with(b"NIKON") {
+        return Some(ImFormat::NEF);
+    } else if make.starts_with(b"SONY") {
+        return Some(ImFormat::ARW);
+    } else if make.starts_with(b"PENTAX") || make.starts_with(b"RICOH") {
+        return Some(ImFormat::PEF);
+    }
+    return None;
+}
+
+/// Reports the raw data of a TIFF based camera RAW file as the main image
+/// and all other images as previews.
+#[allow(clippy::needless_return)]
+fn parse_tiff_raw<BR, R>(reader: &mut R, format: ImFormat, ifds: &[TiffIfd], primary_index: usize, options: &ImOptions) -> ImResult<ImInfo>
+where BR: BinaryReader, R: Read, R: Seek {
+    let first = &ifds[0];
+    let (raw_index, width, height) = match format {
+        ImFormat::RW2 => {
+            match (first.sensor_width, first.sensor_height) {
+                (Some(width), Some(height)) => (0, width, height),
+                _ => return Err(ImError::ParserError(format))
+            }
+        }
+        ImFormat::CR2 => {
+            // the raw data is a lossless JPEG in IFD3, each of its samples
+            // holds several sensor pixels
+            let raw_index = ifds.iter().position(|ifd| ifd.cr2_slices).unwrap_or(3);
+            let strip_offset = ifds.get(raw_index).and_then(|ifd| ifd.strip_offset);
+            let raw = strip_offset.and_then(|offset| read_embedded_jpeg(reader, offset, &ImOptions::default()));
+            match raw {
+                Some(raw) => {
+                    let components = raw.pixel_format.map(|pixel_format| pixel_format.channels).unwrap_or(1);
+                    (raw_index, raw.width * components as u64, raw.height)
+                }
+                None => return Err(ImError::ParserError(format))
+            }
+        }
+        _ => {
+            let primary = &ifds[primary_index];
+            match (primary.width, primary.height) {
+                (Some(width), Some(height)) => (primary_index, width, height),
+                _ => return Err(ImError::ParserError(format))
+            }
+        }
+    };
+
+    let mut previews = Vec::new();
+    for (index, ifd) in ifds.iter().enumerate() {
+        if index == raw_index || ifd.is_mask() {
+            continue;
+        }
+        if let (Some(width), Some(height)) = (ifd.width, ifd.height) {
+            // 6 ... old-style JPEG, 7 ... JPEG
+            let preview_format = if matches!(ifd.compression, Some(6 | 7)) { ImFormat::JPEG } else { format };
+            previews.push((width, height, preview_format));
+        } else if let Some(jpeg) = ifd.jpeg_offset.and_then(|offset| read_embedded_jpeg(reader, offset, &ImOptions::default())) {
+            previews.push((jpeg.width, jpeg.height, ImFormat::JPEG));
+        }
+    }
+    if let Some(jpeg) = first.jpeg_from_raw_offset.and_then(|offset| read_embedded_jpeg(reader, offset, &ImOptions::default())) {
+        previews.push((jpeg.width, jpeg.height, ImFormat::JPEG));
+    }
+
+    let mut info = raw_info(format, width, height, &previews, options);
+    let raw = ifds.get(raw_index).unwrap_or(first);
+    info.pixel_format = raw.pixel_format();
+    if options.orientation {
+        info.orientation = raw.orientation.or(first.orientation)
+            .and_then(|value| ImOrientation::from_exif(value as u16));
+    }
+    if options.resolution {
+        info.resolution = raw.resolution().or_else(|| first.resolution());
+    }
+
+    return Ok(info);
+}
+
+/// Builds the info of a camera RAW file from the size of the raw data and
+/// the sizes and formats of the embedded previews.
+#[allow(clippy::needless_return)]
+fn raw_info(format: ImFormat, width: u64, height: u64, previews: &[(u64, u64, ImFormat)], options: &ImOptions) -> ImInfo {
+    let mut info = ImInfo::new(format, width, height);
+    let preview_size = previews.iter()
+        .max_by_key(|(width, height, _)| width.saturating_mul(*height))
+        .map(|&(width, height, _)| (width, height));
+    info.details = ImDetails::RAW(ImRawInfo { preview_size });
+
+    if options.images {
+        info.images.push(ImImage {
+            index: 0,
+            width,
+            height,
+            format,
+            role: ImRole::Primary,
+        });
+        for &(width, height, format) in previews {
+            info.images.push(ImImage {
+                index: info.images.len(),
+                width,
+                height,
+                format,
+                role: ImRole::Thumbnail,
+            });
+        }
+    }
+
+    return info;
+}
+
+const RAF_MAX_RECORDS: u32 = 1024;
+
+/// Fujifilm RAF files start with a big endian header that contains the
+/// offsets of an embedded JPEG preview and of the CFA header, a list of
+/// records describing the raw data.
+#[allow(clippy::needless_return)]
+fn parse_raf<R>(reader: &mut R, options: &ImOptions) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    let mut header = [0u8; 12];
+    map_err!(RAF reader.seek(SeekFrom::Start(84)));
+    map_err!(RAF reader.read_exact(&mut header));
+    let jpeg_offset       = u32::from_be_bytes(array4!(header, 0)) as u64;
+    let cfa_header_offset = u32::from_be_bytes(array4!(header, 8)) as u64;
+
+    // record count, then records of tag, size and data
+    let mut buf = [0u8; 4];
+    map_err!(RAF reader.seek(SeekFrom::Start(cfa_header_offset)));
+    map_err!(RAF reader.read_exact(&mut buf));
+    let record_count = u32::from_be_bytes(buf);
+
+    let mut full_size    = None;
+    let mut cropped_size = None;
+    for _ in 0..record_count.min(RAF_MAX_RECORDS) {
+        map_err!(RAF reader.read_exact(&mut buf));
+        let tag         = u16::from_be_bytes(array2!(buf, 0));
+        let record_size = u16::from_be_bytes(array2!(buf, 2)) as i64;
+        // 0x100 ... RawImageFullSize, 0x111 ... RawImageCroppedSize
+        if matches!(tag, 0x100 | 0x111) && record_size >= 4 {
+            map_err!(RAF reader.read_exact(&mut buf));
+            let h = u16::from_be_bytes(array2!(buf, 0)) as u64;
+            let w = u16::from_be_bytes(array2!(buf, 2)) as u64;
+            if tag == 0x100 {
+                full_size = Some((w, h));
+                break;
+            }
+            cropped_size = Some((w, h));
+            map_err!(RAF reader.seek(SeekFrom::Current(record_size - 4)));
+        } else {
+            map_err!(RAF reader.seek(SeekFrom::Current(record_size)));
+        }
+    }
+
+    let (width, height) = match full_size.or(cropped_size) {
+        Some(size) => size,
+        None => return Err(ImError::ParserError(ImFormat::RAF))
+    };
+
+    // the preview carries the EXIF data of the camera
+    let preview_options = ImOptions {
+        orientation: options.orientation,
+        ..ImOptions::default()
+    };
+    let preview = if jpeg_offset != 0 {
+        read_embedded_jpeg(reader, jpeg_offset, &preview_options)
+    } else {
+        None
+    };
+
+    let previews: Vec<_> = preview.iter().map(|preview| (preview.width, preview.height, ImFormat::JPEG)).collect();
+    let mut info = raw_info(ImFormat::RAF, width, height, &previews, options);
+    if let Some(preview) = preview {
+        info.orientation = preview.orientation;
+    }
+
+    return Ok(info);
+}
+
+const CR3_MAX_MOOV_SIZE: u64 = 16 * 1024 * 1024;
+
+/// UUID of the box in `moov` that holds the TIFF metadata and the thumbnail.
+const CR3_CANON_UUID: [u8; 16] = [
+    0x85, 0xc0, 0xb6, 0x87, 0x82, 0x0f, 0x11, 0xe0,
+    0x81, 0x11, 0xf4, 0xce, 0x46, 0x2b, 0x6a, 0x48,
+];
+
+#[inline]
+#[allow(clippy::needless_return)]
+fn find_iso_box<'a>(data: &'a [u8], name: &[u8; 4]) -> Option<&'a [u8]> {
+    for iso_box in iso_boxes(data) {
+        let (box_type, payload) = iso_box?;
+        if box_type == name {
+            return Some(payload);
+        }
+    }
+    return None;
+}
+
+/// Reads the `CRAW` sample entry of a CR3 track. Returns width, height and
+/// whether the track holds JPEG data instead of raw data.
+#[allow(clippy::needless_return)]
+fn cr3_track_size(trak: &[u8]) -> Option<(u64, u64, bool)> {
+    let mdia = find_iso_box(trak, b"mdia")?;
+    let minf = find_iso_box(mdia, b"minf")?;
+    let stbl = find_iso_box(minf, b"stbl")?;
+    let stsd = find_iso_box(stbl, b"stsd")?;
+    // full box version and flags, entry count
+    let craw = find_iso_box(stsd.get(8..)?, b"CRAW")?;
+    if craw.len() < 28 {
+        return None;
+    }
+    let w = u16::from_be_bytes(array2!(craw, 24)) as u64;
+    let h = u16::from_be_bytes(array2!(craw, 26)) as u64;
+
+    // the child boxes follow the visual sample entry, which Canon extends by
+    // an image type field
+    for offset in [80, 78] {
+        if let Some(children) = craw.get(offset..) {
+            if find_iso_box(children, b"CMP1").is_some() {
+                return Some((w, h, false));
+            } else if find_iso_box(children, b"JPEG").is_some() {
+                return Some((w, h, true));
+            }
+        }
+    }
+    return None;
+}
+
+/// Canon CR3 files store the full size JPEG preview, a small raw image and
+/// the full raw image as tracks in the `moov` box.
+#[allow(clippy::needless_return)]
+fn parse_cr3<R>(reader: &mut R, ftyp_size: u64, options: &ImOptions) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    map_err!(CR3 reader.seek(SeekFrom::Start(ftyp_size)));
+    let moov_size = skip_to_iso_box(reader, b"moov", u64::MAX, ImFormat::CR3)?;
+    if moov_size > CR3_MAX_MOOV_SIZE {
+        return Err(ImError::ParserError(ImFormat::CR3));
+    }
+    let mut moov = vec![0u8; moov_size as usize - 8];
+    map_err!(CR3 reader.read_exact(&mut moov));
+
+    let mut tracks = Vec::new();
+    let mut previews = Vec::new();
+    let mut cmt1 = None;
+    for moov_box in iso_boxes(&moov) {
+        let (box_type, data) = map_expr!(CR3 moov_box.ok_or(()));
+        if box_type == b"trak" {
+            if let Some(track) = cr3_track_size(data) {
+                tracks.push(track);
+            }
+        } else if box_type == b"uuid" && data.starts_with(&CR3_CANON_UUID) {
+            for canon_box in iso_boxes(&data[16..]) {
+                let (canon_type, canon_data) = map_expr!(CR3 canon_box.ok_or(()));
+                if canon_type == b"CMT1" {
+                    // IFD0 as a TIFF file
+                    cmt1 = Some(canon_data);
+                } else if canon_type == b"THMB" && canon_data.len() >= 8 {
+                    // full box version and flags, width, height
+                    let w = u16::from_be_bytes(array2!(canon_data, 4)) as u64;
+                    let h = u16::from_be_bytes(array2!(canon_data, 6)) as u64;
+                    previews.push((w, h, ImFormat::JPEG));
+                }
+            }
+        }
+    }
+
+    // the biggest raw track is the full raw image
+    let raw_index = tracks.iter().enumerate()
+        .filter(|(_, &(_, _, is_jpeg))| !is_jpeg)
+        .max_by_key(|(_, &(w, h, _))| w * h)
+        .map(|(index, _)| index);
+    let raw_index = map_expr!(CR3 raw_index.ok_or(()));
+    let (width, height, _) = tracks[raw_index];
+
+    for (index, &(w, h, is_jpeg)) in tracks.iter().enumerate() {
+        if index != raw_index {
+            previews.push((w, h, if is_jpeg { ImFormat::JPEG } else { ImFormat::CR3 }));
+        }
+    }
+
+    let mut info = raw_info(ImFormat::CR3, width, height, &previews, options);
+    if options.orientation || options.resolution {
+        if let Some(ifd0) = cmt1.and_then(parse_exif_ifd0) {
+            if options.orientation {
+                info.orientation = ifd0.orientation.and_then(|value| ImOrientation::from_exif(value as u16));
+            }
+            if options.resolution {
+                info.resolution = ifd0.resolution();
+            }
+        }
+    }
+
+    return Ok(info);
+}
+
+const TIFF_MAX_IFDS: usize = 4096;
+const TIFF_MAX_MAKE_SIZE: u64 = 64;
+
+/// Continues the IFD chain after the already parsed `first` IFD at
+/// `ifd_offset`, including SubIFDs, in file order: each IFD is directly
+/// followed by its SubIFDs. A broken IFD ends the walk. If `stop_at_primary`
+/// is set the walk ends after the first IFD that is neither a reduced
+/// resolution image nor a mask. `raw_tags` is passed on to [parse_tiff_ifd].
+#[allow(clippy::needless_return)]
+fn walk_tiff_ifds<BR, R>(reader: &mut R, first: TiffIfd, ifd_offset: u64, bigtiff: bool, stop_at_primary: bool, raw_tags: bool) -> Vec<TiffIfd>
+where BR: BinaryReader, R: Read, R: Seek {
+    let mut ifds = Vec::new();
+
+    // guard against loops in broken files
+    let mut visited = std::collections::HashSet::new();
+    visited.insert(ifd_offset);
+
+    let mut pending = Vec::new();
+    let mut ifd = first;
+    loop {
+        let is_primary = !ifd.is_reduced() && !ifd.is_mask();
+
+        if ifd.next_ifd_offset != 0 {
+            pending.push(ifd.next_ifd_offset);
+        }
+        // reversed so that the first SubIFD is visited next
+        for &offset in ifd.sub_ifd_offsets.iter().rev() {
+            if offset != 0 {
+                pending.push(offset);
+            }
+        }
+        ifds.push(ifd);
+
+        if (stop_at_primary && is_primary) || ifds.len() >= TIFF_MAX_IFDS {
+            break;
+        }
+
+        let next_ifd = loop {
+            match pending.pop() {
+                Some(offset) if visited.insert(offset) => break parse_tiff_ifd::<BR, R>(reader, offset, bigtiff, raw_tags).ok(),
+                Some(_) => {}
+                None => break None
+            }
+        };
+        match next_ifd {
+            Some(next_ifd) => ifd = next_ifd,
+            None => break
+        }
+    }
+
+    return ifds;
+}
+
+/// Reads IFD0 of EXIF data. The data may start with the `Exif\0\0` header of
+/// JPEG APP1 segments. Broken EXIF data is ignored.
+#[allow(clippy::needless_return)]
+fn parse_exif_ifd0(exif: &[u8]) -> Option<TiffIfd> {
+    let exif = exif.strip_prefix(b"Exif\0\0").unwrap_or(exif);
+    if exif.len() < 8 {
+        return None;
+    }
+    let mut reader = std::io::Cursor::new(exif);
+    let ifd = if exif.starts_with(b"MM\0*") {
+        let ifd_offset = BigEndianReader::get_u32(array4!(exif, 4)) as u64;
+        parse_tiff_ifd::<BigEndianReader, _>(&mut reader, ifd_offset, false, false).ok()
+    } else if exif.starts_with(b"II*\0") {
+        let ifd_offset = LittleEndianReader::get_u32(array4!(exif, 4)) as u64;
+        parse_tiff_ifd::<LittleEndianReader, _>(&mut reader, ifd_offset, false, false).ok()
+    } else {
+        None
+    };
+    return ifd;
+}
+
+#[inline]
+#[allow(clippy::needless_return)]
+fn parse_exif_orientation(exif: &[u8]) -> Option<ImOrientation> {
+    return ImOrientation::from_exif(parse_exif_ifd0(exif)?.orientation? as u16);
+}
+
+/// Reads the size of the thumbnail in IFD1 of EXIF data. The size of JPEG
+/// thumbnails is read from the embedded image, uncompressed thumbnails have
+/// it in the IFD itself.
+#[allow(clippy::needless_return)]
+fn parse_exif_thumbnail(exif: &[u8], ifd0: &TiffIfd) -> Option<(u64, u64)> {
+    let exif = exif.strip_prefix(b"Exif\0\0").unwrap_or(exif);
+    if ifd0.next_ifd_offset == 0 {
+        return None;
+    }
+    // JPEGInterchangeFormat is needed for JPEG thumbnails
+    let mut reader = std::io::Cursor::new(exif);
+    let ifd1 = if exif.starts_with(b"MM\0*") {
+        parse_tiff_ifd::<BigEndianReader, _>(&mut reader, ifd0.next_ifd_offset, false, true).ok()?
+    } else {
+        parse_tiff_ifd::<LittleEndianReader, _>(&mut reader, ifd0.next_ifd_offset, false, true).ok()?
+    };
+    if let Some(jpeg_offset) = ifd1.jpeg_offset {
+        let info = read_embedded_jpeg(&mut reader, jpeg_offset, &ImOptions::default())?;
+        return Some((info.width, info.height));
+    }
+    return Some((ifd1.width?, ifd1.height?));
+}
+
+const MPF_MAX_IMAGES: usize = 256;
+
+/// Parses the MP Index IFD of an APP2 `MPF` segment (without the `MPF\0`
+/// prefix). Returns the attribute and the offset of every MP entry. Offsets
+/// are relative to the start of the MP header, the first image (the file
+/// itself) has the offset 0.
+#[allow(clippy::needless_return)]
+fn parse_mpf_entries(mpf: &[u8]) -> Option<Vec<(u32, u64)>> {
+    let big_endian = match mpf.get(..4)? {
+        b"MM\0*" => true,
+        b"II*\0" => false,
+        _ => return None
+    };
+    let get_u16 = |offset: usize| -> Option<u16> {
+        let bytes = mpf.get(offset..offset + 2)?;
+        let bytes = array2!(bytes, 0);
+        return Some(if big_endian { u16::from_be_bytes(bytes) } else { u16::from_le_bytes(bytes) });
+    };
+    let get_u32 = |offset: usize| -> Option<u32> {
+        let bytes = mpf.get(offset..offset + 4)?;
+        let bytes = array4!(bytes, 0);
+        return Some(if big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) });
+    };
+
+    let ifd_offset = get_u32(4)? as usize;
+    let entry_count = get_u16(ifd_offset)? as usize;
+    for index in 0..entry_count {
+        let entry_offset = ifd_offset + 2 + index * 12;
+        // MPEntry, 16 bytes per image: attribute, size, offset and two
+        // dependent image entry numbers
+        if get_u16(entry_offset)? == 0xB002 {
+            let size = get_u32(entry_offset + 4)? as usize;
+            let offset = get_u32(entry_offset + 8)? as usize;
+            let mut entries = Vec::new();
+            for mp_entry in 0..(size / 16).min(MPF_MAX_IMAGES) {
+                let mp_entry_offset = offset + mp_entry * 16;
+                entries.push((get_u32(mp_entry_offset)?, get_u32(mp_entry_offset + 8)? as u64));
+            }
+            return Some(entries);
+        }
+    }
+    return None;
+}
+
+trait ReadSeek: Read + Seek {}
+
+impl<R> ReadSeek for R where R: Read, R: Seek {}
+
+/// A view of a reader that starts at `start`, for parsing images that are
+/// embedded in other files. It isn't generic over the reader, because then
+/// parsing the embedded image would instantiate the parser for endlessly
+/// nested reader types.
+struct SubReader<'a> {
+    reader: &'a mut dyn ReadSeek,
+    start: u64,
+}
+
+impl<'a> Read for SubReader<'a> {
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        return self.reader.read(buf);
+    }
+}
+
+impl<'a> Seek for SubReader<'a> {
+    #[allow(clippy::needless_return)]
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(offset) => match self.start.checked_add(offset) {
+                Some(offset) => SeekFrom::Start(offset),
+                None => return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "seek offset overflow"))
+            },
+            pos => pos,
+        };
+        let offset = self.reader.seek(pos)?;
+        return Ok(offset.saturating_sub(self.start));
+    }
+}
+
+/// Reads the header of an image embedded at `offset` if `is_allowed` accepts
+/// its first 12 bytes. The allowed formats must not embed images themselves,
+/// so that broken files can't nest images endlessly.
+#[allow(clippy::needless_return)]
+fn read_embedded_image<R>(reader: &mut R, offset: u64, is_allowed: fn(&[u8; 12]) -> bool, options: &ImOptions) -> Option<ImInfo>
+where R: Read, R: Seek {
+    let mut magic = [0u8; 12];
+    reader.seek(SeekFrom::Start(offset)).ok()?;
+    reader.read_exact(&mut magic).ok()?;
+    if !is_allowed(&magic) {
+        return None;
+    }
+    let mut sub_reader = SubReader { reader, start: offset };
+    sub_reader.seek(SeekFrom::Start(0)).ok()?;
+    return read_info(&mut sub_reader, options).ok();
+}
+
+/// Reads the header of a JPEG image embedded at `offset`, e.g. the preview of
+/// a camera RAW file. Returns `None` if there is no valid JPEG image.
+#[inline]
+#[allow(clippy::needless_return)]
+fn read_embedded_jpeg<R>(reader: &mut R, offset: u64, options: &ImOptions) -> Option<ImInfo>
+where R: Read, R: Seek {
+    return read_embedded_image(reader, offset, |magic| magic.starts_with(b"\xff\xd8\xff"), options);
+}
+
+/// Reads the header of a PNG or JPEG 2000 image embedded at `offset` in an
+/// ICNS file. Returns `None` if there is no such image.
+#[inline]
+#[allow(clippy::needless_return)]
+fn read_embedded_icns_image<R>(reader: &mut R, offset: u64) -> Option<ImInfo>
+where R: Read, R: Seek {
+    return read_embedded_image(reader, offset, |magic|
+        magic.starts_with(b"\x89PNG\r\n\x1a\n") ||
+        magic == b"\0\0\0\x0CjP  \r\n\x87\n" ||
+        magic.starts_with(b"\xff\x4f\xff\x51"),
+        &ImOptions::default());
+}
+
+/// Maps an ICNS element type to the icon size in pixels, the scale and the
+/// pixel format of the classic image data. Returns `None` for masks and
+/// elements that aren't icons.
+#[allow(clippy::needless_return)]
+fn icns_icon_size(element_type: &[u8]) -> Option<(u64, u64, u8, Option<ImPixelFormat>)> {
+    // 1 bit image followed by a 1 bit mask
+    let mono     = Some(ImPixelFormat::new(ImColorModel::Gray,    2, 1, true));
+    let indexed4 = Some(ImPixelFormat::new(ImColorModel::Indexed, 1, 4, false));
+    let indexed8 = Some(ImPixelFormat::new(ImColorModel::Indexed, 1, 8, false));
+    // the alpha channel is in the matching mask element
+    let rgb      = Some(ImPixelFormat::new(ImColorModel::RGB,     3, 8, false));
+    let argb     = Some(ImPixelFormat::new(ImColorModel::RGB,     4, 8, true));
+    return match element_type {
+        // 1 bit icons with masks
+        b"ICON" => Some((32, 32, 1, Some(ImPixelFormat::new(ImColorModel::Gray, 1, 1, false)))),
+        b"icm#" => Some((16, 12, 1, mono)),
+        b"ics#" => Some((16, 16, 1, mono)),
+        b"ICN#" => Some((32, 32, 1, mono)),
+        b"ich#" => Some((48, 48, 1, mono)),
+        // indexed
+        b"icm4" => Some((16, 12, 1, indexed4)),
+        b"icm8" => Some((16, 12, 1, indexed8)),
+        b"ics4" => Some((16, 16, 1, indexed4)),
+        b"ics8" => Some((16, 16, 1, indexed8)),
+        b"icl4" => Some((32, 32, 1, indexed4)),
+        b"icl8" => Some((32, 32, 1, indexed8)),
+        b"ich4" => Some((48, 48, 1, indexed4)),
+        b"ich8" => Some((48, 48, 1, indexed8)),
+        // RGB, mostly run-length encoded
+        b"is32" => Some((16, 16, 1, rgb)),
+        b"il32" => Some((32, 32, 1, rgb)),
+        b"ih32" => Some((48, 48, 1, rgb)),
+        b"it32" => Some((128, 128, 1, rgb)),
+        // ARGB, PNG or JPEG 2000
+        b"ic04" => Some((16, 16, 1, argb)),
+        b"ic05" => Some((32, 32, 1, argb)),
+        b"icsb" => Some((18, 18, 1, argb)),
+        b"icsB" => Some((36, 36, 2, argb)),
+        b"sb24" => Some((24, 24, 1, argb)),
+        b"SB24" => Some((48, 48, 2, argb)),
+        // PNG or JPEG 2000
+        b"icp4" => Some((16, 16, 1, None)),
+        b"icp5" => Some((32, 32, 1, None)),
+        b"icp6" => Some((64, 64, 1, None)),
+        b"ic07" => Some((128, 128, 1, None)),
+        b"ic08" => Some((256, 256, 1, None)),
+        b"ic09" => Some((512, 512, 1, None)),
+        b"ic10" => Some((1024, 1024, 2, None)),
+        b"ic11" => Some((32, 32, 2, None)),
+        b"ic12" => Some((64, 64, 2, None)),
+        b"ic13" => Some((256, 256, 2, None)),
+        b"ic14" => Some((512, 512, 2, None)),
+        _ => None
+    };
+}
+
+const ICNS_MAX_ICONS: usize = 1024;
+
+/// ICNS files are a sequence of elements, each with a type and a size. Every
+/// icon type has a fixed size, but if requested embedded images are sized
+/// from their own headers.
+#[allow(clippy::needless_return)]
+fn parse_icns<R>(reader: &mut R, options: &ImOptions) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    let mut buf = [0u8; 8];
+    map_err!(ICNS reader.seek(SeekFrom::Start(0)));
+    map_err!(ICNS reader.read_exact(&mut buf));
+    let file_end = u32::from_be_bytes(array4!(buf, 4)) as u64;
+
+    let mut offset = 8;
+    let mut width  = 0;
+    let mut height = 0;
+    let mut pixel_format = None;
+    let mut icons = Vec::new();
+    while offset + 8 <= file_end && icons.len() < ICNS_MAX_ICONS {
+        map_err!(ICNS reader.seek(SeekFrom::Start(offset)));
+        map_err!(ICNS reader.read_exact(&mut buf));
+        let element_size = u32::from_be_bytes(array4!(buf, 4)) as u64;
+        if element_size < 8 {
+            return Err(ImError::ParserError(ImFormat::ICNS));
+        }
+
+        if let Some((mut w, mut h, scale, mut icon_pixel_format)) = icns_icon_size(&buf[..4]) {
+            let mut format = ImFormat::ICNS;
+            if options.embedded_headers && element_size >= 8 + 12 {
+                if let Some(embedded) = read_embedded_icns_image(reader, offset + 8) {
+                    w = embedded.width;
+                    h = embedded.height;
+                    format = embedded.format;
+                    icon_pixel_format = embedded.pixel_format;
+                }
+            }
+            if w >= width && h >= height {
+                width  = w;
+                height = h;
+                pixel_format = icon_pixel_format;
+            }
+            icons.push(ImIcnsIcon {
+                element_type: array4!(buf, 0),
+                width:  w,
+                height: h,
+                scale,
+                format,
+            });
+        }
+
+        offset += element_size;
+    }
+
+    if icons.is_empty() {
+        return Err(ImError::ParserError(ImFormat::ICNS));
+    }
+
+    let mut info = ImInfo::new(ImFormat::ICNS, width, height);
+    info.pixel_format = pixel_format;
+    if options.images {
+        info.images = icons.iter().enumerate().map(|(index, icon)| ImImage {
+            index,
+            width:  icon.width,
+            height: icon.height,
+            format: icon.format,
+            role:   ImRole::Icon,
+        }).collect();
+    }
+    info.details = ImDetails::ICNS(ImIcnsInfo { icons });
+    return Ok(info);
+}
+
+/// Reads the pixel format from a BITMAPCOREHEADER or a BITMAPINFOHEADER (or
+/// any of its successors) starting at `header_offset`. `header` are the
+/// bytes of the header that were already read. Only if `read_more` is set
+/// the rest is read from `reader`, otherwise `None` is returned if `header`
+/// is too short.
+#[allow(clippy::needless_return)]
+fn read_bitmap_pixel_format<R>(reader: &mut R, header: &[u8], header_offset: u64, format: ImFormat, read_more: bool) -> ImResult<Option<ImPixelFormat>>
+where R: Read, R: Seek {
+    if header.len() < 4 {
+        return Err(ImError::ParserError(format));
+    }
+    let mut buf = [0u8; 20];
+    let available = header.len().min(buf.len());
+    buf[..available].copy_from_slice(&header[..available]);
+
+    let header_size = u32::from_le_bytes(array4!(buf, 0));
+    let needed = if header_size == 12 { 12 } else { 20 };
+    if available < needed {
+        if !read_more {
+            return Ok(None);
+        }
+        map_err!(format, reader.seek(SeekFrom::Start(header_offset + available as u64)));
+        map_err!(format, reader.read_exact(&mut buf[available..needed]));
+    }
+
+    let (bit_count, compression) = if header_size == 12 {
+        (u16::from_le_bytes(array2!(buf, 10)), 0)
+    } else {
+        (u16::from_le_bytes(array2!(buf, 14)), u32::from_le_bytes(array4!(buf, 16)))
+    };
+
+    let pixel_format = match (bit_count, compression) {
+        // BI_JPEG and BI_PNG
+        (_, 4 | 5) => None,
+        (1 | 2 | 4 | 8, _) => Some(ImPixelFormat::new(ImColorModel::Indexed, 1, bit_count, false)),
+        (16 | 24 | 32 | 64, 3 | 6) => {
+            // BI_BITFIELDS and BI_ALPHABITFIELDS, the masks follow the
+            // BITMAPINFOHEADER and are part of the BITMAPV3INFOHEADER
+            if !read_more {
+                return Ok(None);
+            }
+            let mut masks = [0u8; 16];
+            let mask_count = if compression == 6 || header_size >= 56 { 4 } else { 3 };
+            map_err!(format, reader.seek(SeekFrom::Start(header_offset + 40)));
+            map_err!(format, reader.read_exact(&mut masks[..mask_count * 4]));
+
+            let red   = u32::from_le_bytes(array4!(masks,  0)).count_ones();
+            let green = u32::from_le_bytes(array4!(masks,  4)).count_ones();
+            let blue  = u32::from_le_bytes(array4!(masks,  8)).count_ones();
+            let alpha = u32::from_le_bytes(array4!(masks, 12)).count_ones();
+            let bits  = red.max(green).max(blue).max(alpha);
+
+            if alpha > 0 {
+                Some(ImPixelFormat::new(ImColorModel::RGB, 4, bits as u16, true))
+            } else {
+                Some(ImPixelFormat::new(ImColorModel::RGB, 3, bits as u16, false))
+            }
+        }
+        (16, _) => Some(ImPixelFormat::new(ImColorModel::RGB, 3, 5, false)),
+        // the 4th byte of 32 bit BI_RGB pixels is unused
+        (24 | 32, _) => Some(ImPixelFormat::new(ImColorModel::RGB, 3, 8, false)),
+        (64, _) => Some(ImPixelFormat::new(ImColorModel::RGB, 4, 16, true)),
+        _ => None
+    };
+
+    return Ok(pixel_format);
+}
+
+#[inline]
+#[allow(clippy::needless_return)]
+fn png_pixel_format(bit_depth: u8, color_type: u8) -> Option<ImPixelFormat> {
+    let bit_depth = bit_depth as u16;
+    return match color_type {
+        0 => Some(ImPixelFormat::new(ImColorModel::Gray,    1, bit_depth, false)),
+        2 => Some(ImPixelFormat::new(ImColorModel::RGB,     3, bit_depth, false)),
+        3 => Some(ImPixelFormat::new(ImColorModel::Indexed, 1, bit_depth, false)),
+        4 => Some(ImPixelFormat::new(ImColorModel::Gray,    2, bit_depth, true)),
+        6 => Some(ImPixelFormat::new(ImColorModel::RGB,     4, bit_depth, true)),
+        _ => None
+    };
+}
+
+/// Reads the header of a PNG or DIB image embedded in an ICO or CUR file.
+/// Returns the format, width, height and pixel format.
+#[allow(clippy::needless_return)]
+fn read_ico_image<R>(reader: &mut R, offset: u64, format: ImFormat) -> ImResult<(ImFormat, u32, u32, Option<ImPixelFormat>)>
+where R: Read, R: Seek {
+    let mut buf = [0u8; 26];
+    map_err!(format, reader.seek(SeekFrom::Start(offset)));
+    map_err!(format, reader.read_exact(&mut buf[..16]));
+
+    if buf.starts_with(b"\x89PNG\r\n\x1a\n") {
+        map_err!(format, reader.read_exact(&mut buf[16..]));
+        if &buf[12..16] != b"IHDR" {
+            return Err(ImError::ParserError(format));
+        }
+        let w = u32::from_be_bytes(array4!(buf, 16));
+        let h = u32::from_be_bytes(array4!(buf, 20));
+        return Ok((ImFormat::PNG, w, h, png_pixel_format(buf[24], buf[25])));
+    }
+
+    // BITMAPINFOHEADER, the height includes the AND mask
+    let header_size = u32::from_le_bytes(array4!(buf, 0));
+    let (w, h) = if header_size == 12 {
+        let w = u16::from_le_bytes(array2!(buf, 4)) as u32;
+        let h = u16::from_le_bytes(array2!(buf, 6)) as u32;
+        (w, h)
+    } else if header_size >= 40 {
+        let w = i32::from_le_bytes(array4!(buf, 4)).unsigned_abs();
+        let h = i32::from_le_bytes(array4!(buf, 8)).unsigned_abs();
+        (w, h)
+    } else {
+        return Err(ImError::ParserError(format));
+    };
+    let pixel_format = read_bitmap_pixel_format(reader, &buf[..16], offset, format, true)?;
+
+    return Ok((ImFormat::DIB, w, h / 2, pixel_format));
+}
+
+/// Reads the resolution from a BITMAPINFOHEADER (or any of its successors)
+/// starting at `header_offset`. The BITMAPCOREHEADER has no resolution.
+#[allow(clippy::needless_return)]
+fn read_bitmap_resolution<R>(reader: &mut R, header_offset: u64, format: ImFormat) -> ImResult<Option<ImResolution>>
+where R: Read, R: Seek {
+    let mut buf = [0u8; 8];
+    map_err!(format, reader.seek(SeekFrom::Start(header_offset)));
+    map_err!(format, reader.read_exact(&mut buf[..4]));
+    if u32::from_le_bytes(array4!(buf, 0)) < 40 {
+        return Ok(None);
+    }
+
+    // biXPelsPerMeter, biYPelsPerMeter
+    map_err!(format, reader.seek(SeekFrom::Start(header_offset + 24)));
+    map_err!(format, reader.read_exact(&mut buf));
+    let x = i32::from_le_bytes(array4!(buf, 0));
+    let y = i32::from_le_bytes(array4!(buf, 4));
+    if x <= 0 || y <= 0 {
+        return Ok(None);
+    }
+
+    return Ok(Some(ImResolution::new(x as f64, y as f64, ImResolutionUnit::Meter)));
+}
+
+/// Reads the ResolutionInfo image resource of a PSD file.
+#[allow(clippy::needless_return)]
+fn parse_psd_resolution<R>(reader: &mut R) -> ImResult<Option<ImResolution>>
+where R: Read, R: Seek {
+    let mut buf = [0u8; 16];
+
+    // skip the color mode data section
+    map_err!(PSD reader.seek(SeekFrom::Start(26)));
+    map_err!(PSD reader.read_exact(&mut buf[..4]));
+    let color_mode_size = u32::from_be_bytes(array4!(buf, 0)) as u64;
+
+    let mut offset = 30 + color_mode_size;
+    map_err!(PSD reader.seek(SeekFrom::Start(offset)));
+    map_err!(PSD reader.read_exact(&mut buf[..4]));
+    let resources_end = offset + 4 + u32::from_be_bytes(array4!(buf, 0)) as u64;
+    offset += 4;
+
+    // signature, ID, name as padded pascal string, size, data padded to even
+    while offset + 12 <= resources_end {
+        map_err!(PSD reader.seek(SeekFrom::Start(offset)));
+        map_err!(PSD reader.read_exact(&mut buf[..7]));
+        if &buf[..4] != b"8BIM" {
+            return Err(ImError::ParserError(ImFormat::PSD));
+        }
+        let id = u16::from_be_bytes(array2!(buf, 4));
+        let name_size = (buf[6] as u64 + 2) & !1;
+        map_err!(PSD reader.seek(SeekFrom::Start(offset + 6 + name_size)));
+        map_err!(PSD reader.read_exact(&mut buf[..4]));
+        let data_size = u32::from_be_bytes(array4!(buf, 0)) as u64;
+
+        if id == 0x03ED {
+            // ResolutionInfo, hRes and vRes are always in pixels per inch
+            // as 16.16 fixed point numbers, no matter the display unit
+            if data_size < 16 {
+                return Err(ImError::ParserError(ImFormat::PSD));
+            }
+            map_err!(PSD reader.read_exact(&mut buf));
+            let x = u32::from_be_bytes(array4!(buf, 0)) as f64 / 65536.0;
+            let y = u32::from_be_bytes(array4!(buf, 8)) as f64 / 65536.0;
+            if x <= 0.0 || y <= 0.0 {
+                return Ok(None);
+            }
+            return Ok(Some(ImResolution::new(x, y, ImResolutionUnit::Inch)));
+        }
+
+        offset += 6 + name_size + 4 + ((data_size + 1) & !1);
+    }
+
+    return Ok(None);
+}
+
+/// Parses the SIZ marker segment at the start of a JPEG 2000 codestream. The
+/// image area is the reference grid minus the image offset.
+#[allow(clippy::needless_return)]
+fn parse_j2k_codestream<R>(reader: &mut R) -> ImResult<ImInfo>
+where R: Read {
+    // SOC, SIZ, Lsiz
+    let mut header = [0u8; 6];
+    map_err!(JP2K reader.read_exact(&mut header));
+    if &header[..4] != b"\xff\x4f\xff\x51" {
+        return Err(ImError::ParserError(ImFormat::JP2K));
+    }
+    let siz_size = u16::from_be_bytes(array2!(header, 4)) as usize;
+    if siz_size < 41 {
+        return Err(ImError::ParserError(ImFormat::JP2K));
+    }
+
+    // Rsiz, Xsiz, Ysiz, XOsiz, YOsiz, XTsiz, YTsiz, XTOsiz, YTOsiz, Csiz,
+    // then Ssiz, XRsiz and YRsiz per component
+    let mut siz = vec![0u8; siz_size - 2];
+    map_err!(JP2K reader.read_exact(&mut siz));
+    let x_size   = u32::from_be_bytes(array4!(siz,  2));
+    let y_size   = u32::from_be_bytes(array4!(siz,  6));
+    let x_offset = u32::from_be_bytes(array4!(siz, 10));
+    let y_offset = u32::from_be_bytes(array4!(siz, 14));
+    let components = u16::from_be_bytes(array2!(siz, 34));
+
+    if x_size <= x_offset || y_size <= y_offset || components == 0 || 36 + 3 * components as usize > siz.len() {
+        return Err(ImError::ParserError(ImFormat::JP2K));
+    }
+
+    // bit depth minus 1, the top bit marks signed values
+    let bits = (0..components as usize).map(|index| (siz[36 + 3 * index] & 0x7f) as u16 + 1).max().unwrap_or(0);
+    let (color_model, color_channels) = if components <= 2 {
+        (ImColorModel::Gray, 1)
+    } else {
+        (ImColorModel::RGB, 3)
+    };
+
+    let mut info = ImInfo::new(ImFormat::JP2K, (x_size - x_offset) as u64, (y_size - y_offset) as u64);
+    info.pixel_format = Some(ImPixelFormat::new(color_model, components, bits, components > color_channels));
+    return Ok(info);
+}
+
+/// Reads the page header of the first page of a JPM file. The reader has to
+/// be after the `ftyp` box.
+#[allow(clippy::needless_return)]
+fn parse_jpm_page<R>(reader: &mut R) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    let page_size = skip_to_iso_box(reader, b"page", u64::MAX, ImFormat::JP2K)?;
+    let phdr_size = skip_to_iso_box(reader, b"phdr", page_size - 8, ImFormat::JP2K)?;
+    if phdr_size < 18 {
+        return Err(ImError::ParserError(ImFormat::JP2K));
+    }
+
+    // NC, PHeight, PWidth, POrient, PColour
+    let mut phdr = [0u8; 10];
+    map_err!(JP2K reader.read_exact(&mut phdr));
+    let h = u32::from_be_bytes(array4!(phdr, 2));
+    let w = u32::from_be_bytes(array4!(phdr, 6));
+
+    return Ok(ImInfo::new(ImFormat::JP2K, w as u64, h as u64));
+}
+
+/// Reads the `res ` box of a JPEG 2000 file. The reader has to be at the
+/// start of the payload of the `jp2h` box. Prefers the default display
+/// resolution (`resd`) over the capture resolution (`resc`).
+#[allow(clippy::needless_return)]
+fn parse_jp2_resolution<R>(reader: &mut R, jp2h_payload_size: u64) -> ImResult<Option<ImResolution>>
+where R: Read, R: Seek {
+    let res_size = match skip_to_iso_box(reader, b"res ", jp2h_payload_size, ImFormat::JP2K) {
+        Ok(res_size) => res_size,
+        Err(_) => return Ok(None)
+    };
+    if res_size > 8 + 2 * 18 + 64 {
+        return Err(ImError::ParserError(ImFormat::JP2K));
+    }
+    let mut res = vec![0u8; res_size as usize - 8];
+    map_err!(JP2K reader.read_exact(&mut res));
+
+    let mut resolution = None;
+    for res_box in iso_boxes(&res) {
+        let (box_type, data) = map_expr!(JP2K res_box.ok_or(()));
+        if (box_type != b"resd" && box_type != b"resc") || data.len() < 10 {
+            continue;
+        }
+        // grid points per meter: numerator / denominator * 10 ^ exponent
+        let y_num = u16::from_be_bytes(array2!(data, 0)) as f64;
+        let y_den = u16::from_be_bytes(array2!(data, 2)) as f64;
+        let x_num = u16::from_be_bytes(array2!(data, 4)) as f64;
+        let x_den = u16::from_be_bytes(array2!(data, 6)) as f64;
+        let y_exp = data[8] as i8 as i32;
+        let x_exp = data[9] as i8 as i32;
+        if x_num == 0.0 || x_den == 0.0 || y_num == 0.0 || y_den == 0.0 {
+            continue;
+        }
+        let x = x_num / x_den * 10f64.powi(x_exp);
+        let y = y_num / y_den * 10f64.powi(y_exp);
+        resolution = Some(ImResolution::new(x, y, ImResolutionUnit::Meter));
+        if box_type == b"resd" {
+            break;
+        }
+    }
+
+    return Ok(resolution);
+}
+
+// with the long names flag, 31 otherwise
+const EXR_MAX_NAME_SIZE: usize = 255;
+const EXR_MAX_PARTS: usize = 1024;
+const EXR_MAX_STRING_SIZE: u32 = 64 * 1024;
+// 1024 channels with the longest possible names
+const EXR_MAX_CHLIST_SIZE: u32 = 1024 * (EXR_MAX_NAME_SIZE as u32 + 1 + 16) + 1;
+
+/// Reads a null terminated attribute name or type. Returns an empty name at
+/// the end of a header.
+fn read_exr_name<R>(reader: &mut R, name: &mut Vec<u8>) -> ImResult<()>
+where R: Read {
+    let mut buf1 = [0u8];
+    name.clear();
+    loop {
+        map_err!(OpenEXR reader.read_exact(&mut buf1));
+        let byte = buf1[0];
+        if byte == 0 {
+            return Ok(());
+        }
+        if name.len() >= EXR_MAX_NAME_SIZE {
+            return Err(ImError::ParserError(ImFormat::OpenEXR));
+        }
+        name.push(byte);
+    }
+}
+
+#[allow(clippy::needless_return)]
+fn read_exr_box<R>(reader: &mut R, size: u32) -> ImResult<ImExrBox>
+where R: Read {
+    if size != 16 {
+        return Err(ImError::ParserError(ImFormat::OpenEXR));
+    }
+
+    let mut box_buf = [0u8; 16];
+    map_err!(OpenEXR reader.read_exact(&mut box_buf));
+
+    let window = ImExrBox::new(
+        i32::from_le_bytes(array4!(box_buf,  0)),
+        i32::from_le_bytes(array4!(box_buf,  4)),
+        i32::from_le_bytes(array4!(box_buf,  8)),
+        i32::from_le_bytes(array4!(box_buf, 12)),
+    );
+
+    if window.width() == 0 || window.height() == 0 {
+        return Err(ImError::ParserError(ImFormat::OpenEXR));
+    }
+
+    return Ok(window);
+}
+
+#[allow(clippy::needless_return)]
+fn read_exr_string<R>(reader: &mut R, size: u32) -> ImResult<String>
+where R: Read {
+    if size > EXR_MAX_STRING_SIZE {
+        return Err(ImError::ParserError(ImFormat::OpenEXR));
+    }
+    let mut buf = Vec::new();
+    map_err!(OpenEXR reader.take(size as u64).read_to_end(&mut buf));
+    if buf.len() != size as usize {
+        return Err(ImError::ParserError(ImFormat::OpenEXR));
+    }
+    return Ok(String::from_utf8_lossy(&buf).into_owned());
+}
+
+/// Reads the attributes of one header. Returns `None` for the empty header
+/// that terminates the headers of multi-part files.
+#[allow(clippy::needless_return)]
+fn read_exr_header<R>(reader: &mut R, pixel_format: &mut Option<ImPixelFormat>) -> ImResult<Option<ImExrPart>>
+where R: Read, R: Seek {
+    let mut name_buf = Vec::new();
+    let mut type_buf = Vec::new();
+    let mut buf4 = [0u8; 4];
+    let mut name = None;
+    let mut part_type = None;
+    let mut display_window = None;
+    let mut data_window = None;
+    let mut empty = true;
+
+    loop {
+        read_exr_name(reader, &mut name_buf)?;
+        if name_buf.is_empty() {
+            break;
+        }
+        empty = false;
+
+        read_exr_name(reader, &mut type_buf)?;
+
+        map_err!(OpenEXR reader.read_exact(&mut buf4));
+        let size = u32::from_le_bytes(buf4);
+
+        match (&name_buf[..], &type_buf[..]) {
+            (b"displayWindow", b"box2i") => display_window = Some(read_exr_box(reader, size)?),
+            (b"dataWindow",    b"box2i") => data_window    = Some(read_exr_box(reader, size)?),
+            (b"displayWindow" | b"dataWindow", _) => return Err(ImError::ParserError(ImFormat::OpenEXR)),
+            (b"name", b"string") => name = Some(read_exr_string(reader, size)?),
+            (b"type", b"string") => {
+                part_type = match &read_exr_string(reader, size)?[..] {
+                    "scanlineimage" => Some(ImExrPartType::Scanline),
+                    "tiledimage"    => Some(ImExrPartType::Tiled),
+                    "deepscanline"  => Some(ImExrPartType::DeepScanline),
+                    "deeptile"      => Some(ImExrPartType::DeepTiled),
+                    _ => None,
+                };
+            }
+            (b"channels", b"chlist") if pixel_format.is_none() && size <= EXR_MAX_CHLIST_SIZE => {
+                let mut chlist = Vec::new();
+                map_err!(OpenEXR reader.take(size as u64).read_to_end(&mut chlist));
+                *pixel_format = Some(parse_exr_chlist(&chlist)?);
+            }
+            _ => {
+                map_err!(OpenEXR reader.seek(SeekFrom::Current(size as i64)));
+            }
+        }
+    }
+
+    if empty {
+        return Ok(None);
+    }
+
+    return match (display_window, data_window) {
+        (Some(display_window), Some(data_window)) => Ok(Some(ImExrPart { name, part_type, display_window, data_window })),
+        _ => Err(ImError::ParserError(ImFormat::OpenEXR))
+    };
+}
+
+/// Reads the headers of all parts. Only the channels of the first part are
+/// used for the pixel format.
+/// https://openexr.com/en/latest/OpenEXRFileLayout.html
+#[allow(clippy::needless_return)]
+fn parse_exr<R>(reader: &mut R, options: &ImOptions) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    let mut buf4 = [0u8; 4];
+    map_err!(OpenEXR reader.seek(SeekFrom::Start(4)));
+    map_err!(OpenEXR reader.read_exact(&mut buf4));
+    let flags = u32::from_le_bytes(buf4);
+    let tiled     = flags & 0x200  != 0;
+    let deep      = flags & 0x800  != 0;
+    let multipart = flags & 0x1000 != 0;
+
+    let mut pixel_format = None;
+    let mut parts = Vec::new();
+    loop {
+        let mut part = match read_exr_header(reader, &mut pixel_format)? {
+            Some(part) => part,
+            None if multipart && !parts.is_empty() => break,
+            None => return Err(ImError::ParserError(ImFormat::OpenEXR)),
+        };
+        if !multipart && part.part_type.is_none() {
+            part.part_type = Some(match (tiled, deep) {
+                (false, false) => ImExrPartType::Scanline,
+                (true,  false) => ImExrPartType::Tiled,
+                (false, true)  => ImExrPartType::DeepScanline,
+                (true,  true)  => ImExrPartType::DeepTiled,
+            });
+        }
+        parts.push(part);
+
+        if !multipart {
+            break;
+        }
+        if parts.len() >= EXR_MAX_PARTS {
+            return Err(ImError::ParserError(ImFormat::OpenEXR));
+        }
+    }
+
+    let display_window = parts[0].display_window;
+    let mut info = ImInfo::new(ImFormat::OpenEXR, display_window.width(), display_window.height());
+    info.pixel_format = pixel_format;
+    if options.images {
+        for (index, part) in parts.iter().enumerate() {
+            info.images.push(ImImage {
+                index,
+                width:  part.display_window.width(),
+                height: part.display_window.height(),
+                format: ImFormat::OpenEXR,
+                role:   if index == 0 { ImRole::Primary } else { ImRole::Page },
+            });
+        }
+    }
+    info.details = ImDetails::OpenEXR(ImExrInfo { tiled, deep, multipart, parts });
+    return Ok(info);
+}
+
+#[allow(clippy::needless_return)]
+fn parse_exr_chlist(chlist: &[u8]) -> ImResult<ImPixelFormat> {
+    let mut has_rgb    = false;
+    let mut has_luma   = false;
+    let mut has_chroma = false;
+    let mut has_alpha  = false;
+    let mut channels: u16 = 0;
+    let mut bits: u16 = 0;
+    let mut rest = chlist;
+
+    loop {
+        let name_len = match rest.iter().position(|&byte| byte == 0) {
+            Some(name_len) => name_len,
+            None => return Err(ImError::ParserError(ImFormat::OpenEXR))
+        };
+        if name_len == 0 {
+            break;
+        }
+        // pixel type, pLinear, reserved, xSampling, ySampling
+        if rest.len() < name_len + 17 {
+            return Err(ImError::ParserError(ImFormat::OpenEXR));
+        }
+
+        let name = &rest[..name_len];
+        // only the last component of layered channel names like "diffuse.R"
+        let name = match name.iter().rposition(|&byte| byte == b'.') {
+            Some(index) => &name[index + 1..],
+            None => name
+        };
+        match name {
+            b"R" | b"G" | b"B" => has_rgb    = true,
+            b"Y"               => has_luma   = true,
+            b"RY" | b"BY"      => has_chroma = true,
+            b"A"               => has_alpha  = true,
+            _ => {}
+        }
+
+        // UINT, HALF, FLOAT
+        let pixel_type = u32::from_le_bytes(array4!(rest, name_len + 1));
+        bits = bits.max(if pixel_type == 1 { 16 } else { 32 });
+        channels += 1;
+
+        rest = &rest[name_len + 17..];
+    }
+
+    let color_model = if has_rgb {
+        ImColorModel::RGB
+    } else if has_chroma {
+        ImColorModel::YCbCr
+    } else if has_luma {
+        ImColorModel::Gray
+    } else {
+        ImColorModel::Other
+    };
+
+    return Ok(ImPixelFormat::new(color_model, channels, bits, has_alpha));
+}
+
+fn skip_gif_sub_blocks<R>(reader: &mut R) -> ImResult<()>
+where R: Read, R: Seek {
+    let mut buf = [0u8];
+    loop {
+        map_err!(GIF reader.read_exact(&mut buf));
+        if buf[0] == 0 {
+            return Ok(());
+        }
+        map_err!(GIF reader.seek(SeekFrom::Current(buf[0] as i64)));
+    }
+}
+
+#[allow(clippy::needless_return)]
+fn parse_gif_animation<R>(reader: &mut R, flags: u8) -> ImResult<ImAnimation>
+where R: Read, R: Seek {
+    // header + logical screen descriptor
+    let mut offset = 13;
+    if flags & 0x80 != 0 {
+        // global color table
+        offset += 3 << ((flags & 0x7) + 1);
+    }
+    map_err!(GIF reader.seek(SeekFrom::Start(offset)));
+
+    let mut frames = 0;
+    let mut loop_count = None;
+    let mut buf = [0u8; 11];
+
+    loop {
+        if reader.read_exact(&mut buf[..1]).is_err() {
+            // be lenient about truncated files, but a GIF without any image
+            // is broken
+            if frames == 0 {
+                return Err(ImError::ParserError(ImFormat::GIF));
+            }
+            break;
+        }
+
+        match buf[0] {
+            0x2C => {
+                // image descriptor
+                map_err!(GIF reader.read_exact(&mut buf[..9]));
+                let image_flags = buf[8];
+                let mut skip = 1; // LZW minimum code size
+                if image_flags & 0x80 != 0 {
+                    // local color table
+                    skip += 3 << ((image_flags & 0x7) + 1);
+                }
+                map_err!(GIF reader.seek(SeekFrom::Current(skip)));
+                skip_gif_sub_blocks(reader)?;
+                frames += 1;
+            }
+            0x21 => {
+                // extension
+                map_err!(GIF reader.read_exact(&mut buf[..2]));
+                let label = buf[0];
+                let block_size = buf[1];
+                if label == 0xFF && block_size == 11 {
+                    // application extension
+                    map_err!(GIF reader.read_exact(&mut buf));
+                    if &buf == b"NETSCAPE2.0" || &buf == b"ANIMEXTS1.0" {
+                        map_err!(GIF reader.read_exact(&mut buf[..4]));
+                        let sub_block_size = buf[0];
+                        if sub_block_size >= 3 && buf[1] == 1 {
+                            loop_count = Some(u16::from_le_bytes(array2!(buf, 2)) as u32);
+                        }
+                        map_err!(GIF reader.seek(SeekFrom::Current(sub_block_size as i64 - 3)));
+                    }
+                } else {
+                    map_err!(GIF reader.seek(SeekFrom::Current(block_size as i64)));
+                }
+                skip_gif_sub_blocks(reader)?;
+            }
+            0x3B => break, // trailer
+            _ => return Err(ImError::ParserError(ImFormat::GIF))
+        }
+    }
+
+    return Ok(ImAnimation { frames, loop_count });
+}
+
+fn parse_png_animation<R>(reader: &mut R, mut offset: u64) -> ImResult<ImAnimation>
+where R: Read, R: Seek {
+    let mut buf = [0u8; 8];
+
+    // acTL has to come before the first IDAT chunk
+    loop {
+        map_err!(PNG reader.seek(SeekFrom::Start(offset)));
+        map_err!(PNG reader.read_exact(&mut buf));
+        let chunk_size = u32::from_be_bytes(array4!(buf, 0));
+
+        match &buf[4..8] {
+            b"acTL" => {
+                if chunk_size < 8 {
+                    return Err(ImError::ParserError(ImFormat::PNG));
+                }
+                map_err!(PNG reader.read_exact(&mut buf));
+                let frames    = u32::from_be_bytes(array4!(buf, 0));
+                let num_plays = u32::from_be_bytes(array4!(buf, 4));
+                return Ok(ImAnimation {
+                    frames: frames as u64,
+                    loop_count: Some(num_plays),
+                });
+            }
+            b"IDAT" | b"IEND" => {
+                return Ok(ImAnimation { frames: 1, loop_count: None });
+            }
+            _ => {}
+        }
+
+        offset += 12 + chunk_size as u64;
+    }
+}
+
+fn parse_png_resolution<R>(reader: &mut R, mut offset: u64) -> ImResult<Option<ImResolution>>
+where R: Read, R: Seek {
+    let mut buf = [0u8; 9];
+
+    // pHYs has to come before the first IDAT chunk
+    loop {
+        map_err!(PNG reader.seek(SeekFrom::Start(offset)));
+        map_err!(PNG reader.read_exact(&mut buf[..8]));
+        let chunk_size = u32::from_be_bytes(array4!(buf, 0));
+
+        match &buf[4..8] {
+            b"pHYs" => {
+                if chunk_size < 9 {
+                    return Err(ImError::ParserError(ImFormat::PNG));
+                }
+                map_err!(PNG reader.read_exact(&mut buf));
+                let x = u32::from_be_bytes(array4!(buf, 0));
+                let y = u32::from_be_bytes(array4!(buf, 4));
+                if x == 0 || y == 0 {
+                    return Ok(None);
+                }
+                let unit = if buf[8] == 1 { ImResolutionUnit::Meter } else { ImResolutionUnit::Unknown };
+                return Ok(Some(ImResolution::new(x as f64, y as f64, unit)));
+            }
+            b"IDAT" | b"IEND" => {
+                return Ok(None);
+            }
+            _ => {}
+        }
+
+        offset += 12 + chunk_size as u64;
+    }
+}
+
+const PNG_MAX_CHUNKS: usize = 4096;
+
+/// Lists the ancillary chunks (lower case first letter) from `offset` up to
+/// `IEND` or the end of the file, at most [PNG_MAX_CHUNKS].
+#[allow(clippy::needless_return)]
+fn parse_png_chunks<R>(reader: &mut R, mut offset: u64) -> ImResult<Vec<ImPngChunk>>
+where R: Read, R: Seek {
+    let mut chunks = Vec::new();
+    let mut buf = [0u8; 8];
+
+    while chunks.len() < PNG_MAX_CHUNKS {
+        map_err!(PNG reader.seek(SeekFrom::Start(offset)));
+        if reader.read_exact(&mut buf).is_err() {
+            // truncated file
+            break;
+        }
+        let chunk_size = u32::from_be_bytes(array4!(buf, 0));
+        if chunk_size > 0x7FFF_FFFF {
+            return Err(ImError::ParserError(ImFormat::PNG));
+        }
+        if &buf[4..8] == b"IEND" {
+            break;
+        }
+        if buf[4] & 0x20 != 0 {
+            chunks.push(ImPngChunk {
+                chunk_type: array4!(buf, 4),
+                size: chunk_size,
+            });
+        }
+
+        offset += 12 + chunk_size as u64;
+    }
+
+    return Ok(chunks);
+}
+
+/// CRC-32 as used by PNG. Bitwise, because only small chunks are checked.
+#[allow(clippy::needless_return)]
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    return !crc;
+}
+
+#[allow(clippy::needless_return)]
+fn parse_png<R>(reader: &mut R, preamble: &[u8], options: &ImOptions) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    let mut png_info = ImPngInfo::default();
+    let mut ihdr_offset = 8;
+    if &preamble[12..16] == b"CgBI" {
+        // Apple's CgBI chunk comes before IHDR
+        png_info.cgbi = true;
+        ihdr_offset += 12 + u32::from_be_bytes(array4!(preamble, 8)) as u64;
+    }
+
+    // size, type, data and CRC, which might be truncated in lenient mode
+    let mut ihdr = [0u8; 25];
+    let mut ihdr_len = 0;
+    if !png_info.cgbi && !options.strict {
+        // the preamble has everything up to the interlace method, only the
+        // CRC is missing
+        ihdr_len = (preamble.len() - 8).min(ihdr.len());
+        ihdr[..ihdr_len].copy_from_slice(&preamble[8..8 + ihdr_len]);
+    } else {
+        map_err!(PNG reader.seek(SeekFrom::Start(ihdr_offset)));
+        while ihdr_len < ihdr.len() {
+            let count = map_expr!(PNG reader.read(&mut ihdr[ihdr_len..]));
+            if count == 0 {
+                break;
+            }
+            ihdr_len += count;
+        }
+    }
+    if ihdr_len < 16 {
+        return Err(ImError::ParserError(ImFormat::PNG));
+    }
+
+    let chunk_size = u32::from_be_bytes(array4!(ihdr, 0));
+    if chunk_size < 8 || &ihdr[4..8] != b"IHDR" {
+        return Err(ImError::ParserError(ImFormat::PNG));
+    }
+    if options.strict && (chunk_size != 13 || ihdr_len < 25 || crc32(&ihdr[4..21]) != u32::from_be_bytes(array4!(ihdr, 21))) {
+        return Err(ImError::ParserError(ImFormat::PNG));
+    }
+
+    let w = u32::from_be_bytes(array4!(ihdr, 8));
+    let h = u32::from_be_bytes(array4!(ihdr, 12));
+
+    let mut info = ImInfo::new(ImFormat::PNG, w as u64, h as u64);
+    if ihdr_len >= 18 {
+        info.pixel_format = png_pixel_format(ihdr[16], ihdr[17]);
+    }
+    if ihdr_len >= 21 {
+        // bit depth, color type, compression method, filter method,
+        // interlace method
+        if options.strict && ihdr[20] > 1 {
+            return Err(ImError::ParserError(ImFormat::PNG));
+        }
+        png_info.interlaced = ihdr[20] == 1;
+    }
+
+    let next_chunk = ihdr_offset + 12 + chunk_size as u64;
+    if options.animation {
+        info.animation = Some(parse_png_animation(reader, next_chunk)?);
+    }
+    if options.resolution {
+        info.resolution = parse_png_resolution(reader, next_chunk)?;
+    }
+    if options.chunks {
+        png_info.chunks = parse_png_chunks(reader, next_chunk)?;
+    }
+    info.details = ImDetails::PNG(png_info);
+    return Ok(info);
+}
+
+/// The header of a RIFF chunk. `offset` is the file offset of the payload.
+struct RiffChunk {
+    fourcc: [u8; 4],
+    offset: u64,
+    size:   u64,
+}
+
+/// Walks the chunks between `offset` and `end` of a RIFF file or of a `LIST`
+/// chunk. Sizes are little endian and chunks are padded to an even size.
+struct RiffChunks {
+    offset: u64,
+    end:    u64,
+}
+
+impl RiffChunks {
     #[inline]
-    fn read_uratio(reader: &mut impl Read) -> std::io::Result<(u32, u32)> {
+    #[allow(clippy::needless_return)]
+    fn new(offset: u64, end: u64) -> Self {
+        return RiffChunks { offset, end };
+    }
+
+    /// Reads the header of the next chunk and leaves the reader at its
+    /// payload. Returns `None` at the end of the list or of a truncated file.
+    #[allow(clippy::needless_return)]
+    fn next_chunk<R>(&mut self, reader: &mut R) -> Option<RiffChunk>
+    where R: Read, R: Seek {
+        if self.offset.checked_add(8)? > self.end {
+            return None;
+        }
         let mut buf = [0u8; 8];
-        reader.read_exact(&mut buf)?;
-        return Ok((
-            u32::from_be_bytes([ buf[0], buf[1], buf[2], buf[3] ]),
-            u32::from_be_bytes([ buf[4], buf[5], buf[6], buf[7] ]),
-        ));
+        reader.seek(SeekFrom::Start(self.offset)).ok()?;
+        reader.read_exact(&mut buf).ok()?;
+        let size = u32::from_le_bytes(array4!(buf, 4)) as u64;
+        let chunk = RiffChunk {
+            fourcc: array4!(buf, 0),
+            offset: self.offset + 8,
+            size,
+        };
+        self.offset = chunk.offset + size + (size & 1);
+        return Some(chunk);
+    }
+}
+
+/// The frame header of a `VP8 ` or `VP8L` chunk.
+#[derive(Clone, Copy)]
+struct WebpBitstream {
+    width:  u64,
+    height: u64,
+    pixel_format: ImPixelFormat,
+    horizontal_scale: u8,
+    vertical_scale:   u8,
+}
+
+/// Reads the frame header of a `VP8 ` or `VP8L` chunk. `alpha_flag` is the
+/// alpha flag of the `VP8X` chunk, if there is one.
+#[allow(clippy::needless_return)]
+fn parse_webp_bitstream<R>(reader: &mut R, chunk: &RiffChunk, alpha_flag: Option<bool>) -> ImResult<WebpBitstream>
+where R: Read {
+    let mut buf = [0u8; 10];
+    if &chunk.fourcc == b"VP8L" {
+        if chunk.size < 5 {
+            return Err(ImError::ParserError(ImFormat::WEBP));
+        }
+        map_err!(WEBP reader.read_exact(&mut buf[..5]));
+        if buf[0] != 0x2f {
+            return Err(ImError::ParserError(ImFormat::WEBP));
+        }
+        let b1 = buf[1] as u64;
+        let b2 = buf[2] as u64;
+        let b3 = buf[3] as u64;
+        let b4 = buf[4] as u64;
+
+        let width  = 1 + (((b2 & 0x3F) << 8) | b1);
+        let height = 1 + (((b4 & 0xF) << 10) | (b3 << 2) | ((b2 & 0xC0) >> 6));
+        // the alpha_is_used bit is only a hint
+        let has_alpha = alpha_flag.unwrap_or(b4 & 0x10 != 0);
+
+        return Ok(WebpBitstream {
+            width,
+            height,
+            pixel_format: ImPixelFormat::new(ImColorModel::RGB, if has_alpha { 4 } else { 3 }, 8, has_alpha),
+            horizontal_scale: 0,
+            vertical_scale:   0,
+        });
+    }
+
+    if chunk.size < 10 {
+        return Err(ImError::ParserError(ImFormat::WEBP));
+    }
+    map_err!(WEBP reader.read_exact(&mut buf));
+    // frame tag, the lowest bit is 0 for keyframes (i.e. intra frames),
+    // followed by the start code
+    if buf[0] & 0x01 != 0 || &buf[3..6] != b"\x9d\x01\x2a" {
+        return Err(ImError::ParserError(ImFormat::WEBP));
+    }
+    // 14 bit sizes, the upper 2 bits are the scaling factors
+    let w = u16::from_le_bytes(array2!(buf, 6));
+    let h = u16::from_le_bytes(array2!(buf, 8));
+    // the alpha of lossy images is in an ALPH chunk, which needs VP8X
+    let pixel_format = if alpha_flag == Some(true) {
+        ImPixelFormat::new(ImColorModel::YCbCr, 4, 8, true)
+    } else {
+        ImPixelFormat::new(ImColorModel::YCbCr, 3, 8, false)
+    };
+
+    return Ok(WebpBitstream {
+        width:  (w & 0x3fff) as u64,
+        height: (h & 0x3fff) as u64,
+        pixel_format,
+        horizontal_scale: (w >> 14) as u8,
+        vertical_scale:   (h >> 14) as u8,
+    });
+}
+
+/// WebP files are RIFF files with either a single `VP8 ` or `VP8L` chunk
+/// (simple format) or a `VP8X` chunk first, which has the canvas size and
+/// the feature flags (extended format). Chunks in unexpected order or of
+/// unknown type are skipped.
+#[allow(clippy::needless_return)]
+fn parse_webp<R>(reader: &mut R, preamble: &[u8], options: &ImOptions) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    let riff_end = 8 + u32::from_le_bytes(array4!(preamble, 4)) as u64;
+    let mut chunks = RiffChunks::new(12, riff_end);
+    let mut buf = [0u8; 10];
+    let mut canvas = None;
+    let mut bitstream = None;
+    let mut webp_info = ImWebpInfo::default();
+
+    while let Some(chunk) = chunks.next_chunk(reader) {
+        match &chunk.fourcc {
+            b"VP8X" if canvas.is_none() => {
+                if chunk.size < 10 {
+                    return Err(ImError::ParserError(ImFormat::WEBP));
+                }
+                map_err!(WEBP reader.read_exact(&mut buf));
+                // feature flags: reserved (2 bits), ICC, alpha, EXIF, XMP,
+                // animation, reserved (1 bit)
+                let flags = buf[0];
+                webp_info.icc       = flags & 0x20 != 0;
+                webp_info.alpha     = flags & 0x10 != 0;
+                webp_info.exif      = flags & 0x08 != 0;
+                webp_info.xmp       = flags & 0x04 != 0;
+                webp_info.animation = flags & 0x02 != 0;
+                canvas = Some((get_u24_le(&buf, 4) as u64 + 1, get_u24_le(&buf, 7) as u64 + 1));
+                if webp_info.animation {
+                    // the bitstreams are in the ANMF chunks
+                    break;
+                }
+            }
+            b"VP8 " | b"VP8L" => {
+                match parse_webp_bitstream(reader, &chunk, canvas.map(|_| webp_info.alpha)) {
+                    Ok(header) => bitstream = Some(header),
+                    // with a VP8X chunk the bitstream is only needed for the
+                    // pixel format
+                    Err(error) if canvas.is_none() => return Err(error),
+                    Err(_) => {}
+                }
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let mut info = match (canvas, bitstream) {
+        (Some((width, height)), _) => ImInfo::new(ImFormat::WEBP, width, height),
+        (None, Some(header)) => ImInfo::new(ImFormat::WEBP, header.width, header.height),
+        (None, None) => return Err(ImError::ParserError(ImFormat::WEBP)),
+    };
+    if let Some(header) = bitstream {
+        info.pixel_format = Some(header.pixel_format);
+        webp_info.horizontal_scale = header.horizontal_scale;
+        webp_info.vertical_scale   = header.vertical_scale;
+        if canvas.is_none() {
+            webp_info.alpha = header.pixel_format.has_alpha;
+        }
+    }
+
+    if options.animation {
+        info.animation = Some(if webp_info.animation {
+            parse_webp_animation(reader, riff_end, &mut webp_info.frames)?
+        } else {
+            ImAnimation { frames: 1, loop_count: None }
+        });
+    }
+    if options.orientation && webp_info.exif {
+        info.orientation = parse_webp_orientation(reader, riff_end)?;
+    }
+    info.details = ImDetails::WebP(webp_info);
+    return Ok(info);
+}
+
+const WEBP_MAX_FRAMES: usize = 4096;
+
+/// Counts the `ANMF` chunks of an animated WebP file and collects the
+/// position and size of the first [WEBP_MAX_FRAMES] frames.
+#[allow(clippy::needless_return)]
+fn parse_webp_animation<R>(reader: &mut R, riff_end: u64, frame_list: &mut Vec<ImWebpFrame>) -> ImResult<ImAnimation>
+where R: Read, R: Seek {
+    let mut chunks = RiffChunks::new(12, riff_end);
+    let mut frames = 0;
+    let mut loop_count = None;
+    let mut buf = [0u8; 12];
+
+    while let Some(chunk) = chunks.next_chunk(reader) {
+        match &chunk.fourcc {
+            b"ANIM" => {
+                if chunk.size < 6 {
+                    return Err(ImError::ParserError(ImFormat::WEBP));
+                }
+                // background color + loop count
+                map_err!(WEBP reader.read_exact(&mut buf[..6]));
+                loop_count = Some(u16::from_le_bytes(array2!(buf, 4)) as u32);
+            }
+            b"ANMF" => {
+                frames += 1;
+                if chunk.size >= 12 && frame_list.len() < WEBP_MAX_FRAMES {
+                    // offsets divided by 2, sizes minus 1, all 24 bit
+                    map_err!(WEBP reader.read_exact(&mut buf));
+                    frame_list.push(ImWebpFrame {
+                        x:      get_u24_le(&buf, 0) * 2,
+                        y:      get_u24_le(&buf, 3) * 2,
+                        width:  get_u24_le(&buf, 6) + 1,
+                        height: get_u24_le(&buf, 9) + 1,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    return Ok(ImAnimation { frames, loop_count });
+}
+
+/// Iterates over the boxes in an in-memory ISO-BMFF box payload, yielding
+/// the box type and its payload.
+struct IsoBoxes<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Iterator for IsoBoxes<'a> {
+    type Item = Option<(&'a [u8], &'a [u8])>;
+
+    #[allow(clippy::needless_return)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let data = self.data;
+        let offset = self.offset;
+        if offset >= data.len() {
+            return None;
+        }
+        if offset + 8 > data.len() {
+            self.offset = data.len();
+            return Some(None);
+        }
+        let mut header_size = 8;
+        let mut box_size = u32::from_be_bytes(array4!(data, offset)) as u64;
+        if box_size == 1 {
+            // largesize
+            if offset + 16 > data.len() {
+                self.offset = data.len();
+                return Some(None);
+            }
+            header_size = 16;
+            box_size = u64::from_be_bytes(array8!(data, offset + 8));
+        } else if box_size == 0 {
+            // box extends to the end of its parent
+            box_size = (data.len() - offset) as u64;
+        }
+        if box_size < header_size as u64 || box_size > (data.len() - offset) as u64 {
+            self.offset = data.len();
+            return Some(None);
+        }
+        let end = offset + box_size as usize;
+        self.offset = end;
+        return Some(Some((&data[offset + 4..offset + 8], &data[offset + header_size..end])));
+    }
+}
+
+#[inline]
+#[allow(clippy::needless_return)]
+fn iso_boxes(data: &[u8]) -> IsoBoxes<'_> {
+    return IsoBoxes { data, offset: 0 };
+}
+
+/// Reads a big endian item ID of 2 or 4 bytes at `offset`.
+#[inline]
+#[allow(clippy::needless_return)]
+fn read_item_id(data: &[u8], offset: usize, large: bool) -> Option<u32> {
+    if large {
+        if offset + 4 > data.len() {
+            return None;
+        }
+        return Some(u32::from_be_bytes(array4!(data, offset)));
+    } else {
+        if offset + 2 > data.len() {
+            return None;
+        }
+        return Some(u16::from_be_bytes(array2!(data, offset)) as u32);
+    }
+}
+
+/// Reads a big endian unsigned integer of 0, 4 or 8 bytes at `offset`, as
+/// used by the `iloc` box.
+#[inline]
+#[allow(clippy::needless_return)]
+fn read_iloc_value(data: &[u8], offset: usize, size: usize) -> Option<u64> {
+    return match size {
+        0 => Some(0),
+        4 if offset + 4 <= data.len() => Some(u32::from_be_bytes(array4!(data, offset)) as u64),
+        8 if offset + 8 <= data.len() => Some(u64::from_be_bytes(array8!(data, offset))),
+        _ => None
+    };
+}
+
+const HEIF_MAX_FTYP_SIZE: u64 = 4096;
+const HEIF_MAX_META_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Determines the format from the brands of the `ftyp` box. The major brand
+/// is checked first, the generic `mif1` and `msf1` brands only mark HEIF
+/// files if there isn't a more specific brand.
+#[allow(clippy::needless_return)]
+fn heif_format(brands: &[u8]) -> Option<ImFormat> {
+    let mut format = None;
+    for brand in brands.chunks_exact(4) {
+        match brand {
+            b"avif" | b"avis" | b"avio" => return Some(ImFormat::AVIF),
+            b"heic" | b"heix" | b"heim" | b"heis" | b"hevc" | b"hevx" | b"hevm" | b"hevs" => return Some(ImFormat::HEIF),
+            b"mif1" | b"msf1" | b"mif2" => format = Some(ImFormat::HEIF),
+            _ => {}
+        }
+    }
+    return format;
+}
+
+/// The location of the data of an item as defined in the `iloc` box. Only
+/// the first extent is recorded.
+struct HeifLocation {
+    /// 0: file offset, 1: offset into the `idat` box
+    construction_method: u8,
+    offset: u64,
+    length: u64,
+}
+
+/// Item properties of a HEIF/AVIF file that are relevant to this library.
+enum HeifProperty {
+    /// `ispe`: width and height
+    Size(u32, u32),
+    /// `clap`: width and height of the clean aperture, rounded to pixels
+    CleanAperture(u32, u32),
+    /// `irot`: anti-clockwise rotation in multiples of 90 degrees
+    Rotation(u8),
+    /// `imir`: 0 mirrors about the vertical axis, 1 about the horizontal axis
+    Mirror(u8),
+    Other,
+}
+
+/// The item structure of a HEIF/AVIF file as defined in its `meta` box.
+struct HeifMeta {
+    primary: Option<u32>,
+    items: Vec<(u32, [u8; 4])>,
+    thumbnails: std::collections::HashSet<u32>,
+    auxiliaries: std::collections::HashSet<u32>,
+    tiles: std::collections::HashSet<u32>,
+    properties: Vec<HeifProperty>,
+    associations: std::collections::HashMap<u32, Vec<u32>>,
+    locations: std::collections::HashMap<u32, HeifLocation>,
+    idat: Vec<u8>,
+}
+
+impl HeifMeta {
+    /// Iterates over the properties of an item in association order.
+    #[allow(clippy::needless_return)]
+    fn item_properties(&self, item_id: u32) -> impl Iterator<Item = &HeifProperty> {
+        let indices = match self.associations.get(&item_id) {
+            Some(indices) => &indices[..],
+            None => &[],
+        };
+        // property indices are 1-based, 0 means no property
+        return indices.iter().filter_map(move |&index| {
+            if index == 0 { None } else { self.properties.get(index as usize - 1) }
+        });
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn item_type(&self, item_id: u32) -> Option<&[u8; 4]> {
+        return self.items.iter().find(|(id, _)| *id == item_id).map(|(_, item_type)| item_type);
+    }
+
+    /// The size of an item as given by its `ispe` property.
+    #[allow(clippy::needless_return)]
+    fn item_size(&self, item_id: u32) -> Option<(u32, u32)> {
+        for property in self.item_properties(item_id) {
+            if let HeifProperty::Size(w, h) = property {
+                return Some(self.crop_item(item_id, (*w, *h)));
+            }
+        }
+        return None;
+    }
+
+    /// Applies the `clap` property of an item to its size.
+    #[allow(clippy::needless_return)]
+    fn crop_item(&self, item_id: u32, size: (u32, u32)) -> (u32, u32) {
+        for property in self.item_properties(item_id) {
+            if let HeifProperty::CleanAperture(w, h) = property {
+                return ((*w).min(size.0), (*h).min(size.1));
+            }
+        }
+        return size;
+    }
+
+    /// Combines the `irot` and `imir` properties of an item, which are
+    /// applied in association order.
+    #[allow(clippy::needless_return)]
+    fn item_orientation(&self, item_id: u32) -> Option<ImOrientation> {
+        let mut orientation = None;
+        for property in self.item_properties(item_id) {
+            let current = orientation.unwrap_or(ImOrientation::TopLeft);
+            match property {
+                HeifProperty::Rotation(angle) => {
+                    orientation = Some(current.rotate_cw(4 - (angle & 3)));
+                }
+                HeifProperty::Mirror(axis) => {
+                    let mirrored = current.mirror_horizontal();
+                    orientation = Some(if axis & 1 == 0 { mirrored } else { mirrored.rotate_cw(2) });
+                }
+                _ => {}
+            }
+        }
+        return orientation;
+    }
+
+    /// Lists the image items. Items are given in the order of the `iinf` box,
+    /// except that the primary item always comes first. Items without an
+    /// `ispe` property (e.g. Exif or XMP metadata) are skipped.
+    #[allow(clippy::needless_return)]
+    fn images(&self, format: ImFormat, primary_size: Option<(u64, u64)>) -> Vec<ImImage> {
+        let mut images = Vec::new();
+        if let Some((w, h)) = primary_size {
+            images.push(ImImage {
+                index:  0,
+                width:  w,
+                height: h,
+                format,
+                role:   ImRole::Primary,
+            });
+        }
+
+        for &(item_id, item_type) in &self.items {
+            if Some(item_id) == self.primary || &item_type == b"Exif" || &item_type == b"mime" || &item_type == b"uri " {
+                continue;
+            }
+            if let Some((w, h)) = self.item_size(item_id) {
+                let role = if self.thumbnails.contains(&item_id) {
+                    ImRole::Thumbnail
+                } else if self.auxiliaries.contains(&item_id) {
+                    ImRole::Auxiliary
+                } else if self.tiles.contains(&item_id) {
+                    ImRole::Tile
+                } else {
+                    ImRole::Page
+                };
+                images.push(ImImage {
+                    index:  images.len(),
+                    width:  w as u64,
+                    height: h as u64,
+                    format,
+                    role,
+                });
+            }
+        }
+
+        return images;
+    }
+}
+
+/// Parses the payload of the `meta` box of a HEIF/AVIF file.
+#[allow(clippy::needless_return)]
+fn parse_heif_meta(meta: &[u8], format: ImFormat) -> ImResult<HeifMeta> {
+    // skip full box version and flags
+    if meta.len() < 4 {
+        return Err(ImError::ParserError(format));
+    }
+
+    let mut heif_meta = HeifMeta {
+        primary: None,
+        items: Vec::new(),
+        thumbnails: std::collections::HashSet::new(),
+        auxiliaries: std::collections::HashSet::new(),
+        tiles: std::collections::HashSet::new(),
+        properties: Vec::new(),
+        associations: std::collections::HashMap::new(),
+        locations: std::collections::HashMap::new(),
+        idat: Vec::new(),
+    };
+
+    for meta_box in iso_boxes(&meta[4..]) {
+        let (box_type, data) = map_expr!(format, meta_box.ok_or(()));
+        if data.len() < 4 {
+            continue;
+        }
+        let version = data[0];
+        match box_type {
+            b"pitm" => {
+                heif_meta.primary = read_item_id(data, 4, version != 0);
+            }
+            b"iinf" => {
+                let entries_offset = if version == 0 { 6 } else { 8 };
+                if entries_offset > data.len() {
+                    return Err(ImError::ParserError(format));
+                }
+                for infe in iso_boxes(&data[entries_offset..]) {
+                    let (infe_type, infe) = map_expr!(format, infe.ok_or(()));
+                    // only item info entries version 2 and 3 have an item type
+                    if infe_type != b"infe" || infe.len() < 4 || infe[0] < 2 {
+                        continue;
+                    }
+                    let large = infe[0] != 2;
+                    let type_offset = if large { 10 } else { 8 };
+                    if type_offset + 4 > infe.len() {
+                        return Err(ImError::ParserError(format));
+                    }
+                    let item_id = map_expr!(format, read_item_id(infe, 4, large).ok_or(()));
+                    heif_meta.items.push((item_id, array4!(infe, type_offset)));
+                }
+            }
+            b"iref" => {
+                let large = version != 0;
+                for reference in iso_boxes(&data[4..]) {
+                    let (ref_type, reference) = map_expr!(format, reference.ok_or(()));
+                    let id_size = if large { 4 } else { 2 };
+                    let from_id = map_expr!(format, read_item_id(reference, 0, large).ok_or(()));
+                    if id_size + 2 > reference.len() {
+                        return Err(ImError::ParserError(format));
+                    }
+                    let count = u16::from_be_bytes(array2!(reference, id_size)) as usize;
+                    match ref_type {
+                        b"thmb" => { heif_meta.thumbnails.insert(from_id); }
+                        b"auxl" => { heif_meta.auxiliaries.insert(from_id); }
+                        b"dimg" => {
+                            for index in 0..count {
+                                let offset = id_size + 2 + index * id_size;
+                                let to_id = map_expr!(format, read_item_id(reference, offset, large).ok_or(()));
+                                heif_meta.tiles.insert(to_id);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            b"iprp" => {
+                // iprp is not a full box
+                for iprp_box in iso_boxes(data) {
+                    let (iprp_type, iprp_data) = map_expr!(format, iprp_box.ok_or(()));
+                    match iprp_type {
+                        b"ipco" => {
+                            for property in iso_boxes(iprp_data) {
+                                let (property_type, property) = map_expr!(format, property.ok_or(()));
+                                heif_meta.properties.push(match property_type {
+                                    b"ispe" if property.len() >= 12 => HeifProperty::Size(
+                                        u32::from_be_bytes(array4!(property, 4)),
+                                        u32::from_be_bytes(array4!(property, 8))),
+                                    b"clap" if property.len() >= 16 => {
+                                        let width_n  = u32::from_be_bytes(array4!(property,  0));
+                                        let width_d  = u32::from_be_bytes(array4!(property,  4));
+                                        let height_n = u32::from_be_bytes(array4!(property,  8));
+                                        let height_d = u32::from_be_bytes(array4!(property, 12));
+                                        if width_d == 0 || height_d == 0 {
+                                            HeifProperty::Other
+                                        } else {
+                                            HeifProperty::CleanAperture(
+                                                (width_n  as f64 / width_d  as f64).round() as u32,
+                                                (height_n as f64 / height_d as f64).round() as u32)
+                                        }
+                                    }
+                                    // irot and imir are not full boxes
+                                    b"irot" if !property.is_empty() => HeifProperty::Rotation(property[0] & 3),
+                                    b"imir" if !property.is_empty() => HeifProperty::Mirror(property[0] & 1),
+                                    _ => HeifProperty::Other,
+                                });
+                            }
+                        }
+                        b"ipma" => {
+                            if iprp_data.len() < 8 {
+                                return Err(ImError::ParserError(format));
+                            }
+                            let large_id = iprp_data[0] != 0;
+                            let large_index = iprp_data[3] & 1 != 0;
+                            let entry_count = u32::from_be_bytes(array4!(iprp_data, 4));
+                            let mut offset = 8;
+                            for _ in 0..entry_count {
+                                let item_id = map_expr!(format, read_item_id(iprp_data, offset, large_id).ok_or(()));
+                                offset += if large_id { 4 } else { 2 };
+                                if offset >= iprp_data.len() {
+                                    return Err(ImError::ParserError(format));
+                                }
+                                let association_count = iprp_data[offset] as usize;
+                                offset += 1;
+                                let indices = heif_meta.associations.entry(item_id).or_default();
+                                for _ in 0..association_count {
+                                    let index = if large_index {
+                                        map_expr!(format, read_item_id(iprp_data, offset, false).ok_or(())) & 0x7FFF
+                                    } else {
+                                        if offset >= iprp_data.len() {
+                                            return Err(ImError::ParserError(format));
+                                        }
+                                        iprp_data[offset] as u32 & 0x7F
+                                    };
+                                    offset += if large_index { 2 } else { 1 };
+                                    indices.push(index);
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            b"iloc" => {
+                // only needed for grid images, so a broken iloc is ignored
+                heif_meta.locations = parse_heif_iloc(data, version).unwrap_or_default();
+            }
+            _ => {}
+        }
+        // idat is not a full box
+        if box_type == b"idat" {
+            heif_meta.idat = data.to_vec();
+        }
+    }
+
+    return Ok(heif_meta);
+}
+
+/// Reads the location of the first extent of each item from an `iloc` box.
+/// Returns `None` if the box is broken.
+#[allow(clippy::needless_return)]
+fn parse_heif_iloc(data: &[u8], version: u8) -> Option<std::collections::HashMap<u32, HeifLocation>> {
+    let mut locations = std::collections::HashMap::new();
+    if data.len() < 8 {
+        return None;
+    }
+    let offset_size      = (data[4] >> 4) as usize;
+    let length_size      = (data[4] & 0xF) as usize;
+    let base_offset_size = (data[5] >> 4) as usize;
+    let index_size       = if version == 1 || version == 2 { (data[5] & 0xF) as usize } else { 0 };
+    let (item_count, mut offset) = if version < 2 {
+        (u16::from_be_bytes(array2!(data, 6)) as u32, 8)
+    } else {
+        if data.len() < 10 {
+            return None;
+        }
+        (u32::from_be_bytes(array4!(data, 6)), 10)
+    };
+    for _ in 0..item_count {
+        let item_id = read_item_id(data, offset, version == 2)?;
+        offset += if version == 2 { 4 } else { 2 };
+        let mut construction_method = 0;
+        if version == 1 || version == 2 {
+            construction_method = read_item_id(data, offset, false)? as u8 & 0xF;
+            offset += 2;
+        }
+        // data reference index
+        offset += 2;
+        let base_offset = read_iloc_value(data, offset, base_offset_size)?;
+        offset += base_offset_size;
+        let extent_count = read_item_id(data, offset, false)?;
+        offset += 2;
+        for extent_index in 0..extent_count {
+            offset += index_size;
+            let extent_offset = read_iloc_value(data, offset, offset_size)?;
+            offset += offset_size;
+            let extent_length = read_iloc_value(data, offset, length_size)?;
+            offset += length_size;
+            if extent_index == 0 {
+                locations.insert(item_id, HeifLocation {
+                    construction_method,
+                    offset: base_offset.saturating_add(extent_offset),
+                    length: extent_length,
+                });
+            }
+        }
+    }
+    return Some(locations);
+}
+
+/// Reads the output size of a `grid` derived image from its item data.
+#[allow(clippy::needless_return)]
+fn read_heif_grid_size<R>(reader: &mut R, heif_meta: &HeifMeta, item_id: u32, format: ImFormat) -> ImResult<Option<(u32, u32)>>
+where R: Read, R: Seek {
+    let location = match heif_meta.locations.get(&item_id) {
+        Some(location) => location,
+        None => return Ok(None)
+    };
+
+    // version, flags, rows - 1, columns - 1, output width and height of 16
+    // or 32 bits
+    let mut grid = [0u8; 12];
+    let length = location.length.min(grid.len() as u64) as usize;
+    if length < 8 {
+        return Err(ImError::ParserError(format));
+    }
+    match location.construction_method {
+        0 => {
+            map_err!(format, reader.seek(SeekFrom::Start(location.offset)));
+            map_err!(format, reader.read_exact(&mut grid[..length]));
+        }
+        1 => {
+            let start = location.offset.min(heif_meta.idat.len() as u64) as usize;
+            let end = start + length;
+            if end > heif_meta.idat.len() {
+                return Err(ImError::ParserError(format));
+            }
+            grid[..length].copy_from_slice(&heif_meta.idat[start..end]);
+        }
+        _ => return Ok(None)
+    }
+
+    if grid[1] & 1 == 0 {
+        return Ok(Some((
+            u16::from_be_bytes(array2!(grid, 4)) as u32,
+            u16::from_be_bytes(array2!(grid, 6)) as u32)));
+    }
+    if length < 12 {
+        return Err(ImError::ParserError(format));
+    }
+    return Ok(Some((
+        u32::from_be_bytes(array4!(grid, 4)),
+        u32::from_be_bytes(array4!(grid, 8)))));
+}
+
+/// Seeks to the payload of the first top level box of the given type,
+/// starting at `offset`. Returns the payload size. Handles 64 bit sizes and
+/// boxes extending to the end of the file.
+fn seek_iso_box<R>(reader: &mut R, mut offset: u64, name: &[u8; 4], format: ImFormat) -> ImResult<u64>
+where R: Read, R: Seek {
+    let mut buf = [0u8; 8];
+    loop {
+        map_err!(format, reader.seek(SeekFrom::Start(offset)));
+        map_err!(format, reader.read_exact(&mut buf));
+        let box_type = array4!(buf, 4);
+        let mut header_size = 8;
+        let mut box_size = u32::from_be_bytes(array4!(buf, 0)) as u64;
+        if box_size == 1 {
+            // largesize
+            map_err!(format, reader.read_exact(&mut buf));
+            header_size = 16;
+            box_size = u64::from_be_bytes(buf);
+        } else if box_size == 0 {
+            // the last box of the file
+            let end = map_expr!(format, reader.seek(SeekFrom::End(0)));
+            box_size = end.saturating_sub(offset);
+        }
+        if box_size < header_size {
+            return Err(ImError::ParserError(format));
+        }
+        if &box_type == name {
+            map_err!(format, reader.seek(SeekFrom::Start(offset + header_size)));
+            return Ok(box_size - header_size);
+        }
+        offset = map_expr!(format, offset.checked_add(box_size).ok_or(()));
+    }
+}
+
+/// Reads the size of the primary item of an AVIF or HEIF file. For derived
+/// images like grids the size of the derived image is reported, not the size
+/// of a tile. The `clap` crop box is applied.
+/// https://www.iso.org/standard/83650.html
+#[allow(clippy::needless_return)]
+fn parse_heif<R>(reader: &mut R, preamble: &[u8], options: &ImOptions) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    let ftyp_size = u32::from_be_bytes(array4!(preamble, 0)) as u64;
+
+    // major brand, minor version, compatible brands
+    let mut brands = preamble[8..12].to_vec();
+    if ftyp_size > 16 {
+        let mut compatible_brands = vec![0u8; (ftyp_size.min(HEIF_MAX_FTYP_SIZE) - 16) as usize];
+        if reader.seek(SeekFrom::Start(16)).is_ok() && reader.read_exact(&mut compatible_brands).is_ok() {
+            brands.extend_from_slice(&compatible_brands);
+        }
+    }
+    let format = match heif_format(&brands) {
+        Some(format) => format,
+        None => return Err(ImError::UnknownFormat)
+    };
+    if ftyp_size < 12 {
+        return Err(ImError::ParserError(format));
+    }
+
+    let meta_size = seek_iso_box(reader, ftyp_size, b"meta", format)?;
+    if !(4..=HEIF_MAX_META_SIZE).contains(&meta_size) {
+        return Err(ImError::ParserError(format));
+    }
+    // the item structure is spread over several boxes, so read the whole
+    // meta box
+    let mut meta = vec![0u8; meta_size as usize];
+    map_err!(format, reader.read_exact(&mut meta));
+    let heif_meta = parse_heif_meta(&meta, format)?;
+
+    let mut size = None;
+    if let Some(primary) = heif_meta.primary {
+        size = heif_meta.item_size(primary);
+        if size.is_none() && heif_meta.item_type(primary) == Some(b"grid") {
+            size = read_heif_grid_size(reader, &heif_meta, primary, format)?
+                .map(|size| heif_meta.crop_item(primary, size));
+        }
+    }
+    if size.is_none() {
+        // no primary item, use the first image size there is
+        size = heif_meta.properties.iter().find_map(|property| match property {
+            HeifProperty::Size(w, h) => Some((*w, *h)),
+            _ => None,
+        });
+    }
+    let (w, h) = map_expr!(format, size.ok_or(()));
+
+    let mut info = ImInfo::new(format, w as u64, h as u64);
+    if options.images {
+        info.images = heif_meta.images(format, Some((w as u64, h as u64)));
+    }
+    if options.orientation {
+        if let Some(primary) = heif_meta.primary {
+            info.orientation = heif_meta.item_orientation(primary);
+        }
+    }
+    return Ok(info);
+}
+
+/// ANI files are RIFF files with an `anih` chunk and a `LIST` chunk of type
+/// `fram`, which contains the frames as `icon` chunks. The frames are ICO or
+/// CUR files, or raw bitmaps without the icon flag.
+#[allow(clippy::needless_return)]
+fn parse_ani<R>(reader: &mut R, preamble: &[u8], options: &ImOptions) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    let riff_end = 8 + u32::from_le_bytes(array4!(preamble, 4)) as u64;
+    let mut chunks = RiffChunks::new(12, riff_end);
+    let mut anih = None;
+    let mut first_frame = None;
+
+    while let Some(chunk) = chunks.next_chunk(reader) {
+        match &chunk.fourcc {
+            b"anih" => {
+                if chunk.size < 36 {
+                    return Err(ImError::ParserError(ImFormat::ANI));
+                }
+                let mut buf = [0u8; 36];
+                map_err!(ANI reader.read_exact(&mut buf));
+                anih = Some(buf);
+            }
+            b"LIST" if chunk.size >= 4 => {
+                let mut list_type = [0u8; 4];
+                map_err!(ANI reader.read_exact(&mut list_type));
+                if &list_type == b"fram" {
+                    let mut frames = RiffChunks::new(chunk.offset + 4, chunk.offset + chunk.size);
+                    while let Some(frame) = frames.next_chunk(reader) {
+                        if &frame.fourcc == b"icon" {
+                            first_frame = Some(frame.offset);
+                            break;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        if anih.is_some() && first_frame.is_some() {
+            break;
+        }
     }
 
-    #[inline]
-    fn read_i16(reader: &mut impl Read) -> std::io::Result<i16> {
-        let mut buf = [0u8; 2];
-        reader.read_exact(&mut buf)?;
-        return Ok(i16::from_be_bytes(buf));
+    // cbSize, nFrames, nSteps, iWidth, iHeight, iBitCount, nPlanes,
+    // iDispRate, bfAttributes
+    let anih = map_expr!(ANI anih.ok_or(()));
+    let frames = u32::from_le_bytes(array4!(anih, 4));
+    let width  = u32::from_le_bytes(array4!(anih, 12));
+    let height = u32::from_le_bytes(array4!(anih, 16));
+    let attributes = u32::from_le_bytes(array4!(anih, 32));
+
+    let mut info = ImInfo::new(ImFormat::ANI, width as u64, height as u64);
+    // AF_ICON, the frames are ICO or CUR files
+    if attributes & 1 != 0 {
+        if let Some(offset) = first_frame {
+            let frame = read_embedded_image(reader, offset, |magic|
+                magic.starts_with(b"\0\0\x01\0") || magic.starts_with(b"\0\0\x02\0"),
+                &ImOptions::default());
+            if let Some(frame) = frame {
+                if width == 0 || height == 0 {
+                    info.width  = frame.width;
+                    info.height = frame.height;
+                }
+                info.pixel_format = frame.pixel_format;
+                if let ImDetails::CUR(hotspot) = frame.details {
+                    info.details = ImDetails::CUR(hotspot);
+                }
+            }
+        }
+    }
+    if info.width == 0 || info.height == 0 {
+        return Err(ImError::ParserError(ImFormat::ANI));
+    }
+    if options.animation {
+        // the file is played in an endless loop
+        info.animation = Some(ImAnimation { frames: frames as u64, loop_count: None });
+    }
+    return Ok(info);
+}
+
+#[allow(clippy::needless_return)]
+fn parse_webp_orientation<R>(reader: &mut R, riff_end: u64) -> ImResult<Option<ImOrientation>>
+where R: Read, R: Seek {
+    let mut chunks = RiffChunks::new(12, riff_end);
+    while let Some(chunk) = chunks.next_chunk(reader) {
+        if &chunk.fourcc == b"EXIF" {
+            // IFD0 is at the start, don't read big thumbnails
+            let mut exif = vec![0u8; chunk.size.min(WEBP_MAX_EXIF_SIZE) as usize];
+            map_err!(WEBP reader.read_exact(&mut exif));
+            return Ok(parse_exif_orientation(&exif));
+        }
+    }
+
+    return Ok(None);
+}
+
+const WEBP_MAX_EXIF_SIZE: u64 = 64 * 1024;
+
+/// Reads bits least significant bit first, as used by JPEG XL.
+struct JxlBitReader<'a> {
+    data: &'a [u8],
+    bit_offset: usize,
+}
+
+impl<'a> JxlBitReader<'a> {
+    #[allow(clippy::needless_return)]
+    fn read_bits(&mut self, count: usize) -> Option<u64> {
+        if self.bit_offset + count > self.data.len() * 8 {
+            return None;
+        }
+        let mut value = 0u64;
+        for index in 0..count {
+            let bit_offset = self.bit_offset + index;
+            let bit = (self.data[bit_offset / 8] >> (bit_offset % 8)) & 1;
+            value |= (bit as u64) << index;
+        }
+        self.bit_offset += count;
+        return Some(value);
     }
 
     #[inline]
-    fn read_i32(reader: &mut impl Read) -> std::io::Result<i32> {
-        let mut buf = [0u8; 4];
-        reader.read_exact(&mut buf)?;
-        return Ok(i32::from_be_bytes(buf));
+    #[allow(clippy::needless_return)]
+    fn read_bool(&mut self) -> Option<bool> {
+        return Some(self.read_bits(1)? != 0);
     }
 
-    #[inline]
-    fn read_iratio(reader: &mut impl Read) -> std::io::Result<(i32, i32)> {
-        let mut buf = [0u8; 8];
-        reader.read_exact(&mut buf)?;
-        return Ok((
-            i32::from_be_bytes([ buf[0], buf[1], buf[2], buf[3] ]),
-            i32::from_be_bytes([ buf[4], buf[5], buf[6], buf[7] ]),
-        ));
+    /// U32(u(9), u(13), u(18), u(30)) of the SizeHeader, plus 1
+    #[allow(clippy::needless_return)]
+    fn read_size(&mut self) -> Option<u64> {
+        let bits = match self.read_bits(2)? {
+            0 => 9,
+            1 => 13,
+            2 => 18,
+            _ => 30,
+        };
+        return Some(self.read_bits(bits)? + 1);
     }
+}
 
-    #[inline]
-    fn read_f32(reader: &mut impl Read) -> std::io::Result<f32> {
-        let mut buf = [0u8; 4];
-        reader.read_exact(&mut buf)?;
-        return Ok(f32::from_be_bytes(buf));
+/// Parses the SizeHeader (and the orientation of the ImageMetadata) of a
+/// JPEG XL codestream at the current position of the reader.
+#[allow(clippy::needless_return)]
+fn parse_jxl_codestream<R>(reader: &mut R, options: &ImOptions) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    // signature, SizeHeader (at most 69 bits) and the start of ImageMetadata
+    let mut buf = [0u8; 14];
+    let mut len = 0;
+    while len < buf.len() {
+        let count = map_expr!(JXL reader.read(&mut buf[len..]));
+        if count == 0 {
+            break;
+        }
+        len += count;
+    }
+    if len < 2 || &buf[..2] != b"\xff\x0a" {
+        return Err(ImError::ParserError(ImFormat::JXL));
     }
 
-    #[inline]
-    fn read_f64(reader: &mut impl Read) -> std::io::Result<f64> {
-        let mut buf = [0u8; 8];
-        reader.read_exact(&mut buf)?;
-        return Ok(f64::from_be_bytes(buf));
+    let mut bits = JxlBitReader { data: &buf[2..len], bit_offset: 0 };
+    let (width, height) = map_expr!(JXL parse_jxl_size_header(&mut bits).ok_or(()));
+
+    let mut info = ImInfo::new(ImFormat::JXL, width, height);
+    if options.orientation {
+        // all_default, extra_fields, orientation
+        if let Some(false) = bits.read_bool() {
+            if let Some(true) = bits.read_bool() {
+                info.orientation = bits.read_bits(3).and_then(|value| ImOrientation::from_exif(value as u16 + 1));
+            }
+        }
     }
+
+    return Ok(info);
 }
 
-macro_rules! array2 {
-    ($data:expr, $offset:expr) => {
-        [ $data[$offset], $data[$offset + 1] ]
+#[allow(clippy::needless_return)]
+fn parse_jxl_size_header(bits: &mut JxlBitReader) -> Option<(u64, u64)> {
+    let small = bits.read_bool()?;
+    let height = if small {
+        (bits.read_bits(5)? + 1) * 8
+    } else {
+        bits.read_size()?
     };
+    let width = match bits.read_bits(3)? {
+        0 if small => (bits.read_bits(5)? + 1) * 8,
+        0 => bits.read_size()?,
+        1 => height,
+        2 => height * 12 / 10,
+        3 => height * 4 / 3,
+        4 => height * 3 / 2,
+        5 => height * 16 / 9,
+        6 => height * 5 / 4,
+        _ => height * 2,
+    };
+    return Some((width, height));
 }
 
-macro_rules! array4 {
-    ($data:expr, $offset:expr) => {
-        [ $data[$offset], $data[$offset + 1], $data[$offset + 2], $data[$offset + 3] ]
-    };
+/// Seeks to the codestream in a JPEG XL container, which is either in a
+/// `jxlc` box or split into `jxlp` boxes.
+fn seek_jxl_codestream<R>(reader: &mut R) -> ImResult<()>
+where R: Read, R: Seek {
+    let mut buf = [0u8; 8];
+    loop {
+        map_err!(JXL reader.read_exact(&mut buf));
+        let mut box_size = u32::from_be_bytes(array4!(buf, 0)) as u64;
+        let mut header_size = 8;
+        if box_size == 1 {
+            // largesize
+            let mut largesize = [0u8; 8];
+            map_err!(JXL reader.read_exact(&mut largesize));
+            box_size = u64::from_be_bytes(largesize);
+            header_size = 16;
+        }
+        match &buf[4..8] {
+            b"jxlc" => return Ok(()),
+            b"jxlp" => {
+                // skip the index of the partial codestream
+                map_err!(JXL reader.seek(SeekFrom::Current(4)));
+                return Ok(());
+            }
+            _ => {}
+        }
+        // a size of 0 means the box extends to the end of the file, so there
+        // is no codestream
+        if box_size < header_size || box_size - header_size > i64::MAX as u64 {
+            return Err(ImError::ParserError(ImFormat::JXL));
+        }
+        map_err!(JXL reader.seek(SeekFrom::Current((box_size - header_size) as i64)));
+    }
 }
 
-macro_rules! map_err {
-    ($fmt:expr, $expr:expr) => {
-        if let Err(_) = $expr {
-            return Err(ImError::ParserError($fmt));
+const NETPBM_MAX_HEADER_SIZE: usize = 64 * 1024;
+const NETPBM_MAX_TOKEN_SIZE: usize = 64;
+
+/// Splits the ASCII header of Netpbm files into tokens. Tokens are separated
+/// by whitespace, comments start with `#` and end at the end of the line.
+/// Reads at most [NETPBM_MAX_HEADER_SIZE] bytes.
+struct NetpbmTokens<'a, R> {
+    reader: &'a mut R,
+    format: ImFormat,
+    header_size: usize,
+}
+
+impl<'a, R> NetpbmTokens<'a, R> where R: Read {
+    #[allow(clippy::needless_return)]
+    fn read_byte(&mut self) -> ImResult<u8> {
+        if self.header_size >= NETPBM_MAX_HEADER_SIZE {
+            return Err(ImError::ParserError(self.format));
         }
-    };
+        self.header_size += 1;
+        let mut buf = [0u8];
+        map_err!(self.format, self.reader.read_exact(&mut buf));
+        return Ok(buf[0]);
+    }
 
-    ($fmt:ident $expr:expr) => {
-        map_err!(ImFormat::$fmt, $expr);
+    #[allow(clippy::needless_return)]
+    fn next_token(&mut self) -> ImResult<Vec<u8>> {
+        let mut byte = self.read_byte()?;
+        loop {
+            if byte == b'#' {
+                while byte != b'\n' && byte != b'\r' {
+                    byte = self.read_byte()?;
+                }
+            } else if !byte.is_ascii_whitespace() {
+                break;
+            }
+            byte = self.read_byte()?;
+        }
+
+        let mut token = Vec::new();
+        // a comment may directly follow a token
+        while !byte.is_ascii_whitespace() && byte != b'#' {
+            if token.len() >= NETPBM_MAX_TOKEN_SIZE {
+                return Err(ImError::ParserError(self.format));
+            }
+            token.push(byte);
+            byte = match self.read_byte() {
+                Ok(byte) => byte,
+                // the header may end at the end of the file
+                Err(_) => break,
+            };
+        }
+        if byte == b'#' {
+            while byte != b'\n' && byte != b'\r' {
+                byte = self.read_byte()?;
+            }
+        }
+
+        return Ok(token);
+    }
+
+    #[allow(clippy::needless_return)]
+    fn next_number(&mut self) -> ImResult<u64> {
+        let token = self.next_token()?;
+        if token.is_empty() {
+            return Err(ImError::ParserError(self.format));
+        }
+        let mut value = 0u64;
+        for &digit in &token {
+            if !digit.is_ascii_digit() {
+                return Err(ImError::ParserError(self.format));
+            }
+            value = map_expr!(self.format, value.checked_mul(10)
+                .and_then(|value| value.checked_add((digit - b'0') as u64))
+                .ok_or(()));
+        }
+        return Ok(value);
+    }
+}
+
+/// Bits per channel for a Netpbm maximum value.
+#[inline]
+#[allow(clippy::needless_return)]
+fn netpbm_bits(format: ImFormat, maxval: u64) -> ImResult<u16> {
+    if maxval == 0 || maxval > 65535 {
+        return Err(ImError::ParserError(format));
+    }
+    return Ok((64 - maxval.leading_zeros()) as u16);
+}
+
+/// Parses the header of a Netpbm file. `magic` is the second byte of the
+/// magic number.
+#[allow(clippy::needless_return)]
+fn parse_netpbm<R>(reader: &mut R, magic: u8) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    let format = match magic {
+        b'1' | b'4' => ImFormat::PBM,
+        b'2' | b'5' => ImFormat::PGM,
+        b'3' | b'6' => ImFormat::PPM,
+        b'7'        => ImFormat::PAM,
+        _           => ImFormat::PFM,
     };
+    map_err!(format, reader.seek(SeekFrom::Start(2)));
+    let mut tokens = NetpbmTokens { reader, format, header_size: 0 };
+
+    if format == ImFormat::PAM {
+        let mut width  = None;
+        let mut height = None;
+        let mut depth  = None;
+        let mut maxval = None;
+        let mut tuple_type = Vec::new();
+        loop {
+            let token = tokens.next_token()?;
+            match &token[..] {
+                b"WIDTH"    => width  = Some(tokens.next_number()?),
+                b"HEIGHT"   => height = Some(tokens.next_number()?),
+                b"DEPTH"    => depth  = Some(tokens.next_number()?),
+                b"MAXVAL"   => maxval = Some(tokens.next_number()?),
+                b"TUPLTYPE" => tuple_type = tokens.next_token()?,
+                b"ENDHDR"   => break,
+                // unknown header lines are skipped token by token
+                _ => {}
+            }
+        }
+
+        let (width, height, depth, maxval) = match (width, height, depth, maxval) {
+            (Some(width), Some(height), Some(depth), Some(maxval)) => (width, height, depth, maxval),
+            _ => return Err(ImError::ParserError(format))
+        };
+        let bits = netpbm_bits(format, maxval)?;
+        let color_model = match &tuple_type[..] {
+            b"BLACKANDWHITE" | b"GRAYSCALE" | b"BLACKANDWHITE_ALPHA" | b"GRAYSCALE_ALPHA" => ImColorModel::Gray,
+            b"RGB" | b"RGB_ALPHA" => ImColorModel::RGB,
+            b"CMYK" | b"CMYK_ALPHA" => ImColorModel::CMYK,
+            _ => ImColorModel::Other,
+        };
+        let has_alpha = tuple_type.ends_with(b"_ALPHA");
+
+        let mut info = ImInfo::new(format, width, height);
+        if depth > 0 && depth <= u16::MAX as u64 {
+            info.pixel_format = Some(ImPixelFormat::new(color_model, depth as u16, bits, has_alpha));
+        }
+        return Ok(info);
+    }
+
+    let width  = tokens.next_number()?;
+    let height = tokens.next_number()?;
+    let mut info = ImInfo::new(format, width, height);
+    info.pixel_format = Some(match format {
+        ImFormat::PBM => ImPixelFormat::new(ImColorModel::Gray, 1, 1, false),
+        ImFormat::PGM => ImPixelFormat::new(ImColorModel::Gray, 1, netpbm_bits(format, tokens.next_number()?)?, false),
+        ImFormat::PPM => ImPixelFormat::new(ImColorModel::RGB,  3, netpbm_bits(format, tokens.next_number()?)?, false),
+        // the scale (and byte order) follows, samples are 32 bit floats
+        _ if magic == b'F' => ImPixelFormat::new(ImColorModel::RGB,  3, 32, false),
+        _                  => ImPixelFormat::new(ImColorModel::Gray, 1, 32, false),
+    });
+
+    return Ok(info);
 }
 
-macro_rules! map_expr {
-    ($fmt:ident $expr:expr) => {
-        match $expr {
-            Err(_) => return Err(ImError::ParserError(ImFormat::$fmt)),
-            Ok(value) => value
+const SVG_MAX_HEADER_SIZE: usize = 64 * 1024;
+
+/// Result of scanning the start of a possible SVG file.
+enum SvgScan {
+    /// More data is needed.
+    Incomplete,
+    /// The root element isn't `svg`.
+    NotSvg,
+    /// The start tag of the `svg` element is malformed.
+    Broken,
+    Svg(ImSvgInfo),
+}
+
+/// Where [scan_svg_header] stopped, so that it can continue there once more
+/// data was read.
+#[derive(Default)]
+struct SvgScanState {
+    /// Start of the first markup that isn't complete yet.
+    pos: usize,
+    /// Up to where that markup was already searched for its end.
+    scanned: usize,
+    /// The quote and internal subset state of an incomplete doctype.
+    doctype_quote: Option<u8>,
+    doctype_internal_subset: bool,
+    /// The start tag of the root element was reached.
+    root: bool,
+}
+
+/// Whether the data starts like an SVG file, i.e. with an XML declaration,
+/// a doctype, a comment or the `svg` element.
+#[allow(clippy::needless_return)]
+fn is_svg_start(data: &[u8]) -> bool {
+    let data = data.strip_prefix(b"\xef\xbb\xbf").unwrap_or(data);
+    let start = data.iter().position(|byte| !byte.is_ascii_whitespace()).unwrap_or(data.len());
+    let data = &data[start..];
+    return data.starts_with(b"<?xml") || data.starts_with(b"<!--") || data.starts_with(b"<!DOCTYPE") || data.starts_with(b"<svg");
+}
+
+#[inline]
+#[allow(clippy::needless_return)]
+fn find_bytes(data: &[u8], needle: &[u8]) -> Option<usize> {
+    return data.windows(needle.len()).position(|window| window == needle);
+}
+
+/// Length of a doctype declaration, including a possible internal subset.
+/// Continues at `start` with the state the previous call left in `state`.
+#[allow(clippy::needless_return)]
+fn svg_doctype_size(data: &[u8], start: usize, state: &mut SvgScanState) -> Option<usize> {
+    for (index, &byte) in data.iter().enumerate().skip(start) {
+        match state.doctype_quote {
+            Some(quote_byte) if byte == quote_byte => state.doctype_quote = None,
+            Some(_) => {}
+            None => match byte {
+                b'"' | b'\'' => state.doctype_quote = Some(byte),
+                b'[' => state.doctype_internal_subset = true,
+                b']' => state.doctype_internal_subset = false,
+                b'>' if !state.doctype_internal_subset => return Some(index + 1),
+                _ => {}
+            }
         }
-    };
+    }
+    return None;
 }
 
-fn find_riff_chunk<R>(reader: &mut R, name: &[u8; 4], chunk_size: u64, format: ImFormat) -> ImResult<u64>
-where R: Read, R: Seek {
-    let mut sub_chunk_size;
-    let mut buf = [0u8; 8];
-    let mut offset = 0;
+/// Scans the prolog of an XML file up to the start tag of the root element.
+/// `eof` is set if `data` is the whole file. When more data is needed the
+/// next call with the extended `data` continues where `state` says this call
+/// stopped.
+fn scan_svg_header(data: &[u8], state: &mut SvgScanState, eof: bool) -> SvgScan {
+    let incomplete = if eof { SvgScan::NotSvg } else { SvgScan::Incomplete };
+    if data.starts_with(b"\xef\xbb\xbf") {
+        state.pos = state.pos.max(3);
+    }
+    loop {
+        while state.pos < data.len() && data[state.pos].is_ascii_whitespace() {
+            state.pos += 1;
+        }
+        let rest = &data[state.pos..];
+        if !eof && rest.len() < b"<!DOCTYPE".len() {
+            return SvgScan::Incomplete;
+        }
+        // the end marker might start in the already searched data
+        let scanned = state.scanned.saturating_sub(state.pos);
+
+        let size = if rest.starts_with(b"<?") {
+            let start = scanned.saturating_sub(1).max(2);
+            find_bytes(&rest[start..], b"?>").map(|end| start + end + 2)
+        } else if rest.starts_with(b"<!--") {
+            let start = scanned.saturating_sub(2).max(4);
+            find_bytes(&rest[start..], b"-->").map(|end| start + end + 3)
+        } else if rest.starts_with(b"<!DOCTYPE") {
+            svg_doctype_size(rest, scanned, state)
+        } else if rest.starts_with(b"<") {
+            state.root = true;
+            // only parse the start tag again if it might be complete now
+            if !eof && scanned > 0 && !rest[scanned..].contains(&b'>') {
+                state.scanned = data.len();
+                return SvgScan::Incomplete;
+            }
+            let result = scan_svg_root(&rest[1..], eof);
+            state.scanned = data.len();
+            return result;
+        } else {
+            return SvgScan::NotSvg;
+        };
+
+        match size {
+            Some(size) => {
+                state.pos += size;
+                state.scanned = 0;
+                state.doctype_quote = None;
+                state.doctype_internal_subset = false;
+            }
+            None => {
+                state.scanned = data.len();
+                return incomplete;
+            }
+        }
+    }
+}
+
+/// Reads the size attributes of the root element, `tag` starts with the
+/// element name.
+fn scan_svg_root(tag: &[u8], eof: bool) -> SvgScan {
+    let is_name_end = |byte: u8| byte.is_ascii_whitespace() || byte == b'>' || byte == b'/' || byte == b'=';
+    let name_len = match tag.iter().position(|&byte| is_name_end(byte)) {
+        Some(name_len) => name_len,
+        None => return if eof { SvgScan::NotSvg } else { SvgScan::Incomplete }
+    };
+    // the element might have a namespace prefix
+    let name = &tag[..name_len];
+    if name != b"svg" && !name.ends_with(b":svg") {
+        return SvgScan::NotSvg;
+    }
 
+    let incomplete = if eof { SvgScan::Broken } else { SvgScan::Incomplete };
+    let mut info = ImSvgInfo { width: None, height: None, view_box: None };
+    let mut pos = name_len;
     loop {
-        if offset > chunk_size {
-            return Err(ImError::ParserError(format));
+        while pos < tag.len() && tag[pos].is_ascii_whitespace() {
+            pos += 1;
         }
-        if let Err(_) = reader.read_exact(&mut buf) {
-            return Err(ImError::ParserError(format));
+        if pos >= tag.len() {
+            return incomplete;
         }
-        sub_chunk_size = u32::from_be_bytes(array4!(&buf, 0)) as u64;
-        if sub_chunk_size < 8 {
-            return Err(ImError::ParserError(format));
+        if tag[pos] == b'>' || tag[pos] == b'/' {
+            return SvgScan::Svg(info);
         }
-        if &buf[4..8] == name {
-            break;
+
+        let name_start = pos;
+        while pos < tag.len() && !is_name_end(tag[pos]) {
+            pos += 1;
         }
-        offset += sub_chunk_size;
-        if let Err(_) = reader.seek(SeekFrom::Current(sub_chunk_size as i64 - 8)) {
-            return Err(ImError::ParserError(format));
+        let name = &tag[name_start..pos];
+        while pos < tag.len() && tag[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if pos >= tag.len() {
+            return incomplete;
+        }
+        if tag[pos] != b'=' {
+            return SvgScan::Broken;
+        }
+        pos += 1;
+        while pos < tag.len() && tag[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if pos >= tag.len() {
+            return incomplete;
+        }
+        let quote = tag[pos];
+        if quote != b'"' && quote != b'\'' {
+            return SvgScan::Broken;
+        }
+        pos += 1;
+        let value_len = match tag[pos..].iter().position(|&byte| byte == quote) {
+            Some(value_len) => value_len,
+            None => return incomplete
+        };
+        let value = &tag[pos..pos + value_len];
+        pos += value_len + 1;
+
+        match name {
+            b"width"   => info.width    = parse_svg_length(value),
+            b"height"  => info.height   = parse_svg_length(value),
+            b"viewBox" => info.view_box = parse_svg_view_box(value),
+            _ => {}
         }
     }
+}
 
-    return Ok(sub_chunk_size);
+/// Length of the number at the start of `data`, without a unit.
+#[allow(clippy::needless_return)]
+fn svg_number_size(data: &[u8]) -> usize {
+    let digits = |pos: usize| data[pos..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+    let mut pos = 0;
+    if pos < data.len() && (data[pos] == b'+' || data[pos] == b'-') {
+        pos += 1;
+    }
+    pos += digits(pos);
+    if pos < data.len() && data[pos] == b'.' {
+        pos += 1;
+        pos += digits(pos);
+    }
+    // an exponent, but not the unit em or ex
+    if pos < data.len() && (data[pos] == b'e' || data[pos] == b'E') {
+        let mut exponent_pos = pos + 1;
+        if exponent_pos < data.len() && (data[exponent_pos] == b'+' || data[exponent_pos] == b'-') {
+            exponent_pos += 1;
+        }
+        let exponent_digits = digits(exponent_pos.min(data.len()));
+        if exponent_digits > 0 {
+            pos = exponent_pos + exponent_digits;
+        }
+    }
+    return pos;
 }
 
-fn parse_tiff<BR, R>(reader: &mut R, preamble: &[u8]) -> ImResult<ImInfo>
-where BR: BinaryReader, R: Read, R: Seek {
-    let ifd_offset = BR::get_u32(array4!(preamble, 4));
-    map_err!(TIFF reader.seek(SeekFrom::Start(ifd_offset as u64)));
+#[allow(clippy::needless_return)]
+fn parse_svg_number(data: &[u8]) -> Option<f64> {
+    let value: f64 = std::str::from_utf8(data).ok()?.parse().ok()?;
+    if !value.is_finite() {
+        return None;
+    }
+    return Some(value);
+}
 
-    let ifd_entry_count = map_expr!(TIFF BR::read_u16(reader)) as u32;
-    // 2 bytes: TagId + 2 bytes: type + 4 bytes: count of values + 4
-    // bytes: value offset
-    let mut width:  Option<u64> = None;
-    let mut height: Option<u64> = None;
+#[allow(clippy::needless_return)]
+fn parse_svg_length(value: &[u8]) -> Option<ImSvgLength> {
+    let start = value.iter().position(|byte| !byte.is_ascii_whitespace())?;
+    let end   = value.iter().rposition(|byte| !byte.is_ascii_whitespace())? + 1;
+    let value = &value[start..end];
 
-    for index in 0..ifd_entry_count {
-        // sizeof ifd_entry_count = 2
-        let entry_offset = ifd_offset + 2 + index * 12;
-        map_err!(TIFF reader.seek(SeekFrom::Start(entry_offset as u64)));
-        let tag = map_expr!(TIFF BR::read_u16(reader));
-
-        // 256 ... width
-        // 257 ... height
-        if tag == 256 || tag == 257 {
-            // if type indicates that value fits into 4 bytes, value
-            // offset is not an offset but value itself
-            let ftype = map_expr!(TIFF BR::read_u16(reader));
-            map_err!(TIFF reader.seek(SeekFrom::Start(entry_offset as u64 + 8)));
-            let value: u64 = match ftype {
-                 1 => map_expr!(TIFF BR::read_u8(reader)).into(),
-                 2 => map_expr!(TIFF BR::read_uchar(reader)).into(),
-                 3 => map_expr!(TIFF BR::read_u16(reader)).into(),
-                 4 => map_expr!(TIFF BR::read_u32(reader)).into(),
-                 5 => map_expr!(TIFF BR::read_uratio(reader)).value::<u64>(),
-                 6 => map_expr!(TIFF BR::read_i8(reader)).max(0) as u64,
-                 7 => map_expr!(TIFF BR::read_ichar(reader)).max(0) as u64,
-                 8 => map_expr!(TIFF BR::read_i16(reader)).max(0) as u64,
-                 9 => map_expr!(TIFF BR::read_i32(reader)).max(0) as u64,
-                10 => map_expr!(TIFF BR::read_iratio(reader)).value::<i64>().max(0) as u64,
-                11 => map_expr!(TIFF BR::read_f32(reader)) as u64,
-                12 => map_expr!(TIFF BR::read_f64(reader)) as u64,
-                _ => return Err(ImError::ParserError(ImFormat::TIFF))
-            };
+    let number_size = svg_number_size(value);
+    let number = parse_svg_number(&value[..number_size])?;
+    if number < 0.0 {
+        return None;
+    }
+    let unit = match &value[number_size..] {
+        b""   => ImSvgUnit::None,
+        b"px" => ImSvgUnit::Px,
+        b"pt" => ImSvgUnit::Pt,
+        b"pc" => ImSvgUnit::Pc,
+        b"mm" => ImSvgUnit::Mm,
+        b"cm" => ImSvgUnit::Cm,
+        b"in" => ImSvgUnit::In,
+        b"em" => ImSvgUnit::Em,
+        b"ex" => ImSvgUnit::Ex,
+        b"%"  => ImSvgUnit::Percent,
+        _ => return None
+    };
+    return Some(ImSvgLength::new(number, unit));
+}
 
-            if tag == 256 {
-                if let Some(height) = height {
-                    return Ok(ImInfo {
-                        format: ImFormat::TIFF,
-                        width: value,
-                        height,
-                    });
-                }
-                width = Some(value);
-            } else {
-                if let Some(width) = width {
-                    return Ok(ImInfo {
-                        format: ImFormat::TIFF,
-                        width,
-                        height: value,
-                    });
+#[allow(clippy::needless_return)]
+fn parse_svg_view_box(value: &[u8]) -> Option<ImSvgViewBox> {
+    let mut numbers = value
+        .split(|&byte| byte.is_ascii_whitespace() || byte == b',')
+        .filter(|number| !number.is_empty())
+        .map(parse_svg_number);
+    let x      = numbers.next()??;
+    let y      = numbers.next()??;
+    let width  = numbers.next()??;
+    let height = numbers.next()??;
+    if numbers.next().is_some() || width < 0.0 || height < 0.0 {
+        return None;
+    }
+    return Some(ImSvgViewBox { x, y, width, height });
+}
+
+/// Reads the prolog and the root start tag of a possible SVG file, at most
+/// [SVG_MAX_HEADER_SIZE] bytes.
+fn parse_svg<R>(reader: &mut R) -> ImResult<ImInfo>
+where R: Read {
+    let mut data = Vec::new();
+    let mut state = SvgScanState::default();
+    loop {
+        let old_len = data.len();
+        data.resize(old_len + 1024, 0);
+        let count = map_expr!(SVG reader.read(&mut data[old_len..]));
+        data.truncate(old_len + count);
+
+        match scan_svg_header(&data, &mut state, count == 0) {
+            SvgScan::Svg(svg_info) => {
+                let (width, height) = svg_info.pixel_size().unwrap_or((0.0, 0.0));
+                let mut info = ImInfo::new(ImFormat::SVG, width.round() as u64, height.round() as u64);
+                info.details = ImDetails::SVG(svg_info);
+                return Ok(info);
+            }
+            SvgScan::NotSvg => return Err(ImError::UnknownFormat),
+            SvgScan::Broken => return Err(ImError::ParserError(ImFormat::SVG)),
+            SvgScan::Incomplete => {
+                if data.len() >= SVG_MAX_HEADER_SIZE {
+                    // without an svg start tag it might be any XML file
+                    if state.root {
+                        return Err(ImError::ParserError(ImFormat::SVG));
+                    }
+                    return Err(ImError::UnknownFormat);
                 }
-                height = Some(value);
             }
         }
     }
+}
+
+#[cfg(feature = "svgz")]
+#[inline]
+#[allow(clippy::needless_return)]
+fn parse_svgz<R>(reader: &mut R) -> ImResult<ImInfo>
+where R: Read {
+    return parse_svg(&mut flate2::read::GzDecoder::new(reader));
+}
+
+#[cfg(not(feature = "svgz"))]
+#[inline]
+#[allow(clippy::needless_return)]
+fn parse_svgz<R>(_reader: &mut R) -> ImResult<ImInfo>
+where R: Read {
+    return Err(ImError::UnknownFormat);
+}
+
+const HDR_MAX_HEADER_SIZE: usize = 64 * 1024;
+
+/// Maps the axes of a Radiance resolution string to the orientation of the
+/// scanlines. The standard `-Y H +X W` means top to bottom, left to right.
+/// If the X axis comes first the scanlines are columns.
+#[allow(clippy::needless_return)]
+fn hdr_orientation(first: &[u8], second: &[u8]) -> Option<ImOrientation> {
+    return match (first, second) {
+        (b"-Y", b"+X") => Some(ImOrientation::TopLeft),
+        (b"-Y", b"-X") => Some(ImOrientation::TopRight),
+        (b"+Y", b"-X") => Some(ImOrientation::BottomRight),
+        (b"+Y", b"+X") => Some(ImOrientation::BottomLeft),
+        (b"+X", b"-Y") => Some(ImOrientation::LeftTop),
+        (b"-X", b"-Y") => Some(ImOrientation::RightTop),
+        (b"-X", b"+Y") => Some(ImOrientation::RightBottom),
+        (b"+X", b"+Y") => Some(ImOrientation::LeftBottom),
+        _ => None
+    };
+}
+
+/// Parses the header lines up to the empty line and the resolution string
+/// following it. Reads at most [HDR_MAX_HEADER_SIZE] bytes.
+#[allow(clippy::needless_return)]
+fn parse_hdr<R>(reader: &mut R, options: &ImOptions) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    map_err!(HDR reader.seek(SeekFrom::Start(0)));
+    let mut data = Vec::new();
+    let resolution = loop {
+        let old_len = data.len();
+        data.resize(old_len + 1024, 0);
+        let count = map_expr!(HDR reader.read(&mut data[old_len..]));
+        data.truncate(old_len + count);
+
+        if let Some(index) = data.windows(2).position(|window| window == b"\n\n") {
+            let start = index + 2;
+            if let Some(end) = data[start..].iter().position(|&byte| byte == b'\n') {
+                break &data[start..start + end];
+            } else if count == 0 {
+                break &data[start..];
+            }
+        } else if count == 0 {
+            return Err(ImError::ParserError(ImFormat::HDR));
+        }
+
+        if data.len() >= HDR_MAX_HEADER_SIZE {
+            return Err(ImError::ParserError(ImFormat::HDR));
+        }
+    };
+
+    let mut tokens = resolution.split(|byte| byte.is_ascii_whitespace()).filter(|token| !token.is_empty());
+    let mut next_token = || tokens.next().ok_or(ImError::ParserError(ImFormat::HDR));
+    let first_axis  = next_token()?;
+    let first_size  = next_token()?;
+    let second_axis = next_token()?;
+    let second_size = next_token()?;
+
+    let orientation = map_expr!(HDR hdr_orientation(first_axis, second_axis).ok_or(()));
+    let parse_size = |token: &[u8]| {
+        return std::str::from_utf8(token).ok().and_then(|token| token.parse::<u64>().ok()).ok_or(ImError::ParserError(ImFormat::HDR));
+    };
+
+    // the first axis is the number of scanlines, the second the length of them
+    let mut info = ImInfo::new(ImFormat::HDR, parse_size(second_size)?, parse_size(first_size)?);
+    if options.orientation {
+        info.orientation = Some(orientation);
+    }
+    return Ok(info);
+}
+
+const TEXTURE_MAX_IMAGES: usize = 4096;
+
+/// Lists the mipmap levels of all layers and faces of a texture. Faces are
+/// counted as layers, i.e. layer 6 is the first face of the second cube map.
+#[allow(clippy::needless_return)]
+fn texture_images(format: ImFormat, width: u64, height: u64, texture: &ImTextureInfo) -> Vec<ImImage> {
+    let mut images = Vec::new();
+    let layers = texture.layers.max(1) as u64 * texture.faces.max(1) as u64;
+    for layer in 0..layers {
+        for level in 0..texture.mip_levels.clamp(1, 64) {
+            if images.len() >= TEXTURE_MAX_IMAGES {
+                return images;
+            }
+            images.push(ImImage {
+                index:  images.len(),
+                width:  (width  >> level).max(1),
+                height: (height >> level).max(1),
+                format,
+                role:   ImRole::MipLevel { layer: layer as u32, level },
+            });
+        }
+    }
+    return images;
+}
+
+#[allow(clippy::needless_return)]
+fn texture_info(format: ImFormat, width: u64, height: u64, texture: ImTextureInfo, options: &ImOptions) -> ImInfo {
+    let mut info = ImInfo::new(format, width, height);
+    if options.images {
+        info.images = texture_images(format, width, height, &texture);
+    }
+    info.details = ImDetails::Texture(texture);
+    return info;
+}
+
+/// Pixel format of uncompressed OpenGL textures.
+#[allow(clippy::needless_return)]
+fn gl_pixel_format(gl_type: u32, gl_format: u32) -> Option<ImPixelFormat> {
+    let bits = match gl_type {
+        // BYTE, UNSIGNED_BYTE
+        0x1400 | 0x1401 => 8,
+        // SHORT, UNSIGNED_SHORT, HALF_FLOAT
+        0x1402 | 0x1403 | 0x140B => 16,
+        // INT, UNSIGNED_INT, FLOAT
+        0x1404..=0x1406 => 32,
+        // compressed (0) or packed formats
+        _ => return None
+    };
+    let (color_model, channels, has_alpha) = match gl_format {
+        // RED, RED_INTEGER, LUMINANCE
+        0x1903 | 0x8D94 | 0x1909 => (ImColorModel::Gray, 1, false),
+        // ALPHA
+        0x1906 => (ImColorModel::Other, 1, true),
+        // LUMINANCE_ALPHA
+        0x190A => (ImColorModel::Gray, 2, true),
+        // RG, RG_INTEGER
+        0x8227 | 0x8228 => (ImColorModel::Other, 2, false),
+        // RGB, BGR, RGB_INTEGER
+        0x1907 | 0x80E0 | 0x8D98 => (ImColorModel::RGB, 3, false),
+        // RGBA, BGRA, RGBA_INTEGER
+        0x1908 | 0x80E1 | 0x8D99 => (ImColorModel::RGB, 4, true),
+        _ => return None
+    };
+    return Some(ImPixelFormat::new(color_model, channels, bits, has_alpha));
+}
+
+/// Pixel format of uncompressed Vulkan formats with 8, 16 or 32 bits per
+/// channel.
+#[allow(clippy::needless_return)]
+fn vk_pixel_format(vk_format: u32) -> Option<ImPixelFormat> {
+    let (color_model, channels, bits, has_alpha) = match vk_format {
+        // R8_*
+          9..=15  => (ImColorModel::Gray,  1,  8, false),
+        // R8G8_*
+         16..=22  => (ImColorModel::Other, 2,  8, false),
+        // R8G8B8_*, B8G8R8_*
+         23..=36  => (ImColorModel::RGB,   3,  8, false),
+        // R8G8B8A8_*, B8G8R8A8_*, A8B8G8R8_*_PACK32
+         37..=57  => (ImColorModel::RGB,   4,  8, true),
+        // R16_*
+         70..=76  => (ImColorModel::Gray,  1, 16, false),
+        // R16G16_*
+         77..=83  => (ImColorModel::Other, 2, 16, false),
+        // R16G16B16_*
+         84..=90  => (ImColorModel::RGB,   3, 16, false),
+        // R16G16B16A16_*
+         91..=97  => (ImColorModel::RGB,   4, 16, true),
+        // R32_*
+         98..=100 => (ImColorModel::Gray,  1, 32, false),
+        // R32G32_*
+        101..=103 => (ImColorModel::Other, 2, 32, false),
+        // R32G32B32_*
+        104..=106 => (ImColorModel::RGB,   3, 32, false),
+        // R32G32B32A32_*
+        107..=109 => (ImColorModel::RGB,   4, 32, true),
+        _ => return None
+    };
+    return Some(ImPixelFormat::new(color_model, channels, bits, has_alpha));
+}
+
+/// Pixel format of uncompressed DXGI formats.
+#[allow(clippy::needless_return)]
+fn dxgi_pixel_format(dxgi_format: u32) -> Option<ImPixelFormat> {
+    let (color_model, channels, bits, has_alpha) = match dxgi_format {
+        // R32G32B32A32_*
+          2..=5   => (ImColorModel::RGB,   4, 32, true),
+        // R32G32B32_*
+          6..=9   => (ImColorModel::RGB,   3, 32, false),
+        // R16G16B16A16_*
+         10..=14  => (ImColorModel::RGB,   4, 16, true),
+        // R32G32_*
+         15..=18  => (ImColorModel::Other, 2, 32, false),
+        // R10G10B10A2_*
+         23..=25  => (ImColorModel::RGB,   4, 10, true),
+        // R8G8B8A8_*
+         27..=32  => (ImColorModel::RGB,   4,  8, true),
+        // R16G16_*
+         33..=38  => (ImColorModel::Other, 2, 16, false),
+        // R32_FLOAT, R32_UINT, R32_SINT
+         41..=43  => (ImColorModel::Gray,  1, 32, false),
+        // R8G8_*
+         48..=52  => (ImColorModel::Other, 2,  8, false),
+        // R16_FLOAT, R16_UNORM, R16_UINT, R16_SNORM, R16_SINT
+         54 | 56..=59 => (ImColorModel::Gray, 1, 16, false),
+        // R8_*
+         60..=64  => (ImColorModel::Gray,  1,  8, false),
+        // A8_UNORM
+         65       => (ImColorModel::Other, 1,  8, true),
+        // B5G6R5_UNORM
+         85       => (ImColorModel::RGB,   3,  6, false),
+        // B5G5R5A1_UNORM
+         86       => (ImColorModel::RGB,   4,  5, true),
+        // B8G8R8A8_UNORM, B8G8R8A8_TYPELESS, B8G8R8A8_UNORM_SRGB
+         87 | 90 | 91 => (ImColorModel::RGB, 4, 8, true),
+        // B8G8R8X8_UNORM, B8G8R8X8_TYPELESS, B8G8R8X8_UNORM_SRGB
+         88 | 92 | 93 => (ImColorModel::RGB, 3, 8, false),
+        // B4G4R4A4_UNORM
+        115       => (ImColorModel::RGB,   4,  4, true),
+        // block compressed, video and depth formats
+        _ => return None
+    };
+    return Some(ImPixelFormat::new(color_model, channels, bits, has_alpha));
+}
+
+#[allow(clippy::needless_return)]
+fn parse_ktx<R>(reader: &mut R, options: &ImOptions) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    let mut header = [0u8; 64];
+    map_err!(KTX reader.seek(SeekFrom::Start(0)));
+    map_err!(KTX reader.read_exact(&mut header));
+
+    // the endianness field is 0x04030201 in the byte order of the writer
+    let big_endian = match &header[12..16] {
+        [1, 2, 3, 4] => false,
+        [4, 3, 2, 1] => true,
+        _ => return Err(ImError::ParserError(ImFormat::KTX))
+    };
+    let field = |index: usize| {
+        let bytes = array4!(header, 16 + index * 4);
+        if big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) }
+    };
+
+    let gl_type    = field(0);
+    let gl_format  = field(2);
+    let width      = field(5);
+    // 0 for 1D and 2D textures, 0 array elements for no array and 0 mipmap
+    // levels if they are to be generated
+    let height     = field(6).max(1);
+    let depth      = field(7).max(1);
+    let layers     = field(8).max(1);
+    let faces      = field(9).max(1);
+    let mip_levels = field(10).max(1);
+
+    let texture = ImTextureInfo::new(depth as u64, layers, faces, mip_levels);
+    let mut info = texture_info(ImFormat::KTX, width as u64, height as u64, texture, options);
+    info.pixel_format = gl_pixel_format(gl_type, gl_format);
+    return Ok(info);
+}
+
+#[allow(clippy::needless_return)]
+fn parse_ktx2<R>(reader: &mut R, options: &ImOptions) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    let mut header = [0u8; 48];
+    map_err!(KTX2 reader.seek(SeekFrom::Start(0)));
+    map_err!(KTX2 reader.read_exact(&mut header));
+
+    let vk_format  = u32::from_le_bytes(array4!(header, 12));
+    let width      = u32::from_le_bytes(array4!(header, 20));
+    let height     = u32::from_le_bytes(array4!(header, 24)).max(1);
+    let depth      = u32::from_le_bytes(array4!(header, 28)).max(1);
+    let layers     = u32::from_le_bytes(array4!(header, 32)).max(1);
+    let faces      = u32::from_le_bytes(array4!(header, 36)).max(1);
+    let mip_levels = u32::from_le_bytes(array4!(header, 40)).max(1);
+
+    let texture = ImTextureInfo::new(depth as u64, layers, faces, mip_levels);
+    let mut info = texture_info(ImFormat::KTX2, width as u64, height as u64, texture, options);
+    // VK_FORMAT_UNDEFINED for Basis Universal supercompression
+    info.pixel_format = vk_pixel_format(vk_format);
+    return Ok(info);
+}
+
+#[allow(clippy::needless_return)]
+fn parse_pvr<R>(reader: &mut R, options: &ImOptions) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    let mut header = [0u8; 52];
+    map_err!(PVR reader.seek(SeekFrom::Start(0)));
+    map_err!(PVR reader.read_exact(&mut header));
+
+    let height     = u32::from_le_bytes(array4!(header, 24));
+    let width      = u32::from_le_bytes(array4!(header, 28));
+    let depth      = u32::from_le_bytes(array4!(header, 32)).max(1);
+    let layers     = u32::from_le_bytes(array4!(header, 36)).max(1);
+    let faces      = u32::from_le_bytes(array4!(header, 40)).max(1);
+    let mip_levels = u32::from_le_bytes(array4!(header, 44)).max(1);
+
+    let texture = ImTextureInfo::new(depth as u64, layers, faces, mip_levels);
+    let mut info = texture_info(ImFormat::PVR, width as u64, height as u64, texture, options);
+
+    // the upper 4 bytes of uncompressed pixel formats are the bits per
+    // channel, the lower 4 bytes the channel names, e.g. 'r', 'g', 'b', 'a'
+    let channel_names = array4!(header, 8);
+    let channel_bits  = array4!(header, 12);
+    if channel_bits != [0; 4] {
+        let channels = channel_names.iter().filter(|&&name| name != 0).count() as u16;
+        let bits = *channel_bits.iter().max().unwrap_or(&0) as u16;
+        let has_alpha = channel_names.contains(&b'a');
+        let color_model = if channel_names.contains(&b'r') && channel_names.contains(&b'g') && channel_names.contains(&b'b') {
+            ImColorModel::RGB
+        } else if channel_names.contains(&b'l') || channel_names.contains(&b'i') {
+            ImColorModel::Gray
+        } else {
+            ImColorModel::Other
+        };
+        if channels > 0 {
+            info.pixel_format = Some(ImPixelFormat::new(color_model, channels, bits, has_alpha));
+        }
+    }
+    return Ok(info);
+}
+
+const BASIS_HEADER_SIZE: usize = 77;
+const BASIS_SLICE_DESC_SIZE: usize = 23;
+const BASIS_MAX_SLICES: u32 = 4096;
 
-    return Err(ImError::ParserError(ImFormat::TIFF));
+#[inline]
+#[allow(clippy::needless_return)]
+fn get_u24_le(data: &[u8], offset: usize) -> u32 {
+    return u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], 0]);
+}
+
+/// Basis Universal files consist of slices, one per mipmap level of every
+/// image (and another one for alpha). The size is only given per slice.
+#[allow(clippy::needless_return)]
+fn parse_basis<R>(reader: &mut R, options: &ImOptions) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    let mut header = [0u8; BASIS_HEADER_SIZE];
+    map_err!(BASIS reader.seek(SeekFrom::Start(0)));
+    map_err!(BASIS reader.read_exact(&mut header));
+
+    let total_slices    = get_u24_le(&header, 14);
+    let total_images    = get_u24_le(&header, 17).max(1);
+    let texture_type    = header[23];
+    let slice_desc_offset = u32::from_le_bytes(array4!(header, 65));
+
+    // the slices of image 0 come first
+    let mut size = None;
+    let mut mip_levels = 0;
+    let mut slice = [0u8; BASIS_SLICE_DESC_SIZE];
+    map_err!(BASIS reader.seek(SeekFrom::Start(slice_desc_offset as u64)));
+    for _ in 0..total_slices.min(BASIS_MAX_SLICES) {
+        map_err!(BASIS reader.read_exact(&mut slice));
+        let image_index = get_u24_le(&slice, 0);
+        let level_index = slice[3] as u32;
+        if image_index != 0 {
+            break;
+        }
+        if level_index == 0 {
+            let w = u16::from_le_bytes(array2!(slice, 5));
+            let h = u16::from_le_bytes(array2!(slice, 7));
+            size = Some((w as u64, h as u64));
+        }
+        mip_levels = mip_levels.max(level_index + 1);
+    }
+    let (width, height) = map_expr!(BASIS size.ok_or(()));
+
+    let texture = match texture_type {
+        // cube map array
+        2 => ImTextureInfo::new(1, (total_images / 6).max(1), 6, mip_levels),
+        // volume
+        4 => ImTextureInfo::new(total_images as u64, 1, 1, mip_levels),
+        // 2D, 2D array, video frames
+        _ => ImTextureInfo::new(1, total_images, 1, mip_levels),
+    };
+    return Ok(texture_info(ImFormat::BASIS, width, height, texture, options));
 }
 
 #[inline]
+#[allow(clippy::needless_return)]
 fn is_tga<R>(file: &mut R) -> std::io::Result<bool>
 where R: Read, R: Seek {
     file.seek(SeekFrom::End(-18))?;
@@ -533,110 +5388,223 @@ where R: Read, R: Seek {
 /// Trait to provide generic [imsz()] function for paths, buffers, and readers.
 pub trait Imsz {
     fn imsz(self) -> ImResult<ImInfo>;
+
+    /// Read whatever is requested by `options` in addition to the width and
+    /// height. The default implementation ignores `options`.
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, _options: &ImOptions) -> ImResult<ImInfo> where Self: Sized {
+        return self.imsz();
+    }
 }
 
 impl Imsz for &str {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return imsz_from_path(self);
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_path_with_options(self, options);
     }
 }
 
 impl Imsz for &String {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return imsz_from_path(self.as_str());
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_path_with_options(self.as_str(), options);
     }
 }
 
 impl Imsz for String {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return imsz_from_path(self.as_str());
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_path_with_options(self.as_str(), options);
     }
 }
 
 impl Imsz for &std::ffi::OsStr {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return imsz_from_path(self);
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_path_with_options(self, options);
     }
 }
 
 impl Imsz for &std::ffi::OsString {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return imsz_from_path(self.as_os_str());
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_path_with_options(self.as_os_str(), options);
     }
 }
 
 impl Imsz for std::ffi::OsString {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return imsz_from_path(self.as_os_str());
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_path_with_options(self.as_os_str(), options);
     }
 }
 
 impl Imsz for &std::path::Path {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return imsz_from_path(self);
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_path_with_options(self, options);
     }
 }
 
 impl Imsz for &std::path::PathBuf {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return imsz_from_path(self.as_path());
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_path_with_options(self.as_path(), options);
     }
 }
 
 impl Imsz for std::path::PathBuf {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return imsz_from_path(self.as_path());
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_path_with_options(self.as_path(), options);
     }
 }
 
 impl Imsz for &[u8] {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return imsz_from_reader(&mut std::io::Cursor::new(self));
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_reader_with_options(&mut std::io::Cursor::new(self), options);
     }
 }
 
 impl<const LEN: usize> Imsz for [u8; LEN] {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return imsz_from_reader(&mut std::io::Cursor::new(&self[..]));
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_reader_with_options(&mut std::io::Cursor::new(&self[..]), options);
     }
 }
 
 impl<const LEN: usize> Imsz for &[u8; LEN] {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return imsz_from_reader(&mut std::io::Cursor::new(&self[..]));
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_reader_with_options(&mut std::io::Cursor::new(&self[..]), options);
     }
 }
 
 impl Imsz for &mut std::fs::File {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return imsz_from_reader(&mut BufReader::new(self));
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_reader_with_options(&mut BufReader::new(self), options);
     }
 }
 
 impl Imsz for std::fs::File {
     #[inline]
-    fn imsz(mut self) -> ImResult<ImInfo> {
-        return imsz_from_reader(&mut BufReader::new(&mut self));
+    #[allow(clippy::needless_return)]
+    fn imsz(self) -> ImResult<ImInfo> {
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(mut self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_reader_with_options(&mut BufReader::new(&mut self), options);
     }
 }
 
 impl Imsz for std::io::Stdin {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return (&self).imsz();
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return (&self).imsz_with_options(options);
     }
 }
 
@@ -644,7 +5612,15 @@ impl Imsz for std::io::Stdin {
 impl Imsz for &std::io::Stdin {
     /// WARNING: This looses already buffered input!
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    /// WARNING: This looses already buffered input!
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
         let lock = self.lock();
 
         #[cfg(target_family="unix")]
@@ -659,7 +5635,7 @@ impl Imsz for &std::io::Stdin {
             std::fs::File::from_raw_handle(lock.as_raw_handle())
         };
 
-        let result = imsz_from_reader(&mut BufReader::new(&mut seekable_stdin));
+        let result = imsz_from_reader_with_options(&mut BufReader::new(&mut seekable_stdin), options);
 
         // Be sure the lock is released *after* all my IO happened.
         drop(lock);
@@ -670,43 +5646,85 @@ impl Imsz for &std::io::Stdin {
 
 impl Imsz for &mut std::io::Cursor<&[u8]> {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return imsz_from_reader(self);
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_reader_with_options(self, options);
     }
 }
 
 impl Imsz for std::io::Cursor<&[u8]> {
     #[inline]
-    fn imsz(mut self) -> ImResult<ImInfo> {
-        return imsz_from_reader(&mut self);
+    #[allow(clippy::needless_return)]
+    fn imsz(self) -> ImResult<ImInfo> {
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(mut self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_reader_with_options(&mut self, options);
     }
 }
 
 impl<const LEN: usize> Imsz for std::io::Cursor<&[u8; LEN]> {
     #[inline]
-    fn imsz(mut self) -> ImResult<ImInfo> {
-        return imsz_from_reader(&mut self);
+    #[allow(clippy::needless_return)]
+    fn imsz(self) -> ImResult<ImInfo> {
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(mut self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_reader_with_options(&mut self, options);
     }
 }
 
 impl<const LEN: usize> Imsz for std::io::Cursor<[u8; LEN]> {
     #[inline]
-    fn imsz(mut self) -> ImResult<ImInfo> {
-        return imsz_from_reader(&mut self);
+    #[allow(clippy::needless_return)]
+    fn imsz(self) -> ImResult<ImInfo> {
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(mut self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_reader_with_options(&mut self, options);
     }
 }
 
 impl<R> Imsz for &mut std::io::BufReader<R> where R: Read, R: Seek {
     #[inline]
+    #[allow(clippy::needless_return)]
     fn imsz(self) -> ImResult<ImInfo> {
-        return imsz_from_reader(self);
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_reader_with_options(self, options);
     }
 }
 
 impl<R> Imsz for std::io::BufReader<R> where R: Read, R: Seek {
     #[inline]
-    fn imsz(mut self) -> ImResult<ImInfo> {
-        return imsz_from_reader(&mut self);
+    #[allow(clippy::needless_return)]
+    fn imsz(self) -> ImResult<ImInfo> {
+        return self.imsz_with_options(&ImOptions::default());
+    }
+
+    #[inline]
+    #[allow(clippy::needless_return)]
+    fn imsz_with_options(mut self, options: &ImOptions) -> ImResult<ImInfo> {
+        return imsz_from_reader_with_options(&mut self, options);
     }
 }
 
@@ -714,22 +5732,86 @@ impl<R> Imsz for std::io::BufReader<R> where R: Read, R: Seek {
 /// 
 /// `input` can be a file path, a byte buffer, a file reader, or a buffered reader.
 #[inline]
+#[allow(clippy::needless_return)]
 pub fn imsz(input: impl Imsz) -> ImResult<ImInfo> {
     return input.imsz();
 }
 
+/// Read width and height of an image and whatever else is requested by
+/// `options`.
+/// 
+/// `input` can be a file path, a byte buffer, a file reader, or a buffered reader.
+#[inline]
+#[allow(clippy::needless_return)]
+pub fn imsz_with_options(input: impl Imsz, options: &ImOptions) -> ImResult<ImInfo> {
+    return input.imsz_with_options(options);
+}
+
+/// Read width and height of all images in a file.
+/// 
+/// `input` can be a file path, a byte buffer, a file reader, or a buffered reader.
+#[inline]
+#[allow(clippy::needless_return)]
+pub fn imsz_all(input: impl Imsz) -> ImResult<Vec<ImImage>> {
+    let options = ImOptions {
+        images: true,
+        ..ImOptions::default()
+    };
+    return Ok(input.imsz_with_options(&options)?.images);
+}
+
 /// Read width and height of an image.
 #[inline]
+#[allow(clippy::needless_return)]
 pub fn imsz_from_path(path: impl AsRef<std::path::Path>) -> ImResult<ImInfo> {
+    return imsz_from_path_with_options(path, &ImOptions::default());
+}
+
+/// Read width and height of an image and whatever else is requested by
+/// `options`.
+#[inline]
+#[allow(clippy::needless_return)]
+pub fn imsz_from_path_with_options(path: impl AsRef<std::path::Path>, options: &ImOptions) -> ImResult<ImInfo> {
     let mut reader = BufReader::new(File::open(path)?);
-    return imsz_from_reader(&mut reader);
+    return imsz_from_reader_with_options(&mut reader, options);
 }
 
 /// Read width and height of an image.
 /// 
 /// Some file formats (like JPEG) need repeated small reads, so passing a
 /// `std::io::BufReader` is recommended.
+#[inline]
+#[allow(clippy::needless_return)]
 pub fn imsz_from_reader<R>(file: &mut R) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    return imsz_from_reader_with_options(file, &ImOptions::default());
+}
+
+/// Read width and height of an image and whatever else is requested by
+/// `options`.
+/// 
+/// Some file formats (like JPEG) need repeated small reads, so passing a
+/// `std::io::BufReader` is recommended.
+#[allow(clippy::needless_return)]
+pub fn imsz_from_reader_with_options<R>(file: &mut R, options: &ImOptions) -> ImResult<ImInfo>
+where R: Read, R: Seek {
+    let mut info = read_info(file, options)?;
+
+    if options.images && info.images.is_empty() {
+        info.images.push(ImImage {
+            index:  0,
+            width:  info.width,
+            height: info.height,
+            format: info.format,
+            role:   ImRole::Primary,
+        });
+    }
+
+    return Ok(info);
+}
+
+#[allow(clippy::needless_return)]
+fn read_info<R>(file: &mut R, options: &ImOptions) -> ImResult<ImInfo>
 where R: Read, R: Seek {
     let mut preamble = [0u8; 30];
 
@@ -743,30 +5825,29 @@ where R: Read, R: Seek {
         let w = u16::from_le_bytes(array2!(preamble, 6));
         let h = u16::from_le_bytes(array2!(preamble, 8));
 
-        return Ok(ImInfo {
-            format: ImFormat::GIF,
-            width:  w as u64,
-            height: h as u64,
-        });
+        let mut info = ImInfo::new(ImFormat::GIF, w as u64, h as u64);
+        if size >= 11 {
+            let flags = preamble[10];
+            let bits = if flags & 0x80 != 0 {
+                // size of the global color table
+                (flags & 0x7) + 1
+            } else {
+                // color resolution
+                ((flags >> 4) & 0x7) + 1
+            };
+            info.pixel_format = Some(ImPixelFormat::new(ImColorModel::Indexed, 1, bits as u16, false));
+
+            if options.animation {
+                info.animation = Some(parse_gif_animation(file, flags)?);
+            }
+        }
+        return Ok(info);
     } else if size >= 8 && preamble.starts_with(b"\x89PNG\r\n\x1a\n") {
         // PNG
         if size < 24 {
             return Err(ImError::ParserError(ImFormat::PNG));
         }
-
-        let chunk_size = u32::from_be_bytes(array4!(preamble, 8));
-        if chunk_size < 8 || &preamble[12..16] != b"IHDR" {
-            return Err(ImError::ParserError(ImFormat::PNG));
-        }
-
-        let w = u32::from_be_bytes(array4!(preamble, 16));
-        let h = u32::from_be_bytes(array4!(preamble, 20));
-
-        return Ok(ImInfo {
-            format: ImFormat::PNG,
-            width:  w as u64,
-            height: h as u64,
-        });
+        return parse_png(file, &preamble[..size], options);
     } else if size >= 10 && preamble.starts_with(b"BM") && &preamble[6..10] == b"\0\0\0\0" {
         // BMP
         let file_size = u32::from_le_bytes(array4!(preamble, 2));
@@ -781,12 +5862,10 @@ where R: Read, R: Seek {
             let w = i16::from_le_bytes(array2!(preamble, 18));
             let h = i16::from_le_bytes(array2!(preamble, 20));
 
-            return Ok(ImInfo {
-                format: ImFormat::BMP,
-                width:  w as u64,
-                // h is negative when stored upside down
-                height: h.abs() as u64,
-            });
+            // h is negative when stored upside down
+            let mut info = ImInfo::new(ImFormat::BMP, w as u64, h.unsigned_abs() as u64);
+            info.pixel_format = read_bitmap_pixel_format(file, &preamble[14..min_size], 14, ImFormat::BMP, options.pixel_format)?;
+            return Ok(info);
         } else {
             if min_size < 26 || header_size <= 12 {
                 return Err(ImError::ParserError(ImFormat::BMP));
@@ -794,12 +5873,13 @@ where R: Read, R: Seek {
             let w = i32::from_le_bytes(array4!(preamble, 18));
             let h = i32::from_le_bytes(array4!(preamble, 22));
 
-            return Ok(ImInfo {
-                format: ImFormat::BMP,
-                width:  w as u64,
-                // h is negative when stored upside down
-                height: h.abs() as u64
-            });
+            // h is negative when stored upside down
+            let mut info = ImInfo::new(ImFormat::BMP, w as u64, h.unsigned_abs() as u64);
+            info.pixel_format = read_bitmap_pixel_format(file, &preamble[14..min_size], 14, ImFormat::BMP, options.pixel_format)?;
+            if options.resolution {
+                info.resolution = read_bitmap_resolution(file, 14, ImFormat::BMP)?;
+            }
+            return Ok(info);
         }
     } else if size >= 3 && &preamble[..2] == b"\xff\xd8" {
         // JPEG
@@ -807,6 +5887,20 @@ where R: Read, R: Seek {
         let mut buf1: [u8; 1] = [ preamble[2] ];
         let mut buf2: [u8; 2] = [0; 2];
         let mut buf4: [u8; 4] = [0; 4];
+        let mut buf6: [u8; 6] = [0; 6];
+        // hierarchical JPEG files define the size of the final image in the
+        // DHP segment, the frames that follow might be smaller
+        let mut dhp_size: Option<(u16, u16)> = None;
+        // color transform from the Adobe APP14 segment
+        let mut adobe_transform: Option<u8> = None;
+        let mut orientation: Option<ImOrientation> = None;
+        let mut jfif_resolution: Option<ImResolution> = None;
+        let mut exif_resolution: Option<ImResolution> = None;
+        let mut exif_seen = false;
+        // the other images of MPO files and the thumbnails of the EXIF,
+        // JFIF and JFXX segments
+        let mut mp_images: Vec<ImImage> = Vec::new();
+        let mut thumbnails: Vec<(u64, u64)> = Vec::new();
         while buf1[0] != b'\xda' && buf1[0] != 0 {
             while buf1[0] != b'\xff' {
                 map_err!(JPEG file.read_exact(&mut buf1));
@@ -814,266 +5908,490 @@ where R: Read, R: Seek {
             while buf1[0] == b'\xff' {
                 map_err!(JPEG file.read_exact(&mut buf1));
             }
-            if buf1[0] >= 0xc0 && buf1[0] <= 0xc3 {
-                map_err!(JPEG file.seek(SeekFrom::Current(3)));
-                map_err!(JPEG file.read_exact(&mut buf4));
-                let h = u16::from_be_bytes(array2!(buf4, 0));
-                let w = u16::from_be_bytes(array2!(buf4, 2));
+            if let Some(mut jpeg_info) = ImJpegInfo::from_marker(buf1[0]) {
+                map_err!(JPEG file.seek(SeekFrom::Current(2)));
+                map_err!(JPEG file.read_exact(&mut buf6));
+                let precision = buf6[0] as u16;
+                let mut h = u16::from_be_bytes(array2!(buf6, 1));
+                let mut w = u16::from_be_bytes(array2!(buf6, 3));
+                let components = buf6[5] as u16;
+
+                if let Some((dhp_w, dhp_h)) = dhp_size {
+                    w = dhp_w;
+                    h = dhp_h;
+                    jpeg_info.hierarchical = true;
+                }
 
-                return Ok(ImInfo {
-                    format: ImFormat::JPEG,
-                    width:  w as u64,
-                    height: h as u64,
-                });
+                let color_model = match (components, adobe_transform) {
+                    (1, _)       => ImColorModel::Gray,
+                    (3, Some(0)) => ImColorModel::RGB,
+                    (3, _)       => ImColorModel::YCbCr,
+                    (4, Some(2)) => ImColorModel::YCCK,
+                    (4, _)       => ImColorModel::CMYK,
+                    _            => ImColorModel::Other,
+                };
+
+                let mut info = ImInfo::new(ImFormat::JPEG, w as u64, h as u64);
+                info.pixel_format = Some(ImPixelFormat::new(color_model, components, precision, false));
+                info.details = ImDetails::JPEG(jpeg_info);
+                info.orientation = orientation;
+                // EXIF only if JFIF gives just the aspect ratio
+                info.resolution = match jfif_resolution {
+                    Some(resolution) if resolution.unit != ImResolutionUnit::Unknown => Some(resolution),
+                    _ => exif_resolution.or(jfif_resolution),
+                };
+                if options.images {
+                    info.images.push(ImImage {
+                        index:  0,
+                        width:  w as u64,
+                        height: h as u64,
+                        format: ImFormat::JPEG,
+                        role:   ImRole::Primary,
+                    });
+                    info.images.append(&mut mp_images);
+                    for (width, height) in thumbnails {
+                        info.images.push(ImImage {
+                            index:  0,
+                            width,
+                            height,
+                            format: ImFormat::JPEG,
+                            role:   ImRole::Thumbnail,
+                        });
+                    }
+                    for (index, image) in info.images.iter_mut().enumerate() {
+                        image.index = index;
+                    }
+                }
+                return Ok(info);
             }
             map_err!(JPEG file.read_exact(&mut buf2));
             let b = u16::from_be_bytes(buf2);
-            let offset = (b - 2) as i64;
+            if b < 2 {
+                return Err(ImError::ParserError(ImFormat::JPEG));
+            }
+            let mut offset = (b - 2) as i64;
+            if buf1[0] == 0xde {
+                // DHP, same layout as a frame header
+                if offset < 5 {
+                    return Err(ImError::ParserError(ImFormat::JPEG));
+                }
+                map_err!(JPEG file.seek(SeekFrom::Current(1)));
+                map_err!(JPEG file.read_exact(&mut buf4));
+                let h = u16::from_be_bytes(array2!(buf4, 0));
+                let w = u16::from_be_bytes(array2!(buf4, 2));
+                dhp_size = Some((w, h));
+                offset -= 5;
+            } else if buf1[0] == 0xee && offset >= 12 {
+                // APP14
+                let mut app14 = [0u8; 12];
+                map_err!(JPEG file.read_exact(&mut app14));
+                if app14.starts_with(b"Adobe") {
+                    adobe_transform = Some(app14[11]);
+                }
+                offset -= 12;
+            } else if buf1[0] == 0xe0 && (options.resolution || options.images) && offset >= 8 {
+                // APP0
+                let segment_end = map_expr!(JPEG file.stream_position()) + offset as u64;
+                let mut app0 = [0u8; 14];
+                let app0_size = (offset as usize).min(app0.len());
+                map_err!(JPEG file.read_exact(&mut app0[..app0_size]));
+                if app0.starts_with(b"JFXX\0") && options.images {
+                    let thumbnail = match app0[5] {
+                        // JPEG thumbnail
+                        0x10 => read_embedded_jpeg(file, segment_end - offset as u64 + 6, &ImOptions::default())
+                            .map(|thumbnail| (thumbnail.width, thumbnail.height)),
+                        // palette and RGB thumbnails
+                        0x11 | 0x13 => Some((app0[6] as u64, app0[7] as u64)),
+                        _ => None
+                    };
+                    if let Some(thumbnail) = thumbnail {
+                        thumbnails.push(thumbnail);
+                    }
+                } else if app0.starts_with(b"JFIF\0") && app0_size >= 12 {
+                    if options.resolution {
+                        let x = u16::from_be_bytes(array2!(app0, 8));
+                        let y = u16::from_be_bytes(array2!(app0, 10));
+                        let unit = match app0[7] {
+                            0 => Some(ImResolutionUnit::Unknown),
+                            1 => Some(ImResolutionUnit::Inch),
+                            2 => Some(ImResolutionUnit::Centimeter),
+                            _ => None
+                        };
+                        if let Some(unit) = unit {
+                            if x != 0 && y != 0 {
+                                jfif_resolution = Some(ImResolution::new(x as f64, y as f64, unit));
+                            }
+                        }
+                    }
+                    // uncompressed RGB thumbnail
+                    if options.images && app0_size >= 14 && app0[12] != 0 && app0[13] != 0 {
+                        thumbnails.push((app0[12] as u64, app0[13] as u64));
+                    }
+                }
+                map_err!(JPEG file.seek(SeekFrom::Start(segment_end)));
+                offset = 0;
+            } else if buf1[0] == 0xe2 && options.images && offset >= 8 {
+                // APP2, might be MPF or an ICC profile
+                let mut mpf = vec![0u8; 4];
+                map_err!(JPEG file.read_exact(&mut mpf));
+                offset -= 4;
+                if mpf == b"MPF\0" {
+                    let mpf_start = map_expr!(JPEG file.stream_position());
+                    mpf.resize(offset as usize, 0);
+                    map_err!(JPEG file.read_exact(&mut mpf));
+                    offset = 0;
+                    let entries = parse_mpf_entries(&mpf).unwrap_or_default();
+                    // the first entry is the primary image
+                    for (attribute, mp_offset) in entries.into_iter().skip(1) {
+                        if let Some(image) = read_embedded_jpeg(file, mpf_start + mp_offset, &ImOptions::default()) {
+                            mp_images.push(ImImage {
+                                index:  0,
+                                width:  image.width,
+                                height: image.height,
+                                format: ImFormat::JPEG,
+                                // large thumbnails (VGA and full HD), the
+                                // other types are multi-frame images like
+                                // the views of stereo images
+                                role:   if matches!(attribute & 0xFFFFFF, 0x010001 | 0x010002) { ImRole::Thumbnail } else { ImRole::Page },
+                            });
+                        }
+                    }
+                    map_err!(JPEG file.seek(SeekFrom::Start(mpf_start + mpf.len() as u64)));
+                }
+            } else if buf1[0] == 0xe1 && (options.orientation || options.resolution || options.images) && !exif_seen && offset >= 6 {
+                // APP1, might be EXIF or XMP
+                let mut app1 = vec![0u8; offset as usize];
+                map_err!(JPEG file.read_exact(&mut app1));
+                if app1.starts_with(b"Exif\0\0") {
+                    exif_seen = true;
+                    if let Some(ifd) = parse_exif_ifd0(&app1) {
+                        if options.orientation {
+                            orientation = ifd.orientation.and_then(|value| ImOrientation::from_exif(value as u16));
+                        }
+                        if options.resolution {
+                            exif_resolution = ifd.resolution();
+                        }
+                        if options.images {
+                            if let Some(thumbnail) = parse_exif_thumbnail(&app1, &ifd) {
+                                thumbnails.push(thumbnail);
+                            }
+                        }
+                    }
+                }
+                offset = 0;
+            }
             map_err!(JPEG file.seek(SeekFrom::Current(offset)));
             map_err!(JPEG file.read_exact(&mut buf1));
         }
         return Err(ImError::ParserError(ImFormat::JPEG));
-    } else if size >= 30 && preamble.starts_with(b"RIFF") && &preamble[8..12] == b"WEBP" {
+    } else if size >= 12 && preamble.starts_with(b"RIFF") && &preamble[8..12] == b"WEBP" {
         // WEBP
-        let hdr = &preamble[12..16];
-        if hdr == b"VP8L" {
-            let b0 = preamble[21];
-            let b1 = preamble[22];
-            let b2 = preamble[23];
-            let b3 = preamble[24];
-
-            let w = 1u32 + ((((b1 & 0x3F) as u32) << 8) | b0 as u32);
-            let h = 1u32 + ((((b3 & 0xF) as u32) << 10) | ((b2 as u32) << 2) | ((b1 & 0xC0) as u32 >> 6));
-
-            return Ok(ImInfo {
-                format: ImFormat::WEBP,
-                width:  w as u64,
-                height: h as u64,
-            });
-        } else if hdr == b"VP8 " {
-            let b0 = preamble[23];
-            let b1 = preamble[24];
-            let b2 = preamble[25];
-            if b0 != 0x9d || b1 != 0x01 || b2 != 0x2a {
-                return Err(ImError::ParserError(ImFormat::WEBP));
-            }
-            let w = u16::from_le_bytes(array2!(preamble, 26));
-            let h = u16::from_le_bytes(array2!(preamble, 28));
-            return Ok(ImInfo {
-                format: ImFormat::WEBP,
-                width:  w as u64 & 0x3ffff,
-                height: h as u64 & 0x3ffff,
-            });
-        } else if hdr == b"VP8X" {
-            let w1 = preamble[24] as u32;
-            let w2 = preamble[25] as u32;
-            let w3 = preamble[26] as u32;
-            let h1 = preamble[27] as u32;
-            let h2 = preamble[28] as u32;
-            let h3 = preamble[29] as u32;
-
-            let width  = (w1 | w2 << 8 | w3 << 16) + 1;
-            let height = (h1 | h2 << 8 | h3 << 16) + 1;
-
-            return Ok(ImInfo {
-                format: ImFormat::WEBP,
-                width:  width  as u64,
-                height: height as u64,
-            });
+        return parse_webp(file, &preamble, options);
+    } else if size >= 12 && preamble.starts_with(b"RIFF") && &preamble[8..12] == b"ACON" {
+        // ANI
+        return parse_ani(file, &preamble, options);
+    } else if size >= 3 && preamble[0] == b'P' && matches!(preamble[1], b'1'..=b'6' | b'f' | b'F') &&
+              (preamble[2].is_ascii_whitespace() || preamble[2] == b'#') {
+        // PBM, PGM, PPM and PFM
+        return parse_netpbm(file, preamble[1]);
+    } else if size >= 3 && preamble.starts_with(b"P7") && (preamble[2] == b'\n' || preamble[2] == b'\r') {
+        // PAM, but not the similar XV thumbnails (`P7 332`)
+        return parse_netpbm(file, preamble[1]);
+    } else if preamble.starts_with(b"#?RADIANCE\n") || preamble.starts_with(b"#?RGBE\n") {
+        // Radiance HDR
+        return parse_hdr(file, options);
+    } else if is_svg_start(&preamble[..size]) {
+        // SVG
+        map_err!(SVG file.seek(SeekFrom::Start(0)));
+        return parse_svg(file);
+    } else if size >= 3 && preamble.starts_with(b"\x1f\x8b\x08") {
+        // gzip compressed SVG, only with the svgz feature
+        map_err!(SVG file.seek(SeekFrom::Start(0)));
+        return parse_svgz(file);
+    } else if size >= 12 && preamble.starts_with(b"\xabKTX 11\xbb\r\n\x1a\n") {
+        // KTX
+        return parse_ktx(file, options);
+    } else if size >= 12 && preamble.starts_with(b"\xabKTX 20\xbb\r\n\x1a\n") {
+        // KTX2
+        return parse_ktx2(file, options);
+    } else if size >= 4 && preamble.starts_with(b"PVR\x03") {
+        // PVR version 3
+        return parse_pvr(file, options);
+    } else if size >= 16 && preamble.starts_with(b"\x13\xab\xa1\x5c") {
+        // ASTC
+        let w = get_u24_le(&preamble, 7);
+        let h = get_u24_le(&preamble, 10);
+        let d = get_u24_le(&preamble, 13);
+        let texture = ImTextureInfo::new(d.max(1) as u64, 1, 1, 1);
+        return Ok(texture_info(ImFormat::ASTC, w as u64, h as u64, texture, options));
+    } else if size >= 6 && preamble.starts_with(b"sB") && u16::from_le_bytes(array2!(preamble, 4)) as usize == BASIS_HEADER_SIZE {
+        // Basis Universal
+        return parse_basis(file, options);
+    } else if size >= 2 && preamble.starts_with(b"\xff\x0a") {
+        // JPEG XL codestream
+        map_err!(JXL file.seek(SeekFrom::Start(0)));
+        return parse_jxl_codestream(file, options);
+    } else if size >= 12 && preamble.starts_with(b"\0\0\0\x0CJXL \r\n\x87\n") {
+        // JPEG XL container
+        map_err!(JXL file.seek(SeekFrom::Start(12)));
+        seek_jxl_codestream(file)?;
+        return parse_jxl_codestream(file, options);
+    } else if size >= 12 && &preamble[4..12] == b"ftypcrx " {
+        // CR3
+        let ftyp_size = u32::from_be_bytes(array4!(preamble, 0));
+        if ftyp_size < 12 {
+            return Err(ImError::ParserError(ImFormat::CR3));
         }
-        return Err(ImError::ParserError(ImFormat::WEBP));
-    } else if size >= 12 && (&preamble[4..12] == b"ftypavif" || &preamble[4..12] == b"ftypheic") {
-        // AVIF and HEIF
-        let format = if &preamble[8..12] == b"avif" {
-            ImFormat::AVIF
-        } else {
-            ImFormat::HEIF
-        };
-
-        let ftype_size = u32::from_be_bytes(array4!(preamble, 0));
-        if ftype_size < 12 {
-            return Err(ImError::ParserError(format));
+        return parse_cr3(file, ftyp_size as u64, options);
+    } else if size >= 16 && preamble.starts_with(b"FUJIFILMCCD-RAW ") {
+        // RAF
+        return parse_raf(file, options);
+    } else if size >= 12 && &preamble[4..8] == b"ftyp" {
+        // AVIF and HEIF, or any other ISO-BMFF file
+        return parse_heif(file, &preamble, options);
+    } else if size >= 24 && preamble.starts_with(b"\0\0\0\x0CjP  ") && &preamble[16..20] == b"ftyp" &&
+              matches!(&preamble[20..24], b"jp2 " | b"jpx " | b"jpm ") {
+        // JPEG 2000, JPX (extended) and JPM (compound)
+        let chunk_size = u32::from_be_bytes(array4!(preamble, 12));
+        map_err!(JP2K file.seek(SeekFrom::Start(12 + chunk_size as u64)));
+        if &preamble[20..24] == b"jpm " {
+            return parse_jpm_page(file);
         }
-        map_err!(format, file.seek(SeekFrom::Start(ftype_size as u64)));
+        let chunk_size = skip_to_iso_box(file, b"jp2h", u64::MAX, ImFormat::JP2K)?;
 
-        // chunk nesting: meta > iprp > ipco > ispe
-        let chunk_size = find_riff_chunk(file, b"meta", u64::MAX, format)?;
-        if chunk_size < 12 {
-            return Err(ImError::ParserError(format));
-        }
-        map_err!(format, file.seek(SeekFrom::Current(4)));
-        let chunk_size = find_riff_chunk(file, b"iprp", chunk_size - 12, format)?;
-        let chunk_size = find_riff_chunk(file, b"ipco", chunk_size -  8, format)?;
-        let chunk_size = find_riff_chunk(file, b"ispe", chunk_size -  8, format)?;
+        let jp2h_size = chunk_size;
+        let jp2h_start = map_expr!(JP2K file.stream_position());
+        let chunk_size = skip_to_iso_box(file, b"ihdr", jp2h_size, ImFormat::JP2K)?;
 
-        if chunk_size < 12 {
-            return Err(ImError::ParserError(format));
+        if chunk_size < 22 {
+            return Err(ImError::ParserError(ImFormat::JP2K));
         }
 
-        let mut buf = [0u8; 12];
-        map_err!(format, file.read_exact(&mut buf));
+        let mut buf = [0u8; 14];
+        map_err!(JP2K file.read_exact(&mut buf));
 
+        let h = u32::from_be_bytes(array4!(buf, 0));
         let w = u32::from_be_bytes(array4!(buf, 4));
-        let h = u32::from_be_bytes(array4!(buf, 8));
+        let components = u16::from_be_bytes(array2!(buf, 8));
+        let bpc = buf[10];
+
+        let mut info = ImInfo::new(ImFormat::JP2K, w as u64, h as u64);
+
+        // 255 means the bit depth varies per component
+        if bpc != 255 {
+            // the colr box is optional and follows ihdr in jp2h
+            let mut enum_cs = None;
+            map_err!(JP2K file.seek(SeekFrom::Current(chunk_size as i64 - 22)));
+            if jp2h_size >= chunk_size + 8 {
+                if let Ok(colr_size) = skip_to_iso_box(file, b"colr", jp2h_size - chunk_size - 8, ImFormat::JP2K) {
+                    let mut colr = [0u8; 7];
+                    if colr_size >= 15 && file.read_exact(&mut colr).is_ok() && colr[0] == 1 {
+                        enum_cs = Some(u32::from_be_bytes(array4!(colr, 3)));
+                    }
+                }
+            }
 
-        return Ok(ImInfo {
-            format,
-            width:  w as u64,
-            height: h as u64,
-        });
-    } else if size >= 24 && preamble.starts_with(b"\0\0\0\x0CjP  ") && &preamble[16..24] == b"ftypjp2 " {
-        // JPEG 2000
-        let chunk_size = u32::from_be_bytes(array4!(preamble, 12));
-        map_err!(JP2K file.seek(SeekFrom::Start(12 + chunk_size as u64)));
-        let chunk_size = find_riff_chunk(file, b"jp2h", u64::MAX, ImFormat::JP2K)?;
-        let chunk_size = find_riff_chunk(file, b"ihdr", chunk_size, ImFormat::JP2K)?;
+            let (color_model, color_channels) = match enum_cs {
+                Some(16 | 20 | 21) => (ImColorModel::RGB,   3),
+                Some(17)           => (ImColorModel::Gray,  1),
+                Some(18)           => (ImColorModel::YCbCr, 3),
+                Some(12)           => (ImColorModel::CMYK,  4),
+                Some(14)           => (ImColorModel::Lab,   3),
+                Some(_)            => (ImColorModel::Other, components),
+                None if components <= 2 => (ImColorModel::Gray, 1),
+                None               => (ImColorModel::RGB,   3),
+            };
 
-        if chunk_size < 8 {
-            return Err(ImError::ParserError(ImFormat::JP2K));
+            let bits = (bpc & 0x7f) as u16 + 1;
+            info.pixel_format = Some(ImPixelFormat::new(color_model, components, bits, components > color_channels));
         }
 
-        let mut buf = [0u8; 8];
-        map_err!(JP2K file.read_exact(&mut buf));
-
-        let h = u32::from_be_bytes(array4!(buf, 0));
-        let w = u32::from_be_bytes(array4!(buf, 4));
+        if options.resolution {
+            map_err!(JP2K file.seek(SeekFrom::Start(jp2h_start)));
+            info.resolution = parse_jp2_resolution(file, jp2h_size - 8)?;
+        }
 
-        return Ok(ImInfo {
-            format: ImFormat::JP2K,
-            width:  w as u64,
-            height: h as u64,
-        });
-    } else if size >= 8 && (preamble.starts_with(b"II*\0") || preamble.starts_with(b"MM\0*")) {
-        // TIFF
+        return Ok(info);
+    } else if size >= 4 && preamble.starts_with(b"\xff\x4f\xff\x51") {
+        // JPEG 2000 codestream: SOC and SIZ markers
+        map_err!(JP2K file.seek(SeekFrom::Start(0)));
+        return parse_j2k_codestream(file);
+    } else if size >= 8 && (
+            preamble.starts_with(b"II*\0") || preamble.starts_with(b"MM\0*") ||
+            preamble.starts_with(b"II+\0") || preamble.starts_with(b"MM\0+") ||
+            preamble.starts_with(b"IIRO") || preamble.starts_with(b"IISR") ||
+            preamble.starts_with(b"MMOR") || preamble.starts_with(b"IIU\0")) {
+        // TIFF, BigTIFF and TIFF based camera RAW formats
         if preamble.starts_with(b"MM") {
             // big endian
-            return parse_tiff::<BigEndianReader, R>(file, &preamble[..size]);
+            return parse_tiff::<BigEndianReader, R>(file, &preamble[..size], options);
         } else {
             // little endian
-            return parse_tiff::<LittleEndianReader, R>(file, &preamble[..size]);
+            return parse_tiff::<LittleEndianReader, R>(file, &preamble[..size], options);
         }
     } else if size >= 14 && preamble.starts_with(b"qoif") {
         // QOI
         let w = u32::from_be_bytes(array4!(preamble, 4));
         let h = u32::from_be_bytes(array4!(preamble, 8));
 
-        return Ok(ImInfo {
-            format: ImFormat::QOI,
-            width:  w as u64,
-            height: h as u64,
-        });
-    } else if size >= 22 && preamble.starts_with(b"8BPS\0\x01\0\0\0\0\0\0") {
-        // PSD
+        let channels = preamble[12] as u16;
+
+        let mut info = ImInfo::new(ImFormat::QOI, w as u64, h as u64);
+        info.pixel_format = Some(ImPixelFormat::new(ImColorModel::RGB, channels, 8, channels > 3));
+        return Ok(info);
+    } else if size >= 22 && preamble.starts_with(b"8BPS\0") && (preamble[5] == 1 || preamble[5] == 2) && preamble[6..12] == [0; 6] {
+        // PSD and PSB (version 2)
         let h = u32::from_be_bytes(array4!(preamble, 14));
         let w = u32::from_be_bytes(array4!(preamble, 18));
 
-        return Ok(ImInfo {
-            format: ImFormat::PSD,
-            width:  w as u64,
-            height: h as u64,
-        });
+        let mut info = ImInfo::new(ImFormat::PSD, w as u64, h as u64);
+        if size >= 26 {
+            let version  = u16::from_be_bytes(array2!(preamble,  4));
+            let channels = u16::from_be_bytes(array2!(preamble, 12));
+            let depth    = u16::from_be_bytes(array2!(preamble, 22));
+            let mode     = u16::from_be_bytes(array2!(preamble, 24));
+
+            let (color_model, color_channels) = match mode {
+                // Bitmap, Grayscale, Duotone
+                0 | 1 | 8 => (ImColorModel::Gray,    1),
+                2         => (ImColorModel::Indexed, 1),
+                3         => (ImColorModel::RGB,     3),
+                4         => (ImColorModel::CMYK,    4),
+                9         => (ImColorModel::Lab,     3),
+                // Multichannel
+                _         => (ImColorModel::Other,   channels),
+            };
+            info.pixel_format = Some(ImPixelFormat::new(color_model, channels, depth, channels > color_channels));
+            info.details = ImDetails::PSD(ImPsdInfo {
+                version,
+                channels,
+                depth,
+                color_mode: ImPsdColorMode::from_u16(mode),
+            });
+        }
+        if options.resolution {
+            info.resolution = parse_psd_resolution(file)?;
+        }
+        return Ok(info);
     } else if size >= 22 && preamble.starts_with(b"gimp xcf ") && preamble[13] == 0 {
         // XCF
         let w = u32::from_be_bytes(array4!(preamble, 14));
         let h = u32::from_be_bytes(array4!(preamble, 18));
 
-        return Ok(ImInfo {
-            format: ImFormat::XCF,
-            width:  w as u64,
-            height: h as u64,
-        });
-    } else if size >= 6 && preamble.starts_with(b"\0\0\x01\0") {
-        // ICO
+        let mut info = ImInfo::new(ImFormat::XCF, w as u64, h as u64);
+        if size >= 26 {
+            // "file" for version 0, "v001", "v002" etc. for newer versions
+            let version = if preamble[9] == b'v' {
+                std::str::from_utf8(&preamble[10..13]).ok().and_then(|v| v.parse::<u32>().ok()).unwrap_or(0)
+            } else {
+                0
+            };
+
+            let bits = if version >= 4 && size >= 30 {
+                let precision = u32::from_be_bytes(array4!(preamble, 26));
+                if version == 4 {
+                    match precision {
+                        0 => 8, 1 | 3 => 16, _ => 32
+                    }
+                } else {
+                    match precision / 100 {
+                        1 => 8, 2 | 5 => 16, 3 | 6 => 32, _ => 64
+                    }
+                }
+            } else {
+                8
+            };
+
+            info.pixel_format = match u32::from_be_bytes(array4!(preamble, 22)) {
+                0 => Some(ImPixelFormat::new(ImColorModel::RGB,     3, bits, false)),
+                1 => Some(ImPixelFormat::new(ImColorModel::Gray,    1, bits, false)),
+                2 => Some(ImPixelFormat::new(ImColorModel::Indexed, 1, 8,    false)),
+                _ => None
+            };
+        }
+        return Ok(info);
+    } else if size >= 6 && (preamble.starts_with(b"\0\0\x01\0") || (preamble.starts_with(b"\0\0\x02\0") && (preamble[4] | preamble[5]) != 0)) {
+        // ICO and CUR
+        let format = if preamble[2] == 1 { ImFormat::ICO } else { ImFormat::CUR };
         let count = u16::from_le_bytes(array2!(preamble, 4));
-        map_err!(ICO file.seek(SeekFrom::Start(6)));
 
+        let mut entries = Vec::with_capacity(count as usize);
         let mut buf = [0u8; 16];
-        let mut width:  u32 = 0;
-        let mut height: u32 = 0;
+        map_err!(format, file.seek(SeekFrom::Start(6)));
         for _ in 0..count {
-            map_err!(ICO file.read_exact(&mut buf));
-            let w = buf[0] as u32;
-            let h = buf[1] as u32;
-            if w >= width && h >= height {
-                width  = w;
-                height = h;
-            }
+            map_err!(format, file.read_exact(&mut buf));
+            entries.push(buf);
         }
 
-        return Ok(ImInfo {
-            format: ImFormat::ICO,
-            width:  width  as u64,
-            height: height as u64,
-        });
-    } else if size > 8 && preamble.starts_with(b"\x76\x2f\x31\x01") && (preamble[4] == 0x01 || preamble[4] == 0x02) {
-        // OpenEXR
-        // https://www.openexr.com/documentation/openexrfilelayout.pdf
-        map_err!(OpenEXR file.seek(SeekFrom::Start(8)));
-
-        let mut name_buf = Vec::new();
-        let mut type_buf = Vec::new();
-        let mut buf1 = [0u8];
-        let mut buf4 = [0u8; 4];
-
-        loop {
-            name_buf.clear();
-            loop {
-                map_err!(OpenEXR file.read_exact(&mut buf1));
-                let byte = buf1[0];
-                if byte == 0 {
-                    break;
-                }
-                name_buf.push(byte);
-            }
-
-            if name_buf.is_empty() {
-                break;
+        let mut width:  u32 = 0;
+        let mut height: u32 = 0;
+        let mut pixel_format = None;
+        let mut hotspot = None;
+        let mut images = Vec::new();
+        for (index, entry) in entries.iter().enumerate() {
+            // 0 means 256
+            let mut w = if entry[0] == 0 { 256 } else { entry[0] as u32 };
+            let mut h = if entry[1] == 0 { 256 } else { entry[1] as u32 };
+            let mut image_format = format;
+            let mut image_pixel_format = None;
+
+            if format == ImFormat::ICO {
+                let bit_count = u16::from_le_bytes(array2!(entry, 6));
+                image_pixel_format = match bit_count {
+                    1 | 2 | 4 | 8 => Some(ImPixelFormat::new(ImColorModel::Indexed, 1, bit_count, false)),
+                    16 => Some(ImPixelFormat::new(ImColorModel::RGB, 3, 5, false)),
+                    24 => Some(ImPixelFormat::new(ImColorModel::RGB, 3, 8, false)),
+                    32 => Some(ImPixelFormat::new(ImColorModel::RGB, 4, 8, true)),
+                    _ => None
+                };
             }
 
-            type_buf.clear();
-            loop {
-                map_err!(OpenEXR file.read_exact(&mut buf1));
-                let byte = buf1[0];
-                if byte == 0 {
-                    break;
+            if options.embedded_headers {
+                let offset = u32::from_le_bytes(array4!(entry, 12));
+                let (embedded_format, embedded_w, embedded_h, embedded_pixel_format) = read_ico_image(file, offset as u64, format)?;
+                image_format = embedded_format;
+                w = embedded_w;
+                h = embedded_h;
+                if embedded_pixel_format.is_some() {
+                    image_pixel_format = embedded_pixel_format;
                 }
-                type_buf.push(byte);
             }
 
-            map_err!(OpenEXR file.read_exact(&mut buf4));
-            let size = u32::from_le_bytes(buf4);
-
-            if &name_buf == b"displayWindow" {
-                if &type_buf != b"box2i" || size != 16 {
-                    return Err(ImError::ParserError(ImFormat::OpenEXR));
-                }
-
-                let mut box_buf = [0u8; 16];
-                map_err!(OpenEXR file.read_exact(&mut box_buf));
-
-                let x1 = i32::from_le_bytes(array4!(box_buf,  0)) as i64;
-                let y1 = i32::from_le_bytes(array4!(box_buf,  4)) as i64;
-                let x2 = i32::from_le_bytes(array4!(box_buf,  8)) as i64;
-                let y2 = i32::from_le_bytes(array4!(box_buf, 12)) as i64;
-
-                let width  = x2 - x1 + 1;
-                let height = y2 - y1 + 1;
-
-                if width <= 0 || height <= 0 {
-                    return Err(ImError::ParserError(ImFormat::OpenEXR));
+            if w >= width && h >= height {
+                width  = w;
+                height = h;
+                pixel_format = image_pixel_format;
+                if format == ImFormat::CUR {
+                    hotspot = Some(ImHotspot {
+                        x: u16::from_le_bytes(array2!(entry, 4)),
+                        y: u16::from_le_bytes(array2!(entry, 6)),
+                    });
                 }
-
-                return Ok(ImInfo {
-                    format: ImFormat::OpenEXR,
-                    width:  width  as u64,
-                    height: height as u64,
+            }
+            if options.images {
+                images.push(ImImage {
+                    index,
+                    width:  w as u64,
+                    height: h as u64,
+                    format: image_format,
+                    role:   ImRole::Icon,
                 });
-            } else {
-                map_err!(OpenEXR file.seek(SeekFrom::Current(size as i64)));
             }
         }
 
-        return Err(ImError::ParserError(ImFormat::OpenEXR));
+        let mut info = ImInfo::new(format, width as u64, height as u64);
+        info.images = images;
+        info.pixel_format = pixel_format;
+        if let Some(hotspot) = hotspot {
+            info.details = ImDetails::CUR(hotspot);
+        }
+        return Ok(info);
+    } else if size >= 8 && preamble.starts_with(b"icns") {
+        // ICNS
+        return parse_icns(file, options);
+    } else if size > 8 && preamble.starts_with(b"\x76\x2f\x31\x01") && (preamble[4] == 0x01 || preamble[4] == 0x02) {
+        // OpenEXR
+        return parse_exr(file, options);
     } else if size >= 30 && preamble[0] == 0x0A && preamble[1] < 6 && (preamble[3] == 1 || preamble[3] == 2 || preamble[3] == 4 || preamble[3] == 8) {
         // PCX
         let x1 = u16::from_le_bytes(array2!(preamble,  4)) as i64;
@@ -1088,11 +6406,31 @@ where R: Read, R: Seek {
             return Err(ImError::ParserError(ImFormat::PCX));
         }
 
-        return Ok(ImInfo {
-            format: ImFormat::PCX,
-            width:  width  as u64,
-            height: height as u64,
-        });
+        let mut info = ImInfo::new(ImFormat::PCX, width as u64, height as u64);
+        if options.pixel_format {
+            let bits = preamble[3] as u16;
+            let mut planes = [0u8];
+            map_err!(PCX file.seek(SeekFrom::Start(65)));
+            map_err!(PCX file.read_exact(&mut planes));
+            let planes = planes[0] as u16;
+
+            info.pixel_format = match (bits, planes) {
+                (8, 3) => Some(ImPixelFormat::new(ImColorModel::RGB, 3, 8, false)),
+                (8, 4) => Some(ImPixelFormat::new(ImColorModel::RGB, 4, 8, true)),
+                (_, 1) => Some(ImPixelFormat::new(ImColorModel::Indexed, 1, bits, false)),
+                // planar EGA images
+                (1, 2..=4) => Some(ImPixelFormat::new(ImColorModel::Indexed, 1, planes, false)),
+                _ => None
+            };
+        }
+        if options.resolution {
+            let x = u16::from_le_bytes(array2!(preamble, 12));
+            let y = u16::from_le_bytes(array2!(preamble, 14));
+            if x != 0 && y != 0 {
+                info.resolution = Some(ImResolution::new(x as f64, y as f64, ImResolutionUnit::Inch));
+            }
+        }
+        return Ok(info);
     } else if size >= 30 && preamble.starts_with(b"DDS \x7C\0\0\0") && (u32::from_le_bytes(array4!(preamble, 8)) & 0x1007) != 0 {
         // DDS
         // http://doc.51windows.net/directx9_sdk/graphics/reference/DDSFileReference/ddsfileformat.htm
@@ -1101,21 +6439,113 @@ where R: Read, R: Seek {
         let h = u32::from_le_bytes(array4!(preamble, 12));
         let w = u32::from_le_bytes(array4!(preamble, 16));
 
-        return Ok(ImInfo {
-            format: ImFormat::DDS,
-            width:  w as u64,
-            height: h as u64,
-        });
+        // magic + DDS_HEADER, continue reading after the preamble
+        let mut header = [0u8; 128];
+        header[..size].copy_from_slice(&preamble[..size]);
+        map_err!(DDS file.read_exact(&mut header[size..]));
+
+        // DDS_PIXELFORMAT
+        let flags = u32::from_le_bytes(array4!(header,  80));
+        let red   = u32::from_le_bytes(array4!(header,  92)).count_ones() as u16;
+        let green = u32::from_le_bytes(array4!(header,  96)).count_ones() as u16;
+        let blue  = u32::from_le_bytes(array4!(header, 100)).count_ones() as u16;
+        let alpha = u32::from_le_bytes(array4!(header, 104)).count_ones() as u16;
+        // DDPF_ALPHAPIXELS
+        let has_alpha = flags & 0x1 != 0 && alpha > 0;
+        let alpha_channels = has_alpha as u16;
+
+        let mut info = ImInfo::new(ImFormat::DDS, w as u64, h as u64);
+        info.pixel_format = if flags & 0x4 != 0 {
+            // DDPF_FOURCC, compressed
+            None
+        } else if flags & 0x40 != 0 {
+            // DDPF_RGB
+            let bits = red.max(green).max(blue).max(if has_alpha { alpha } else { 0 });
+            Some(ImPixelFormat::new(ImColorModel::RGB, 3 + alpha_channels, bits, has_alpha))
+        } else if flags & 0x200 != 0 {
+            // DDPF_YUV
+            let bits = red.max(green).max(blue);
+            Some(ImPixelFormat::new(ImColorModel::YCbCr, 3 + alpha_channels, bits, has_alpha))
+        } else if flags & 0x20000 != 0 {
+            // DDPF_LUMINANCE
+            let bits = red.max(if has_alpha { alpha } else { 0 });
+            Some(ImPixelFormat::new(ImColorModel::Gray, 1 + alpha_channels, bits, has_alpha))
+        } else if flags & 0x20 != 0 {
+            // DDPF_PALETTEINDEXED8
+            Some(ImPixelFormat::new(ImColorModel::Indexed, 1, 8, false))
+        } else if flags & 0x2 != 0 {
+            // DDPF_ALPHA
+            Some(ImPixelFormat::new(ImColorModel::Other, 1, alpha, true))
+        } else {
+            None
+        };
+
+        let header_flags = u32::from_le_bytes(array4!(header, 8));
+        let caps2 = u32::from_le_bytes(array4!(header, 112));
+
+        // DDSD_MIPMAPCOUNT
+        let mip_levels = if header_flags & 0x20000 != 0 {
+            u32::from_le_bytes(array4!(header, 28)).max(1)
+        } else {
+            1
+        };
+
+        // DDSD_DEPTH
+        let depth = if header_flags & 0x800000 != 0 {
+            u32::from_le_bytes(array4!(header, 24)).max(1)
+        } else {
+            1
+        };
+
+        let texture = if flags & 0x4 != 0 && &header[84..88] == b"DX10" {
+            // DDS_HEADER_DXT10
+            let mut buf = [0u8; 20];
+            map_err!(DDS file.read_exact(&mut buf));
+            let dxgi_format = u32::from_le_bytes(array4!(buf,  0));
+            let dimension   = u32::from_le_bytes(array4!(buf,  4));
+            let misc_flag   = u32::from_le_bytes(array4!(buf,  8));
+            let array_size  = u32::from_le_bytes(array4!(buf, 12)).max(1);
+
+            info.pixel_format = dxgi_pixel_format(dxgi_format);
+
+            // D3D10_RESOURCE_DIMENSION_TEXTURE1D, 2D and 3D
+            let depth = match dimension {
+                2 => { info.height = 1; 1 }
+                3 => 1,
+                4 => depth,
+                _ => return Err(ImError::ParserError(ImFormat::DDS))
+            };
+            // DDS_RESOURCE_MISC_TEXTURECUBE
+            let faces = if misc_flag & 0x4 != 0 { 6 } else { 1 };
+            ImTextureInfo::new(depth as u64, array_size, faces, mip_levels)
+        } else if caps2 & 0x200 != 0 {
+            // DDSCAPS2_CUBEMAP, count the DDSCAPS2_CUBEMAP_POSITIVEX etc. flags
+            let faces = (caps2 & 0xFC00).count_ones().max(1);
+            ImTextureInfo::new(1, 1, faces, mip_levels)
+        } else if caps2 & 0x200000 != 0 {
+            // DDSCAPS2_VOLUME
+            ImTextureInfo::new(depth as u64, 1, 1, mip_levels)
+        } else {
+            ImTextureInfo::new(1, 1, 1, mip_levels)
+        };
+
+        if options.images {
+            info.images = texture_images(ImFormat::DDS, info.width, info.height, &texture);
+        }
+        info.details = ImDetails::Texture(texture);
+
+        return Ok(info);
     } else if size >= 14 && preamble.starts_with(b"\x28\0\0\0") && &preamble[12..14] == b"\x01\0" && preamble[15] == 0 {
         // DIB
         let w = i32::from_le_bytes(array4!(preamble, 4));
         let h = i32::from_le_bytes(array4!(preamble, 8));
 
-        return Ok(ImInfo {
-            format: ImFormat::DIB,
-            width:  w as u64,
-            height: h.abs() as u64,
-        });
+        let mut info = ImInfo::new(ImFormat::DIB, w as u64, h.unsigned_abs() as u64);
+        info.pixel_format = read_bitmap_pixel_format(file, &preamble[..size], 0, ImFormat::DIB, options.pixel_format)?;
+        if options.resolution {
+            info.resolution = read_bitmap_resolution(file, 0, ImFormat::DIB)?;
+        }
+        return Ok(info);
     } else if size >= 20 && preamble.starts_with(b"VTF\0") {
         // VTF
         let header_size = u32::from_le_bytes(array4!(preamble, 12));
@@ -1125,11 +6555,44 @@ where R: Read, R: Seek {
             return Err(ImError::ParserError(ImFormat::VTF));
         }
 
-        return Ok(ImInfo {
-            format: ImFormat::VTF,
-            width:  w as u64,
-            height: h as u64,
-        });
+        let mut info = ImInfo::new(ImFormat::VTF, w as u64, h as u64);
+        if options.pixel_format {
+            let mut buf = [0u8; 4];
+            map_err!(VTF file.seek(SeekFrom::Start(52)));
+            map_err!(VTF file.read_exact(&mut buf));
+
+            info.pixel_format = match i32::from_le_bytes(buf) {
+                // RGBA8888, ABGR8888, ARGB8888, BGRA8888
+                0 | 1 | 11 | 12 => Some(ImPixelFormat::new(ImColorModel::RGB, 4, 8, true)),
+                // RGB888, BGR888, RGB888_BLUESCREEN, BGR888_BLUESCREEN, BGRX8888
+                2 | 3 | 9 | 10 | 16 => Some(ImPixelFormat::new(ImColorModel::RGB, 3, 8, false)),
+                // RGB565, BGR565
+                4 | 17 => Some(ImPixelFormat::new(ImColorModel::RGB, 3, 6, false)),
+                // I8
+                5 => Some(ImPixelFormat::new(ImColorModel::Gray, 1, 8, false)),
+                // IA88
+                6 => Some(ImPixelFormat::new(ImColorModel::Gray, 2, 8, true)),
+                // P8
+                7 => Some(ImPixelFormat::new(ImColorModel::Indexed, 1, 8, false)),
+                // A8
+                8 => Some(ImPixelFormat::new(ImColorModel::Other, 1, 8, true)),
+                // BGRX5551
+                18 => Some(ImPixelFormat::new(ImColorModel::RGB, 3, 5, false)),
+                // BGRA4444
+                19 => Some(ImPixelFormat::new(ImColorModel::RGB, 4, 4, true)),
+                // BGRA5551
+                21 => Some(ImPixelFormat::new(ImColorModel::RGB, 4, 5, true)),
+                // UV88
+                22 => Some(ImPixelFormat::new(ImColorModel::Other, 2, 8, false)),
+                // UVWQ8888, UVLX8888
+                23 | 26 => Some(ImPixelFormat::new(ImColorModel::Other, 4, 8, false)),
+                // RGBA16161616F, RGBA16161616
+                24 | 25 => Some(ImPixelFormat::new(ImColorModel::RGB, 4, 16, true)),
+                // DXT1, DXT3, DXT5, DXT1_ONEBITALPHA and NONE
+                _ => None
+            };
+        }
+        return Ok(info);
     } else if size >= 24 && preamble.starts_with(b"FORM") && matches!(&preamble[8..12], b"ILBM"|b"PBM ") && &preamble[12..16] == b"BMHD" {
         let chunk_len = u32::from_be_bytes(array4!(preamble, 4));
         if chunk_len < 32 {
@@ -1146,21 +6609,41 @@ where R: Read, R: Seek {
         let w = u16::from_be_bytes(array2!(preamble, 20));
         let h = u16::from_be_bytes(array2!(preamble, 22));
 
-        return Ok(ImInfo {
-            format: ImFormat::ILBM,
-            width:  w as u64,
-            height: h as u64,
-        })
+        let mut info = ImInfo::new(ImFormat::ILBM, w as u64, h as u64);
+        if size >= 30 {
+            let planes = preamble[28] as u16;
+            // mskHasMask
+            let has_mask = preamble[29] == 1;
+            info.pixel_format = match planes {
+                24 => Some(ImPixelFormat::new(ImColorModel::RGB, 3 + has_mask as u16, 8, has_mask)),
+                32 => Some(ImPixelFormat::new(ImColorModel::RGB, 4, 8, true)),
+                1..=8 => Some(ImPixelFormat::new(ImColorModel::Indexed, 1 + has_mask as u16, planes, has_mask)),
+                _ => None
+            };
+        }
+        return Ok(info);
     } else if size >= 30 && preamble[1] < 2 && preamble[2] < 12 && is_tga(file)? {
         // TGA
         let w = u16::from_le_bytes(array2!(preamble, 12));
         let h = u16::from_le_bytes(array2!(preamble, 14));
 
-        return Ok(ImInfo {
-            format: ImFormat::TGA,
-            width:  w as u64,
-            height: h as u64,
-        });
+        let depth = preamble[16] as u16;
+        let alpha_bits = preamble[17] & 0xf;
+
+        let mut info = ImInfo::new(ImFormat::TGA, w as u64, h as u64);
+        info.pixel_format = match (preamble[2] & 0x7, depth) {
+            // color-mapped
+            (1, _) => Some(ImPixelFormat::new(ImColorModel::Indexed, 1, depth, false)),
+            // true-color
+            (2, 15 | 16) => Some(ImPixelFormat::new(ImColorModel::RGB, 3 + (alpha_bits > 0) as u16, 5, alpha_bits > 0)),
+            (2, 24) => Some(ImPixelFormat::new(ImColorModel::RGB, 3, 8, false)),
+            (2, 32) => Some(ImPixelFormat::new(ImColorModel::RGB, 3 + (alpha_bits > 0) as u16, 8, alpha_bits > 0)),
+            // grayscale
+            (3, 8)  => Some(ImPixelFormat::new(ImColorModel::Gray, 1, 8, false)),
+            (3, 16) => Some(ImPixelFormat::new(ImColorModel::Gray, 2, 8, true)),
+            _ => None
+        };
+        return Ok(info);
     }
     return Err(ImError::UnknownFormat);
 }
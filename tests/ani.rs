@@ -0,0 +1,75 @@
+use imsz::{imsz, imsz_with_options, ImAnimation, ImDetails, ImError, ImFormat, ImHotspot, ImOptions};
+
+fn chunk(name: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut data = name.to_vec();
+    data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    data.extend_from_slice(payload);
+    if payload.len() % 2 != 0 {
+        data.push(0);
+    }
+    data
+}
+
+fn ani(chunks: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = b"RIFF\0\0\0\0ACON".to_vec();
+    for item in chunks {
+        data.extend_from_slice(item);
+    }
+    let riff_size = (data.len() - 8) as u32;
+    data[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    data
+}
+
+fn anih(frames: u32, width: u32, height: u32, attributes: u32) -> Vec<u8> {
+    let mut payload = Vec::new();
+    for value in [36, frames, frames, width, height, 0, 0, 10, attributes] {
+        payload.extend_from_slice(&value.to_le_bytes());
+    }
+    chunk(b"anih", &payload)
+}
+
+fn frames(icons: &[&[u8]]) -> Vec<u8> {
+    let mut payload = b"fram".to_vec();
+    for icon in icons {
+        payload.extend_from_slice(&chunk(b"icon", icon));
+    }
+    chunk(b"LIST", &payload)
+}
+
+// 32 x 16 cursor with the hotspot at 5, 3 and a truncated image
+const CURSOR: &[u8] = b"\x00\x00\x02\x00\x01\x00\x20\x10\x00\x00\x05\x00\x03\x00\x00\x00\x00\x00\x16\x00\x00\x00";
+
+#[test]
+fn ani_cursor_frames() {
+    let info_list = chunk(b"LIST", &[b"INFO".to_vec(), chunk(b"INAM", b"cursor\0")].concat());
+    let data = ani(&[info_list, anih(3, 0, 0, 1), chunk(b"rate", &[0; 12]), frames(&[CURSOR, CURSOR, CURSOR])]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::ANI);
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(info.details, ImDetails::CUR(ImHotspot { x: 5, y: 3 }));
+    assert_eq!(info.animation, None);
+
+    let options = ImOptions::new().animation(true);
+    let info = imsz_with_options(&data[..], &options).unwrap();
+    assert_eq!(info.animation, Some(ImAnimation { frames: 3, loop_count: None }));
+}
+
+#[test]
+fn ani_header_size() {
+    // raw frames, the size is only in the header
+    let data = ani(&[anih(2, 32, 16, 0), frames(&[&[0; 8], &[0; 8]])]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(info.details, ImDetails::None);
+}
+
+#[test]
+fn ani_broken() {
+    // no anih chunk
+    let data = ani(&[frames(&[CURSOR])]);
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::ANI))));
+
+    // no size anywhere
+    let data = ani(&[anih(1, 0, 0, 1), frames(&[b"not a cursor"])]);
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::ANI))));
+}
@@ -0,0 +1,88 @@
+use imsz::{imsz_with_options, imsz_from_path, imsz_from_path_with_options, ImAnimation, ImOptions};
+
+fn get_testdata(fname: &str) -> std::path::PathBuf {
+    let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("testdata");
+    path.push(fname);
+    path
+}
+
+fn options() -> ImOptions {
+    ImOptions::new().animation(true)
+}
+
+#[test]
+fn animation_opt_in() {
+    let info = imsz_from_path(get_testdata("image.gif")).unwrap();
+    assert_eq!(info.animation, None);
+}
+
+#[test]
+fn animation_still_images() {
+    for fname in ["image.gif", "image.png", "image_lossless.webp", "image_lossy.webp", "image_lossy_vp8x.webp", "image_lossless_vp8x.webp"] {
+        let info = imsz_from_path_with_options(get_testdata(fname), &options()).unwrap();
+        assert_eq!(info.animation, Some(ImAnimation { frames: 1, loop_count: None }), "{fname}");
+    }
+}
+
+#[test]
+fn animation_gif() {
+    let mut data = Vec::new();
+    // header, logical screen descriptor with a 2 color global color table
+    data.extend_from_slice(b"GIF89a\x20\x00\x10\x00\x80\x00\x00");
+    data.extend_from_slice(b"\x00\x00\x00\xff\xff\xff");
+    // NETSCAPE2.0 application extension, loop 3 times
+    data.extend_from_slice(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x03\x00\x00");
+    for _ in 0..3 {
+        // graphic control extension
+        data.extend_from_slice(b"\x21\xf9\x04\x00\x0a\x00\x00\x00");
+        // image descriptor with a local color table
+        data.extend_from_slice(b"\x2c\x00\x00\x00\x00\x20\x00\x10\x00\x80");
+        data.extend_from_slice(b"\x00\x00\x00\xff\xff\xff");
+        // image data
+        data.extend_from_slice(b"\x02\x02\x4c\x01\x00");
+    }
+    data.push(0x3b);
+
+    let info = imsz_with_options(&data[..], &options()).unwrap();
+    assert_eq!(info.width,  32);
+    assert_eq!(info.height, 16);
+    assert_eq!(info.animation, Some(ImAnimation { frames: 3, loop_count: Some(3) }));
+    assert!(info.animation.unwrap().is_animated());
+}
+
+#[test]
+fn animation_apng() {
+    let mut data = Vec::new();
+    data.extend_from_slice(b"\x89PNG\r\n\x1a\n");
+    data.extend_from_slice(b"\x00\x00\x00\x0dIHDR\x00\x00\x00\x20\x00\x00\x00\x10\x08\x06\x00\x00\x00\x00\x00\x00\x00");
+    data.extend_from_slice(b"\x00\x00\x00\x01sRGB\x00\x00\x00\x00\x00");
+    data.extend_from_slice(b"\x00\x00\x00\x08acTL\x00\x00\x00\x05\x00\x00\x00\x00\x00\x00\x00\x00");
+    data.extend_from_slice(b"\x00\x00\x00\x00IDAT\x00\x00\x00\x00");
+
+    let info = imsz_with_options(&data[..], &options()).unwrap();
+    assert_eq!(info.animation, Some(ImAnimation { frames: 5, loop_count: Some(0) }));
+}
+
+#[test]
+fn animation_webp() {
+    let mut chunks = Vec::new();
+    // VP8X with animation flag, 32 x 16
+    chunks.extend_from_slice(b"VP8X\x0a\x00\x00\x00\x02\x00\x00\x00\x1f\x00\x00\x0f\x00\x00");
+    // ANIM, loop 2 times
+    chunks.extend_from_slice(b"ANIM\x06\x00\x00\x00\x00\x00\x00\x00\x02\x00");
+    for _ in 0..4 {
+        // ANMF with odd size and padding
+        chunks.extend_from_slice(b"ANMF\x01\x00\x00\x00\x00\x00");
+    }
+    let mut data = Vec::new();
+    data.extend_from_slice(b"RIFF");
+    data.extend_from_slice(&(chunks.len() as u32 + 4).to_le_bytes());
+    data.extend_from_slice(b"WEBP");
+    data.extend_from_slice(&chunks);
+
+    let info = imsz_with_options(&data[..], &options()).unwrap();
+    assert_eq!(info.width,  32);
+    assert_eq!(info.height, 16);
+    assert_eq!(info.animation, Some(ImAnimation { frames: 4, loop_count: Some(2) }));
+}
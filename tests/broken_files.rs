@@ -1,5 +1,6 @@
 use imsz::{imsz, ImError, ImFormat, ImInfo, ImResult};
 
+#[allow(clippy::assertions_on_constants)]
 fn expect_broken(data: &[u8], expect_format: ImFormat) {
     let actual = imsz(data);
 
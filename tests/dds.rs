@@ -0,0 +1,103 @@
+use imsz::{imsz, imsz_all, ImColorModel, ImDetails, ImError, ImFormat, ImInfo, ImPixelFormat, ImRole, ImTextureInfo};
+
+fn get_testdata(fname: &str) -> std::path::PathBuf {
+    let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("testdata");
+    path.push(fname);
+    path
+}
+
+fn texture(info: &ImInfo) -> ImTextureInfo {
+    match info.details {
+        ImDetails::Texture(texture) => texture,
+        _ => panic!("no texture details: {:?}", info.details),
+    }
+}
+
+fn set_u32(data: &mut [u8], offset: usize, value: u32) {
+    data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn dx10(data: &[u8], dxgi_format: u32, dimension: u32, misc_flag: u32, array_size: u32) -> Vec<u8> {
+    let mut data = data[..128].to_vec();
+    // DDPF_FOURCC
+    set_u32(&mut data, 80, 0x4);
+    data[84..88].copy_from_slice(b"DX10");
+    for value in [dxgi_format, dimension, misc_flag, array_size, 0] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data
+}
+
+#[test]
+fn dds_legacy() {
+    let mut data = std::fs::read(get_testdata("image.dds")).unwrap();
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(texture(&info), ImTextureInfo::new(1, 1, 1, 1));
+
+    // DDSD_MIPMAPCOUNT, full mipmap chain
+    data[10] |= 0x02;
+    set_u32(&mut data, 28, 6);
+    assert_eq!(texture(&imsz(&data[..]).unwrap()), ImTextureInfo::new(1, 1, 1, 6));
+
+    // incomplete cube map, only +X and -X
+    set_u32(&mut data, 112, 0x0E00);
+    assert_eq!(texture(&imsz(&data[..]).unwrap()), ImTextureInfo::new(1, 1, 2, 6));
+
+    // DDSD_DEPTH, DDSCAPS2_VOLUME
+    data[10] |= 0x80;
+    set_u32(&mut data, 24, 8);
+    set_u32(&mut data, 112, 0x200000);
+    assert_eq!(texture(&imsz(&data[..]).unwrap()), ImTextureInfo::new(8, 1, 1, 6));
+}
+
+#[test]
+fn dds_dx10() {
+    let data = std::fs::read(get_testdata("image.dds")).unwrap();
+
+    // R16G16B16A16_FLOAT cube map array with 2 cubes
+    let info = imsz(&dx10(&data, 10, 3, 0x4, 2)[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::RGB, 4, 16, true)));
+    assert_eq!(texture(&info), ImTextureInfo::new(1, 2, 6, 1));
+    assert_eq!(imsz_all(&dx10(&data, 10, 3, 0x4, 2)[..]).unwrap().len(), 12);
+
+    // BC7_UNORM array
+    let info = imsz(&dx10(&data, 98, 3, 0, 4)[..]).unwrap();
+    assert_eq!(info.pixel_format, None);
+    assert_eq!(texture(&info), ImTextureInfo::new(1, 4, 1, 1));
+
+    // R8_UNORM volume texture
+    let mut volume = dx10(&data, 61, 4, 0, 1);
+    volume[10] |= 0x80;
+    set_u32(&mut volume, 24, 4);
+    let info = imsz(&volume[..]).unwrap();
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::Gray, 1, 8, false)));
+    assert_eq!(texture(&info), ImTextureInfo::new(4, 1, 1, 1));
+
+    // 1D texture with mipmap levels
+    let mut line = dx10(&data, 28, 2, 0, 1);
+    line[10] |= 0x02;
+    set_u32(&mut line, 28, 6);
+    let images = imsz_all(&line[..]).unwrap();
+    assert_eq!(images.len(), 6);
+    assert_eq!(images[5].role, ImRole::MipLevel { layer: 0, level: 5 });
+    assert_eq!((images[5].width, images[5].height), (1, 1));
+
+    // huge cube map array with a full mipmap chain, the listing is capped
+    let mut huge = dx10(&data, 10, 3, 0x4, u32::MAX);
+    huge[10] |= 0x02;
+    set_u32(&mut huge, 28, u32::MAX);
+    let info = imsz(&huge[..]).unwrap();
+    assert_eq!(texture(&info), ImTextureInfo::new(1, u32::MAX, 6, u32::MAX));
+    let images = imsz_all(&huge[..]).unwrap();
+    assert_eq!(images.len(), 4096);
+    assert_eq!(images[4095].role, ImRole::MipLevel { layer: 63, level: 63 });
+
+    // invalid resource dimension
+    assert!(matches!(imsz(&dx10(&data, 28, 1, 0, 1)[..]), Err(ImError::ParserError(ImFormat::DDS))));
+
+    // truncated DDS_HEADER_DXT10
+    let truncated = &dx10(&data, 28, 3, 0, 1)[..136];
+    assert!(matches!(imsz(truncated), Err(ImError::ParserError(ImFormat::DDS))));
+}
@@ -0,0 +1,127 @@
+use imsz::{imsz, imsz_all, ImDetails, ImError, ImExrBox, ImExrInfo, ImExrPart, ImExrPartType, ImFormat, ImInfo, ImRole};
+
+fn attribute(name: &str, type_name: &str, value: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(name.as_bytes());
+    data.push(0);
+    data.extend_from_slice(type_name.as_bytes());
+    data.push(0);
+    data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    data.extend_from_slice(value);
+    data
+}
+
+fn box2i(name: &str, x_min: i32, y_min: i32, x_max: i32, y_max: i32) -> Vec<u8> {
+    let mut value = Vec::new();
+    for coord in [x_min, y_min, x_max, y_max] {
+        value.extend_from_slice(&coord.to_le_bytes());
+    }
+    attribute(name, "box2i", &value)
+}
+
+fn exr(flags: u32, headers: &[Vec<Vec<u8>>]) -> Vec<u8> {
+    let mut data = b"\x76\x2f\x31\x01".to_vec();
+    data.extend_from_slice(&(2 | flags).to_le_bytes());
+    for header in headers {
+        for attribute in header {
+            data.extend_from_slice(attribute);
+        }
+        data.push(0);
+    }
+    if flags & 0x1000 != 0 {
+        data.push(0);
+    }
+    data
+}
+
+fn exr_info(info: &ImInfo) -> &ImExrInfo {
+    match &info.details {
+        ImDetails::OpenEXR(exr_info) => exr_info,
+        _ => panic!("no OpenEXR details: {:?}", info.details),
+    }
+}
+
+#[test]
+fn exr_data_window() {
+    let data = exr(0, &[vec![
+        box2i("dataWindow", -8, -4, 39, 19),
+        attribute("compression", "compression", b"\x00"),
+        box2i("displayWindow", 0, 0, 31, 15),
+    ]]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::OpenEXR);
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(exr_info(&info), &ImExrInfo {
+        tiled: false,
+        deep: false,
+        multipart: false,
+        parts: vec![ImExrPart {
+            name: None,
+            part_type: Some(ImExrPartType::Scanline),
+            display_window: ImExrBox::new(0, 0, 31, 15),
+            data_window: ImExrBox::new(-8, -4, 39, 19),
+        }],
+    });
+    assert_eq!(exr_info(&info).parts[0].data_window.width(), 48);
+
+    // tiled single-part file
+    let data = exr(0x200, &[vec![box2i("displayWindow", 0, 0, 31, 15), box2i("dataWindow", 0, 0, 31, 15)]]);
+    let info = imsz(&data[..]).unwrap();
+    assert!(exr_info(&info).tiled);
+    assert_eq!(exr_info(&info).parts[0].part_type, Some(ImExrPartType::Tiled));
+}
+
+#[test]
+fn exr_multipart() {
+    let data = exr(0x1000 | 0x800, &[
+        vec![
+            attribute("name", "string", b"beauty"),
+            attribute("type", "string", b"scanlineimage"),
+            box2i("displayWindow", 0, 0, 31, 15),
+            box2i("dataWindow", 0, 0, 31, 15),
+        ],
+        vec![
+            attribute("name", "string", b"depth"),
+            attribute("type", "string", b"deeptile"),
+            box2i("displayWindow", 0, 0, 15, 7),
+            box2i("dataWindow", 2, 2, 9, 5),
+        ],
+    ]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+    let exr_info = exr_info(&info);
+    assert!(exr_info.multipart);
+    assert!(exr_info.deep);
+    assert_eq!(exr_info.parts.len(), 2);
+    assert_eq!(exr_info.parts[1], ImExrPart {
+        name: Some("depth".to_string()),
+        part_type: Some(ImExrPartType::DeepTiled),
+        display_window: ImExrBox::new(0, 0, 15, 7),
+        data_window: ImExrBox::new(2, 2, 9, 5),
+    });
+
+    let images = imsz_all(&data[..]).unwrap();
+    let actual: Vec<_> = images.iter().map(|image| (image.role, image.width, image.height)).collect();
+    assert_eq!(actual, vec![(ImRole::Primary, 32, 16), (ImRole::Page, 16, 8)]);
+}
+
+#[test]
+fn exr_broken() {
+    // missing dataWindow
+    let data = exr(0, &[vec![box2i("displayWindow", 0, 0, 31, 15)]]);
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::OpenEXR))));
+
+    // empty window
+    let data = exr(0, &[vec![box2i("displayWindow", 0, 0, -1, 15), box2i("dataWindow", 0, 0, 31, 15)]]);
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::OpenEXR))));
+
+    // multi-part file without any part
+    let data = exr(0x1000, &[]);
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::OpenEXR))));
+
+    // overly long attribute name
+    let mut data = exr(0, &[]);
+    data.truncate(8);
+    data.resize(1024, b'a');
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::OpenEXR))));
+}
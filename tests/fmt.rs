@@ -23,6 +23,31 @@ fn fmt_debug() {
         (ImFormat::DIB,     "DIB"),
         (ImFormat::VTF,     "VTF"),
         (ImFormat::ILBM,    "ILBM"),
+        (ImFormat::CUR,     "CUR"),
+        (ImFormat::DNG,     "DNG"),
+        (ImFormat::CR2,     "CR2"),
+        (ImFormat::NEF,     "NEF"),
+        (ImFormat::ARW,     "ARW"),
+        (ImFormat::ORF,     "ORF"),
+        (ImFormat::RW2,     "RW2"),
+        (ImFormat::PEF,     "PEF"),
+        (ImFormat::RAF,     "RAF"),
+        (ImFormat::CR3,     "CR3"),
+        (ImFormat::JXL,     "JXL"),
+        (ImFormat::PBM,     "PBM"),
+        (ImFormat::PGM,     "PGM"),
+        (ImFormat::PPM,     "PPM"),
+        (ImFormat::PAM,     "PAM"),
+        (ImFormat::PFM,     "PFM"),
+        (ImFormat::SVG,     "SVG"),
+        (ImFormat::KTX,     "KTX"),
+        (ImFormat::KTX2,    "KTX2"),
+        (ImFormat::PVR,     "PVR"),
+        (ImFormat::ASTC,    "ASTC"),
+        (ImFormat::BASIS,   "BASIS"),
+        (ImFormat::HDR,     "HDR"),
+        (ImFormat::ICNS,    "ICNS"),
+        (ImFormat::ANI,     "ANI"),
     ];
     for (format, expected) in data {
         let actual = format!("{format:?}");
@@ -53,6 +78,31 @@ fn fmt_display() {
         (ImFormat::DIB,     "DIB"),
         (ImFormat::VTF,     "VTF"),
         (ImFormat::ILBM,    "ILBM"),
+        (ImFormat::CUR,     "CUR"),
+        (ImFormat::DNG,     "DNG"),
+        (ImFormat::CR2,     "CR2"),
+        (ImFormat::NEF,     "NEF"),
+        (ImFormat::ARW,     "ARW"),
+        (ImFormat::ORF,     "ORF"),
+        (ImFormat::RW2,     "RW2"),
+        (ImFormat::PEF,     "PEF"),
+        (ImFormat::RAF,     "RAF"),
+        (ImFormat::CR3,     "CR3"),
+        (ImFormat::JXL,     "JPEG XL"),
+        (ImFormat::PBM,     "PBM"),
+        (ImFormat::PGM,     "PGM"),
+        (ImFormat::PPM,     "PPM"),
+        (ImFormat::PAM,     "PAM"),
+        (ImFormat::PFM,     "PFM"),
+        (ImFormat::SVG,     "SVG"),
+        (ImFormat::KTX,     "KTX"),
+        (ImFormat::KTX2,    "KTX2"),
+        (ImFormat::PVR,     "PVR"),
+        (ImFormat::ASTC,    "ASTC"),
+        (ImFormat::BASIS,   "Basis"),
+        (ImFormat::HDR,     "HDR"),
+        (ImFormat::ICNS,    "ICNS"),
+        (ImFormat::ANI,     "ANI"),
     ];
     for (format, expected) in data {
         let actual = format!("{format}");
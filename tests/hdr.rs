@@ -0,0 +1,60 @@
+use imsz::{imsz, imsz_with_options, ImError, ImFormat, ImOptions, ImOrientation};
+
+fn hdr(resolution: &str) -> Vec<u8> {
+    let mut data = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n".to_vec();
+    data.extend_from_slice(resolution.as_bytes());
+    data
+}
+
+#[test]
+fn hdr_orientation() {
+    let options = ImOptions::new().orientation(true);
+    for (resolution, orientation) in [
+        ("-Y 16 +X 32\n", ImOrientation::TopLeft),
+        ("-Y 16 -X 32\n", ImOrientation::TopRight),
+        ("+Y 16 -X 32\n", ImOrientation::BottomRight),
+        ("+Y 16 +X 32\n", ImOrientation::BottomLeft),
+        ("+X 32 -Y 16\n", ImOrientation::LeftTop),
+        ("-X 32 -Y 16\n", ImOrientation::RightTop),
+        ("-X 32 +Y 16\n", ImOrientation::RightBottom),
+        ("+X 32 +Y 16\n", ImOrientation::LeftBottom),
+    ] {
+        let info = imsz_with_options(&hdr(resolution)[..], &options).unwrap();
+        assert_eq!(info.format, ImFormat::HDR);
+        assert_eq!(info.orientation, Some(orientation), "{resolution:?}");
+        assert_eq!(info.display_size(), (32, 16), "{resolution:?}");
+    }
+
+    // scanlines are columns, so the stored size is transposed
+    let info = imsz(&hdr("+X 32 -Y 16\n")[..]).unwrap();
+    assert_eq!((info.width, info.height), (16, 32));
+    assert_eq!(info.orientation, None);
+}
+
+#[test]
+fn hdr_header() {
+    // long header, the resolution string at the end of the file
+    let mut data = b"#?RGBE\n".to_vec();
+    for _ in 0..100 {
+        data.extend_from_slice(b"# a comment that needs more than one read\n");
+    }
+    data.extend_from_slice(b"\n-Y 16 +X 32");
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+}
+
+#[test]
+fn hdr_broken() {
+    assert!(matches!(imsz(&hdr("Y 16 X 32\n")[..]), Err(ImError::ParserError(ImFormat::HDR))));
+    assert!(matches!(imsz(&hdr("-Y 16 -Y 32\n")[..]), Err(ImError::ParserError(ImFormat::HDR))));
+    assert!(matches!(imsz(&hdr("-Y 16 +X\n")[..]), Err(ImError::ParserError(ImFormat::HDR))));
+    assert!(matches!(imsz(&hdr("-Y -16 +X 32\n")[..]), Err(ImError::ParserError(ImFormat::HDR))));
+
+    // no empty line
+    assert!(matches!(imsz(&b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n"[..]), Err(ImError::ParserError(ImFormat::HDR))));
+
+    // the header is bounded
+    let mut data = b"#?RADIANCE\n".to_vec();
+    data.resize(1024 * 1024, b'#');
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::HDR))));
+}
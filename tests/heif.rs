@@ -0,0 +1,188 @@
+use imsz::{imsz, imsz_all, ImError, ImFormat, ImRole};
+
+fn iso_box(name: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&(payload.len() as u32 + 8).to_be_bytes());
+    data.extend_from_slice(name);
+    data.extend_from_slice(payload);
+    data
+}
+
+fn large_box(name: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut data = 1u32.to_be_bytes().to_vec();
+    data.extend_from_slice(name);
+    data.extend_from_slice(&(payload.len() as u64 + 16).to_be_bytes());
+    data.extend_from_slice(payload);
+    data
+}
+
+fn full_box(name: &[u8; 4], version: u8, payload: &[u8]) -> Vec<u8> {
+    let mut data = vec![version, 0, 0, 0];
+    data.extend_from_slice(payload);
+    iso_box(name, &data)
+}
+
+fn ispe(width: u32, height: u32) -> Vec<u8> {
+    let mut data = width.to_be_bytes().to_vec();
+    data.extend_from_slice(&height.to_be_bytes());
+    full_box(b"ispe", 0, &data)
+}
+
+fn clap(width: (u32, u32), height: (u32, u32)) -> Vec<u8> {
+    let mut data = Vec::new();
+    for value in [width.0, width.1, height.0, height.1, 0, 1, 0, 1] {
+        data.extend_from_slice(&value.to_be_bytes());
+    }
+    iso_box(b"clap", &data)
+}
+
+/// Items are (item ID, item type, property indices), the first item is the
+/// primary item.
+fn meta(items: &[(u16, &[u8; 4], &[u8])], properties: &[Vec<u8>], extra: &[Vec<u8>]) -> Vec<u8> {
+    let mut iinf = (items.len() as u16).to_be_bytes().to_vec();
+    let mut ipma = (items.len() as u32).to_be_bytes().to_vec();
+    for (item_id, item_type, associations) in items {
+        let mut infe = item_id.to_be_bytes().to_vec();
+        infe.extend_from_slice(b"\0\0");
+        infe.extend_from_slice(*item_type);
+        infe.push(0);
+        iinf.extend_from_slice(&full_box(b"infe", 2, &infe));
+
+        ipma.extend_from_slice(&item_id.to_be_bytes());
+        ipma.push(associations.len() as u8);
+        ipma.extend_from_slice(associations);
+    }
+
+    let mut iprp = iso_box(b"ipco", &properties.concat());
+    iprp.extend_from_slice(&full_box(b"ipma", 0, &ipma));
+
+    let mut meta = full_box(b"iinf", 0, &iinf);
+    meta.extend_from_slice(&full_box(b"pitm", 0, &items[0].0.to_be_bytes()));
+    meta.extend_from_slice(&iso_box(b"iprp", &iprp));
+    for item in extra {
+        meta.extend_from_slice(item);
+    }
+    meta
+}
+
+fn heif(brands: &[u8], meta_box: &[u8]) -> Vec<u8> {
+    let mut data = iso_box(b"ftyp", brands);
+    data.extend_from_slice(meta_box);
+    data
+}
+
+#[test]
+fn heif_brands() {
+    let meta = full_box(b"meta", 0, &meta(&[(1, b"hvc1", b"\x81")], &[ispe(32, 16)], &[]));
+    for (brands, format) in [
+        (&b"mif1\0\0\0\0mif1heic"[..], ImFormat::HEIF),
+        (b"msf1\0\0\0\0msf1hevc", ImFormat::HEIF),
+        (b"heix\0\0\0\0mif1heix", ImFormat::HEIF),
+        (b"mif1\0\0\0\0mif1", ImFormat::HEIF),
+        (b"mif1\0\0\0\0mif1avif", ImFormat::AVIF),
+        (b"avis\0\0\0\0msf1avis", ImFormat::AVIF),
+        (b"avio\0\0\0\0mif1avio", ImFormat::AVIF),
+    ] {
+        let info = imsz(&heif(brands, &meta)[..]).unwrap();
+        assert_eq!(info.format, format, "{:?}", std::str::from_utf8(brands));
+        assert_eq!((info.width, info.height), (32, 16));
+    }
+
+    // other ISO-BMFF files like MP4 videos
+    assert!(matches!(imsz(&heif(b"isom\0\0\0\0isommp41", &meta)[..]), Err(ImError::UnknownFormat)));
+}
+
+#[test]
+fn heif_primary_item() {
+    // the size of the thumbnail comes first
+    let meta_box = full_box(b"meta", 0, &meta(&[(2, b"hvc1", b"\x82"), (1, b"hvc1", b"\x81")], &[ispe(8, 4), ispe(32, 16)], &[]));
+    let data = heif(b"heic\0\0\0\0mif1heic", &meta_box);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+
+    let images = imsz_all(&data[..]).unwrap();
+    let actual: Vec<_> = images.iter().map(|image| (image.role, image.width, image.height)).collect();
+    assert_eq!(actual, vec![(ImRole::Primary, 32, 16), (ImRole::Page, 8, 4)]);
+}
+
+#[test]
+fn heif_grid() {
+    // 2 x 1 grid of 32 x 32 tiles, the tile size is the first property
+    let mut dimg = 1u16.to_be_bytes().to_vec();
+    dimg.extend_from_slice(b"\x00\x02\x00\x02\x00\x03");
+    let iref = full_box(b"iref", 0, &iso_box(b"dimg", &dimg));
+    let items: [(u16, &[u8; 4], &[u8]); 3] = [(1, b"grid", b"\x02"), (2, b"hvc1", b"\x81"), (3, b"hvc1", b"\x81")];
+    let meta_box = full_box(b"meta", 0, &meta(&items, &[ispe(32, 32), ispe(64, 32)], std::slice::from_ref(&iref)));
+    let data = heif(b"heic\0\0\0\0mif1heic", &meta_box);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (64, 32));
+
+    let images = imsz_all(&data[..]).unwrap();
+    let actual: Vec<_> = images.iter().map(|image| (image.role, image.width, image.height)).collect();
+    assert_eq!(actual, vec![(ImRole::Primary, 64, 32), (ImRole::Tile, 32, 32), (ImRole::Tile, 32, 32)]);
+
+    // a truncated iloc box is ignored
+    let iloc_box = iso_box(b"iloc", b"\x01\x00\x00\x00\x44\x00\x00\x01\x00\x01");
+    let meta_box = full_box(b"meta", 0, &meta(&items, &[ispe(32, 32), ispe(64, 32)], &[iref.clone(), iloc_box]));
+    let info = imsz(&heif(b"heic\0\0\0\0mif1heic", &meta_box)[..]).unwrap();
+    assert_eq!((info.width, info.height), (64, 32));
+
+    // grid without ispe, the output size is read from the item data in idat
+    let mut iloc = 1u16.to_be_bytes().to_vec();
+    // item ID, construction method, data reference index, extent count
+    iloc.extend_from_slice(b"\x00\x01\x00\x01\x00\x00\x00\x01");
+    iloc.extend_from_slice(&0u32.to_be_bytes());
+    iloc.extend_from_slice(&8u32.to_be_bytes());
+    let mut iloc_box = vec![1, 0, 0, 0, 0x44, 0x00];
+    iloc_box.extend_from_slice(&iloc);
+    let iloc_box = iso_box(b"iloc", &iloc_box);
+    let idat = iso_box(b"idat", b"\x00\x00\x00\x01\x00\x40\x00\x20");
+    let items: [(u16, &[u8; 4], &[u8]); 3] = [(1, b"grid", b""), (2, b"hvc1", b"\x81"), (3, b"hvc1", b"\x81")];
+    let meta_box = full_box(b"meta", 0, &meta(&items, &[ispe(32, 32)], &[iref.clone(), iloc_box, idat]));
+    let info = imsz(&heif(b"heic\0\0\0\0mif1heic", &meta_box)[..]).unwrap();
+    assert_eq!((info.width, info.height), (64, 32));
+
+    // 32 bit output size in the mdat box
+    let mut iloc = vec![0, 0, 0, 0, 0x44, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01];
+    let grid_offset = iloc.len();
+    iloc.extend_from_slice(&[0; 4]);
+    iloc.extend_from_slice(&12u32.to_be_bytes());
+    let meta_box_len = |iloc: &[u8]| full_box(b"meta", 0, &meta(&items, &[ispe(32, 32)], &[iref.clone(), iso_box(b"iloc", iloc)])).len();
+    let mdat_offset = iso_box(b"ftyp", b"heic\0\0\0\0mif1heic").len() + meta_box_len(&iloc) + 8;
+    iloc[grid_offset..grid_offset + 4].copy_from_slice(&(mdat_offset as u32).to_be_bytes());
+    let meta_box = full_box(b"meta", 0, &meta(&items, &[ispe(32, 32)], &[iref, iso_box(b"iloc", &iloc)]));
+    let mut data = heif(b"heic\0\0\0\0mif1heic", &meta_box);
+    data.extend_from_slice(&iso_box(b"mdat", b"\x00\x01\x00\x01\x00\x00\x00\x40\x00\x00\x00\x20"));
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (64, 32));
+}
+
+#[test]
+fn heif_clean_aperture() {
+    // 32 x 18 coded, 64/2 x 16 shown
+    let meta_box = full_box(b"meta", 0, &meta(&[(1, b"av01", b"\x81\x82")], &[ispe(32, 18), clap((64, 2), (16, 1))], &[]));
+    let info = imsz(&heif(b"avif\0\0\0\0mif1avif", &meta_box)[..]).unwrap();
+    assert_eq!(info.format, ImFormat::AVIF);
+    assert_eq!((info.width, info.height), (32, 16));
+}
+
+#[test]
+fn heif_largesize() {
+    let meta_data = meta(&[(1, b"hvc1", b"\x81")], &[ispe(32, 16)], &[]);
+    let mut meta_payload = vec![0, 0, 0, 0];
+    meta_payload.extend_from_slice(&meta_data);
+
+    let mut data = iso_box(b"ftyp", b"heic\0\0\0\0mif1heic");
+    data.extend_from_slice(&large_box(b"free", &[0; 16]));
+    data.extend_from_slice(&large_box(b"meta", &meta_payload));
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+
+    // meta box extending to the end of the file
+    let mut data = iso_box(b"ftyp", b"heic\0\0\0\0mif1heic");
+    data.extend_from_slice(&0u32.to_be_bytes());
+    data.extend_from_slice(b"meta");
+    data.extend_from_slice(&meta_payload);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+}
@@ -0,0 +1,101 @@
+use imsz::{imsz, imsz_with_options, ImColorModel, ImDetails, ImError, ImFormat, ImIcnsIcon, ImIcnsInfo, ImInfo, ImOptions, ImPixelFormat, ImRole};
+
+fn icns_info(info: &ImInfo) -> ImIcnsInfo {
+    match &info.details {
+        ImDetails::ICNS(icns_info) => icns_info.clone(),
+        _ => panic!("no ICNS details: {:?}", info.details),
+    }
+}
+
+fn icns(elements: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+    let mut data = b"icns\0\0\0\0".to_vec();
+    for (element_type, payload) in elements {
+        data.extend_from_slice(*element_type);
+        data.extend_from_slice(&(payload.len() as u32 + 8).to_be_bytes());
+        data.extend_from_slice(payload);
+    }
+    let file_size = data.len() as u32;
+    data[4..8].copy_from_slice(&file_size.to_be_bytes());
+    data
+}
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut data = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+    data.extend_from_slice(&width.to_be_bytes());
+    data.extend_from_slice(&height.to_be_bytes());
+    data.extend_from_slice(b"\x08\x06\x00\x00\x00\x00\x00\x00\x00");
+    data
+}
+
+#[test]
+fn icns_embedded_headers() {
+    let data = icns(&[(b"is32", &[0; 8]), (b"s8mk", &[0; 256]), (b"ic07", &png(32, 16))]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::ICNS);
+    assert_eq!((info.width, info.height), (128, 128));
+    assert_eq!(icns_info(&info).icons, vec![
+        ImIcnsIcon { element_type: *b"is32", width:  16, height:  16, scale: 1, format: ImFormat::ICNS },
+        ImIcnsIcon { element_type: *b"ic07", width: 128, height: 128, scale: 1, format: ImFormat::ICNS },
+    ]);
+
+    let info = imsz_with_options(&data[..], &ImOptions::new().embedded_headers(true)).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(icns_info(&info).icons, vec![
+        ImIcnsIcon { element_type: *b"is32", width: 16, height: 16, scale: 1, format: ImFormat::ICNS },
+        ImIcnsIcon { element_type: *b"ic07", width: 32, height: 16, scale: 1, format: ImFormat::PNG },
+    ]);
+}
+
+#[test]
+fn icns_element_sizes() {
+    // the masks and the table of contents aren't icons
+    let data = icns(&[
+        (b"TOC ", b"is32\x00\x00\x00\x10"),
+        (b"ICN#", &[0; 256]),
+        (b"il32", &[0; 8]),
+        (b"l8mk", &[0; 1024]),
+        (b"ic11", b"\x00\x00\x00\x00"),
+        (b"ic10", b"\x00\x00\x00\x00"),
+    ]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (1024, 1024));
+    assert_eq!(info.pixel_format, None);
+    let sizes: Vec<_> = icns_info(&info).icons.iter().map(|icon| (icon.element_type, icon.width, icon.scale)).collect();
+    assert_eq!(sizes, vec![(*b"ICN#", 32, 1), (*b"il32", 32, 1), (*b"ic11", 32, 2), (*b"ic10", 1024, 2)]);
+
+    let data = icns(&[(b"il32", &[0; 8]), (b"ics8", &[0; 256])]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 32));
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::RGB, 3, 8, false)));
+}
+
+#[test]
+fn icns_embedded_images() {
+    let mut jp2 = b"\x00\x00\x00\x0cjP  \r\n\x87\n\x00\x00\x00\x14ftypjp2 \x00\x00\x00\x00jp2 ".to_vec();
+    jp2.extend_from_slice(b"\x00\x00\x00\x2djp2h\x00\x00\x00\x16ihdr\x00\x00\x01\x00\x00\x00\x01\x00\x00\x03\x07\x07\x00\x00");
+    jp2.extend_from_slice(b"\x00\x00\x00\x0fcolr\x01\x00\x00\x00\x00\x00\x10");
+    let data = icns(&[(b"ic08", &jp2), (b"ic13", &png(256, 256)), (b"ic05", &[0; 32 * 32 * 4])]);
+
+    let options = ImOptions::new().images(true).embedded_headers(true);
+    let info = imsz_with_options(&data[..], &options).unwrap();
+    let actual: Vec<_> = info.images.iter().map(|image| (image.index, image.width, image.height, image.format, image.role)).collect();
+    assert_eq!(actual, vec![
+        (0, 256, 256, ImFormat::JP2K, ImRole::Icon),
+        (1, 256, 256, ImFormat::PNG,  ImRole::Icon),
+        (2,  32,  32, ImFormat::ICNS, ImRole::Icon),
+    ]);
+    assert_eq!((info.width, info.height), (256, 256));
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::RGB, 4, 8, true)));
+}
+
+#[test]
+fn icns_broken() {
+    // no icons
+    let data = icns(&[(b"icnV", b"\x00\x00\x00\x00")]);
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::ICNS))));
+
+    // element smaller than its header
+    let mut data = icns(&[(b"is32", &[0; 8])]);
+    data[12..16].copy_from_slice(&4u32.to_be_bytes());
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::ICNS))));
+}
@@ -0,0 +1,70 @@
+use imsz::{imsz, imsz_from_path, imsz_with_options, ImColorModel, ImDetails, ImFormat, ImHotspot, ImImage, ImOptions, ImPixelFormat, ImRole};
+
+fn get_testdata(fname: &str) -> std::path::PathBuf {
+    let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("testdata");
+    path.push(fname);
+    path
+}
+
+// Icon with a 256 x 256 PNG image and a 16 x 16 bitmap image. The directory
+// claims the bitmap to be 48 x 48.
+fn icon() -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(b"\x00\x00\x01\x00\x02\x00");
+    // 256 x 256, 32 bits, PNG at 38
+    data.extend_from_slice(b"\x00\x00\x00\x00\x01\x00\x20\x00\x1a\x00\x00\x00\x26\x00\x00\x00");
+    // 48 x 48, 8 bits, bitmap at 64
+    data.extend_from_slice(b"\x30\x30\x00\x00\x01\x00\x08\x00\x28\x00\x00\x00\x40\x00\x00\x00");
+    // PNG signature and IHDR
+    data.extend_from_slice(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x01\x00\x00\x00\x01\x00\x08\x06");
+    // BITMAPINFOHEADER, the height includes the AND mask
+    data.extend_from_slice(b"\x28\x00\x00\x00\x10\x00\x00\x00\x20\x00\x00\x00\x01\x00\x08\x00\x00\x00\x00\x00");
+    data.extend_from_slice(&[0u8; 20]);
+    data
+}
+
+#[test]
+fn ico_size_256() {
+    let info = imsz(&icon()[..]).unwrap();
+    assert_eq!(info.format, ImFormat::ICO);
+    assert_eq!(info.width,  256);
+    assert_eq!(info.height, 256);
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::RGB, 4, 8, true)));
+}
+
+#[test]
+fn ico_embedded_headers() {
+    let options = ImOptions::new().images(true).embedded_headers(true);
+    let info = imsz_with_options(&icon()[..], &options).unwrap();
+    assert_eq!(info.width,  256);
+    assert_eq!(info.height, 256);
+    assert_eq!(info.images, vec![
+        ImImage { index: 0, width: 256, height: 256, format: ImFormat::PNG, role: ImRole::Icon },
+        ImImage { index: 1, width:  16, height:  16, format: ImFormat::DIB, role: ImRole::Icon },
+    ]);
+
+    let options = ImOptions::new().images(true);
+    let info = imsz_with_options(&icon()[..], &options).unwrap();
+    assert_eq!(info.images[1], ImImage { index: 1, width: 48, height: 48, format: ImFormat::ICO, role: ImRole::Icon });
+}
+
+#[test]
+fn ico_embedded_headers_testdata() {
+    let options = ImOptions::new().embedded_headers(true);
+    for fname in ["image.ico", "image.cur"] {
+        let info = imsz_with_options(get_testdata(fname), &options).unwrap();
+        assert_eq!(info.width,  32, "{fname}");
+        assert_eq!(info.height, 16, "{fname}");
+        assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::Indexed, 1, 4, false)), "{fname}");
+    }
+}
+
+#[test]
+fn cur_hotspot() {
+    let info = imsz_from_path(get_testdata("image.cur")).unwrap();
+    assert_eq!(info.format, ImFormat::CUR);
+    assert_eq!(info.details, ImDetails::CUR(ImHotspot { x: 3, y: 5 }));
+    // the bit count field holds the hotspot
+    assert_eq!(info.pixel_format, None);
+}
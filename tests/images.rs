@@ -0,0 +1,92 @@
+use imsz::{imsz_all, imsz_from_path, imsz_from_path_with_options, imsz_with_options, ImFormat, ImImage, ImOptions, ImRole};
+
+fn get_testdata(fname: &str) -> std::path::PathBuf {
+    let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("testdata");
+    path.push(fname);
+    path
+}
+
+fn options() -> ImOptions {
+    ImOptions::new().images(true)
+}
+
+fn image(index: usize, width: u64, height: u64, format: ImFormat, role: ImRole) -> ImImage {
+    ImImage { index, width, height, format, role }
+}
+
+#[test]
+fn images_opt_in() {
+    let info = imsz_from_path(get_testdata("image.ico")).unwrap();
+    assert!(info.images.is_empty());
+}
+
+#[test]
+fn images_single() {
+    for (fname, format) in [("image.png", ImFormat::PNG), ("image.gif", ImFormat::GIF), ("image.jpeg", ImFormat::JPEG)] {
+        let info = imsz_from_path_with_options(get_testdata(fname), &options()).unwrap();
+        assert_eq!(info.images, vec![image(0, 32, 16, format, ImRole::Primary)], "{fname}");
+    }
+}
+
+#[test]
+fn images_ico() {
+    let info = imsz_from_path_with_options(get_testdata("image.ico"), &options()).unwrap();
+    assert_eq!(info.images, vec![image(0, 32, 16, ImFormat::ICO, ImRole::Icon)]);
+}
+
+#[test]
+fn images_avif() {
+    // the alpha channel is an auxiliary image
+    let data = std::fs::read(get_testdata("image.avif")).unwrap();
+    let images = imsz_all(&data[..]).unwrap();
+    assert_eq!(images, vec![
+        image(0, 32, 16, ImFormat::AVIF, ImRole::Primary),
+        image(1, 32, 16, ImFormat::AVIF, ImRole::Auxiliary),
+    ]);
+}
+
+#[test]
+fn images_tiff_pages() {
+    let mut data = Vec::new();
+    data.extend_from_slice(b"II*\x00\x08\x00\x00\x00");
+    // IFD at 8: 32 x 16, next IFD at 38
+    data.extend_from_slice(b"\x02\x00");
+    data.extend_from_slice(b"\x00\x01\x03\x00\x01\x00\x00\x00\x20\x00\x00\x00");
+    data.extend_from_slice(b"\x01\x01\x03\x00\x01\x00\x00\x00\x10\x00\x00\x00");
+    data.extend_from_slice(b"\x26\x00\x00\x00");
+    // IFD at 38: 16 x 8, next IFD loops back to 8
+    data.extend_from_slice(b"\x02\x00");
+    data.extend_from_slice(b"\x00\x01\x03\x00\x01\x00\x00\x00\x10\x00\x00\x00");
+    data.extend_from_slice(b"\x01\x01\x03\x00\x01\x00\x00\x00\x08\x00\x00\x00");
+    data.extend_from_slice(b"\x08\x00\x00\x00");
+
+    let info = imsz_with_options(&data[..], &options()).unwrap();
+    assert_eq!(info.width,  32);
+    assert_eq!(info.height, 16);
+    assert_eq!(info.images, vec![
+        image(0, 32, 16, ImFormat::TIFF, ImRole::Primary),
+        image(1, 16,  8, ImFormat::TIFF, ImRole::Page),
+    ]);
+}
+
+#[test]
+fn images_dds_mipmaps() {
+    let mut data = std::fs::read(get_testdata("image.dds")).unwrap();
+    // DDSD_MIPMAPCOUNT, 3 levels
+    data[10] |= 0x02;
+    data[28..32].copy_from_slice(&3u32.to_le_bytes());
+
+    let images = imsz_all(&data[..]).unwrap();
+    assert_eq!(images, vec![
+        image(0, 32, 16, ImFormat::DDS, ImRole::MipLevel { layer: 0, level: 0 }),
+        image(1, 16,  8, ImFormat::DDS, ImRole::MipLevel { layer: 0, level: 1 }),
+        image(2,  8,  4, ImFormat::DDS, ImRole::MipLevel { layer: 0, level: 2 }),
+    ]);
+
+    // complete cube map
+    data[112..116].copy_from_slice(&0xFE00u32.to_le_bytes());
+    let images = imsz_all(&data[..]).unwrap();
+    assert_eq!(images.len(), 18);
+    assert_eq!(images[17], image(17, 8, 4, ImFormat::DDS, ImRole::MipLevel { layer: 5, level: 2 }));
+}
@@ -0,0 +1,68 @@
+use imsz::{imsz, ImColorModel, ImError, ImFormat, ImPixelFormat};
+
+fn siz(size: (u32, u32), offset: (u32, u32), components: &[u8]) -> Vec<u8> {
+    let mut segment = vec![0, 0];
+    for value in [size.0, size.1, offset.0, offset.1, size.0, size.1, 0, 0] {
+        segment.extend_from_slice(&value.to_be_bytes());
+    }
+    segment.extend_from_slice(&(components.len() as u16).to_be_bytes());
+    for &ssiz in components {
+        segment.extend_from_slice(&[ssiz, 1, 1]);
+    }
+
+    let mut data = b"\xff\x4f\xff\x51".to_vec();
+    data.extend_from_slice(&(segment.len() as u16 + 2).to_be_bytes());
+    data.extend_from_slice(&segment);
+    // COD marker
+    data.extend_from_slice(b"\xff\x52");
+    data
+}
+
+fn iso_box(name: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&(payload.len() as u32 + 8).to_be_bytes());
+    data.extend_from_slice(name);
+    data.extend_from_slice(payload);
+    data
+}
+
+#[test]
+fn jp2_codestream() {
+    // the image offset is subtracted from the reference grid
+    let info = imsz(&siz((40, 20), (8, 4), &[7, 7, 7])[..]).unwrap();
+    assert_eq!(info.format, ImFormat::JP2K);
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::RGB, 3, 8, false)));
+
+    // signed 12 bit gray with alpha
+    let info = imsz(&siz((32, 16), (0, 0), &[0x8b, 0x07])[..]).unwrap();
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::Gray, 2, 12, true)));
+}
+
+#[test]
+fn jp2_jpm() {
+    // NC, PHeight, PWidth, POrient, PColour
+    let phdr = iso_box(b"phdr", b"\x00\x01\x00\x00\x00\x10\x00\x00\x00\x20\x00\x00\x00\x00");
+    let mut data = b"\x00\x00\x00\x0cjP  \r\n\x87\n".to_vec();
+    data.extend_from_slice(&iso_box(b"ftyp", b"jpm \x00\x00\x00\x00jpm "));
+    data.extend_from_slice(&iso_box(b"rreq", b"\x01\x00\x00"));
+    data.extend_from_slice(&iso_box(b"page", &phdr));
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::JP2K);
+    assert_eq!((info.width, info.height), (32, 16));
+}
+
+#[test]
+fn jp2_broken() {
+    // empty image area
+    let data = siz((8, 4), (8, 4), &[7]);
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::JP2K))));
+
+    // no components
+    let data = siz((32, 16), (0, 0), &[]);
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::JP2K))));
+
+    // truncated SIZ marker segment
+    let data = siz((32, 16), (0, 0), &[7, 7, 7]);
+    assert!(matches!(imsz(&data[..20]), Err(ImError::ParserError(ImFormat::JP2K))));
+}
@@ -0,0 +1,174 @@
+use imsz::{imsz, imsz_all, ImDetails, ImFormat, ImJpegInfo, ImJpegProcess, ImRole};
+
+fn jpeg_with_segments(segments: &[(u8, &[u8])]) -> Vec<u8> {
+    let mut data = vec![0xff, 0xd8];
+    for (marker, payload) in segments {
+        data.extend_from_slice(&[0xff, *marker]);
+        data.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+        data.extend_from_slice(payload);
+    }
+    data.extend_from_slice(&[0xff, 0xda]);
+    data
+}
+
+// precision 8, height 16, width 32, 1 component
+const FRAME_HEADER: &[u8] = b"\x08\x00\x10\x00\x20\x01\x01\x11\x00";
+
+#[test]
+fn jpeg_sof_markers() {
+    let data = [
+        (0xc0, ImJpegProcess::Baseline,    false, false),
+        (0xc1, ImJpegProcess::Extended,    false, false),
+        (0xc2, ImJpegProcess::Progressive, false, false),
+        (0xc3, ImJpegProcess::Lossless,    false, false),
+        (0xc5, ImJpegProcess::Extended,    false, true),
+        (0xc6, ImJpegProcess::Progressive, false, true),
+        (0xc7, ImJpegProcess::Lossless,    false, true),
+        (0xc9, ImJpegProcess::Extended,    true,  false),
+        (0xca, ImJpegProcess::Progressive, true,  false),
+        (0xcb, ImJpegProcess::Lossless,    true,  false),
+        (0xcd, ImJpegProcess::Extended,    true,  true),
+        (0xce, ImJpegProcess::Progressive, true,  true),
+        (0xcf, ImJpegProcess::Lossless,    true,  true),
+    ];
+    for (marker, process, arithmetic, hierarchical) in data {
+        // DHT, DAC and JPG must be skipped
+        let buf = jpeg_with_segments(&[
+            (0xc4, b"\x00\x00"),
+            (0xcc, b"\x00\x00"),
+            (0xc8, b"\x00\x00"),
+            (marker, FRAME_HEADER),
+        ]);
+        let info = imsz(&buf[..]).unwrap();
+        assert_eq!(info.format, ImFormat::JPEG);
+        assert_eq!(info.width,  32);
+        assert_eq!(info.height, 16);
+        assert_eq!(info.details, ImDetails::JPEG(ImJpegInfo { process, arithmetic, hierarchical }), "marker: {marker:#x}");
+    }
+}
+
+#[test]
+fn jpeg_hierarchical() {
+    let buf = jpeg_with_segments(&[
+        // DHP: precision 8, height 16, width 32
+        (0xde, b"\x08\x00\x10\x00\x20\x01\x01\x11\x00"),
+        // first frame is downsampled
+        (0xc0, b"\x08\x00\x08\x00\x10\x01\x01\x11\x00"),
+    ]);
+    let info = imsz(&buf[..]).unwrap();
+    assert_eq!(info.width,  32);
+    assert_eq!(info.height, 16);
+    assert_eq!(info.details, ImDetails::JPEG(ImJpegInfo {
+        process: ImJpegProcess::Baseline,
+        arithmetic: false,
+        hierarchical: true,
+    }));
+}
+
+/// A baseline JPEG with 1 component and the given size, including EOI.
+fn small_jpeg(width: u16, height: u16) -> Vec<u8> {
+    let mut frame_header = vec![8];
+    frame_header.extend_from_slice(&height.to_be_bytes());
+    frame_header.extend_from_slice(&width.to_be_bytes());
+    frame_header.extend_from_slice(b"\x01\x01\x11\x00");
+    let mut data = jpeg_with_segments(&[(0xc0, &frame_header)]);
+    data.extend_from_slice(b"\x00\x02\x00\xff\xd9");
+    data
+}
+
+fn roles_and_sizes(data: &[u8]) -> Vec<(ImRole, u64, u64)> {
+    let images = imsz_all(data).unwrap();
+    for (index, image) in images.iter().enumerate() {
+        assert_eq!(image.index, index);
+        assert_eq!(image.format, ImFormat::JPEG);
+    }
+    images.iter().map(|image| (image.role, image.width, image.height)).collect()
+}
+
+#[test]
+fn jpeg_mpo() {
+    let second = small_jpeg(32, 16);
+    let large_thumbnail = small_jpeg(8, 4);
+
+    // APP2 with a big endian MP header: MPFVersion and MPEntry with 3 entries
+    let mut mpf = b"MPF\0MM\0*\x00\x00\x00\x08".to_vec();
+    mpf.extend_from_slice(b"\x00\x02");
+    mpf.extend_from_slice(b"\xb0\x00\x00\x07\x00\x00\x00\x040100");
+    mpf.extend_from_slice(b"\xb0\x02\x00\x07\x00\x00\x00\x30\x00\x00\x00\x26");
+    mpf.extend_from_slice(&[0; 4]);
+    let entries_offset = mpf.len();
+    mpf.resize(entries_offset + 48, 0);
+
+    let mut data = jpeg_with_segments(&[(0xe2, &mpf), (0xc0, FRAME_HEADER)]);
+    data.extend_from_slice(b"\x00\x02\x00\xff\xd9");
+    // SOI, APP2 marker and length, "MPF\0"
+    let segment_start = 2 + 2 + 2;
+    let mpf_start = segment_start + 4;
+    let second_offset = data.len();
+    data.extend_from_slice(&second);
+    let thumbnail_offset = data.len();
+    data.extend_from_slice(&large_thumbnail);
+
+    // baseline MP primary image, disparity image, large thumbnail (VGA)
+    let entries = [
+        (0x2003_0000u32, 0u32),
+        (0x0002_0002, (second_offset - mpf_start) as u32),
+        (0x0001_0001, (thumbnail_offset - mpf_start) as u32),
+    ];
+    for (index, (attribute, offset)) in entries.iter().enumerate() {
+        let entry = segment_start + entries_offset + index * 16;
+        data[entry..entry + 4].copy_from_slice(&attribute.to_be_bytes());
+        data[entry + 8..entry + 12].copy_from_slice(&offset.to_be_bytes());
+    }
+
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+    assert!(info.images.is_empty());
+
+    assert_eq!(roles_and_sizes(&data), vec![
+        (ImRole::Primary,   32, 16),
+        (ImRole::Page,      32, 16),
+        (ImRole::Thumbnail,  8,  4),
+    ]);
+}
+
+#[test]
+fn jpeg_exif_thumbnail() {
+    let thumbnail = small_jpeg(16, 8);
+    // little endian IFD0 with only the orientation, IFD1 with
+    // JPEGInterchangeFormat and JPEGInterchangeFormatLength
+    let mut exif = b"Exif\0\0II*\0\x08\x00\x00\x00".to_vec();
+    exif.extend_from_slice(b"\x01\x00\x12\x01\x03\x00\x01\x00\x00\x00\x01\x00\x00\x00\x1a\x00\x00\x00");
+    exif.extend_from_slice(b"\x02\x00");
+    exif.extend_from_slice(b"\x01\x02\x04\x00\x01\x00\x00\x00\x38\x00\x00\x00");
+    exif.extend_from_slice(b"\x02\x02\x04\x00\x01\x00\x00\x00");
+    exif.extend_from_slice(&(thumbnail.len() as u32).to_le_bytes());
+    exif.extend_from_slice(&[0; 4]);
+    exif.extend_from_slice(&thumbnail);
+
+    let data = jpeg_with_segments(&[(0xe1, &exif), (0xc0, FRAME_HEADER)]);
+    assert_eq!(roles_and_sizes(&data), vec![
+        (ImRole::Primary,   32, 16),
+        (ImRole::Thumbnail, 16,  8),
+    ]);
+}
+
+#[test]
+fn jpeg_jfif_thumbnails() {
+    // JFIF with a 4 x 2 RGB thumbnail
+    let mut jfif = b"JFIF\0\x01\x02\x00\x00\x01\x00\x01\x04\x02".to_vec();
+    jfif.resize(jfif.len() + 4 * 2 * 3, 0);
+    // JFXX with a JPEG thumbnail and one with a palette thumbnail
+    let mut jfxx_jpeg = b"JFXX\0\x10".to_vec();
+    jfxx_jpeg.extend_from_slice(&small_jpeg(8, 4));
+    let mut jfxx_palette = b"JFXX\0\x11\x06\x03".to_vec();
+    jfxx_palette.resize(jfxx_palette.len() + 768 + 6 * 3, 0);
+
+    let data = jpeg_with_segments(&[(0xe0, &jfif), (0xe0, &jfxx_jpeg), (0xe0, &jfxx_palette), (0xc0, FRAME_HEADER)]);
+    assert_eq!(roles_and_sizes(&data), vec![
+        (ImRole::Primary,   32, 16),
+        (ImRole::Thumbnail,  4,  2),
+        (ImRole::Thumbnail,  8,  4),
+        (ImRole::Thumbnail,  6,  3),
+    ]);
+}
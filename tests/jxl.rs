@@ -0,0 +1,107 @@
+use imsz::{imsz, imsz_with_options, ImError, ImFormat, ImOptions, ImOrientation};
+
+// writes bits least significant bit first
+struct BitWriter {
+    data: Vec<u8>,
+    bit_offset: usize,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        BitWriter { data: b"\xff\x0a".to_vec(), bit_offset: 0 }
+    }
+
+    fn bits(mut self, count: usize, value: u64) -> Self {
+        for index in 0..count {
+            if self.bit_offset % 8 == 0 {
+                self.data.push(0);
+            }
+            let bit = ((value >> index) & 1) as u8;
+            *self.data.last_mut().unwrap() |= bit << (self.bit_offset % 8);
+            self.bit_offset += 1;
+        }
+        self
+    }
+}
+
+fn iso_box(name: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&(payload.len() as u32 + 8).to_be_bytes());
+    data.extend_from_slice(name);
+    data.extend_from_slice(payload);
+    data
+}
+
+fn container(boxes: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = b"\x00\x00\x00\x0cJXL \r\n\x87\n".to_vec();
+    data.extend_from_slice(&iso_box(b"ftyp", b"jxl \x00\x00\x00\x00jxl "));
+    for item in boxes {
+        data.extend_from_slice(item);
+    }
+    data
+}
+
+fn size(data: &[u8]) -> (u64, u64) {
+    let info = imsz(data).unwrap();
+    assert_eq!(info.format, ImFormat::JXL);
+    (info.width, info.height)
+}
+
+#[test]
+fn jxl_small_size() {
+    // small, height / 8 - 1, ratio 2:1
+    let data = BitWriter::new().bits(1, 1).bits(5, 1).bits(3, 7).data;
+    assert_eq!(size(&data), (32, 16));
+
+    // small, height / 8 - 1, no ratio, width / 8 - 1
+    let data = BitWriter::new().bits(1, 1).bits(5, 1).bits(3, 0).bits(5, 3).data;
+    assert_eq!(size(&data), (32, 16));
+}
+
+#[test]
+fn jxl_large_size() {
+    // 9 bit height, no ratio, 30 bit width
+    let data = BitWriter::new().bits(1, 0).bits(2, 0).bits(9, 499).bits(3, 0).bits(2, 3).bits(30, 99_999).data;
+    assert_eq!(size(&data), (100_000, 500));
+
+    // 13 bit height, ratio 12:10
+    let data = BitWriter::new().bits(1, 0).bits(2, 1).bits(13, 999).bits(3, 2).data;
+    assert_eq!(size(&data), (1200, 1000));
+
+    // 18 bit height, ratio 16:9
+    let data = BitWriter::new().bits(1, 0).bits(2, 2).bits(18, 1079).bits(3, 5).data;
+    assert_eq!(size(&data), (1920, 1080));
+}
+
+#[test]
+fn jxl_orientation() {
+    // not all default, extra fields, orientation - 1
+    let data = BitWriter::new().bits(1, 1).bits(5, 1).bits(3, 7).bits(1, 0).bits(1, 1).bits(3, 5).data;
+    let options = ImOptions::new().orientation(true);
+    let info = imsz_with_options(&data[..], &options).unwrap();
+    assert_eq!(info.orientation, Some(ImOrientation::RightTop));
+    assert_eq!(info.display_size(), (16, 32));
+}
+
+#[test]
+fn jxl_container() {
+    let codestream = BitWriter::new().bits(1, 1).bits(5, 1).bits(3, 7).data;
+    let data = container(&[iso_box(b"jxll", b"\x05"), iso_box(b"jxlc", &codestream)]);
+    assert_eq!(size(&data), (32, 16));
+
+    let mut partial = 0u32.to_be_bytes().to_vec();
+    partial.extend_from_slice(&codestream);
+    let data = container(&[iso_box(b"Exif", b"\x00\x00\x00\x00"), iso_box(b"jxlp", &partial)]);
+    assert_eq!(size(&data), (32, 16));
+}
+
+#[test]
+fn jxl_broken() {
+    // truncated SizeHeader
+    let data = BitWriter::new().bits(1, 0).bits(2, 3).bits(10, 0).data;
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::JXL))));
+
+    // container without codestream
+    let data = container(&[iso_box(b"jxll", b"\x05")]);
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::JXL))));
+}
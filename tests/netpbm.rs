@@ -0,0 +1,67 @@
+use imsz::{imsz, ImColorModel, ImError, ImFormat, ImPixelFormat};
+
+fn assert_header(data: &[u8], format: ImFormat, pixel_format: ImPixelFormat) {
+    let info = imsz(data).unwrap();
+    assert_eq!(info.format, format);
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(info.pixel_format, Some(pixel_format));
+}
+
+#[test]
+fn netpbm_comments() {
+    assert_header(b"P2#comment\n32#comment\r16 # comment\n#\n65535\n", ImFormat::PGM,
+        ImPixelFormat::new(ImColorModel::Gray, 1, 16, false));
+    assert_header(b"P4\n32\t16\n", ImFormat::PBM,
+        ImPixelFormat::new(ImColorModel::Gray, 1, 1, false));
+    assert_header(b"P3 32 16 15 ", ImFormat::PPM,
+        ImPixelFormat::new(ImColorModel::RGB, 3, 4, false));
+    assert_header(b"Pf\n32 16\n-1.0\n", ImFormat::PFM,
+        ImPixelFormat::new(ImColorModel::Gray, 1, 32, false));
+    // the header may end at the end of the file
+    assert_header(b"P1\n32 16", ImFormat::PBM,
+        ImPixelFormat::new(ImColorModel::Gray, 1, 1, false));
+}
+
+#[test]
+fn netpbm_pam() {
+    assert_header(b"P7\n# comment\nHEIGHT 16\nWIDTH 32\nDEPTH 2\nMAXVAL 65535\nTUPLTYPE GRAYSCALE_ALPHA\nENDHDR\n", ImFormat::PAM,
+        ImPixelFormat::new(ImColorModel::Gray, 2, 16, true));
+    assert_header(b"P7\nWIDTH 32\nHEIGHT 16\nDEPTH 5\nMAXVAL 255\nTUPLTYPE SPECTRAL\nENDHDR\n", ImFormat::PAM,
+        ImPixelFormat::new(ImColorModel::Other, 5, 8, false));
+
+    // missing MAXVAL
+    let data = b"P7\nWIDTH 32\nHEIGHT 16\nDEPTH 3\nENDHDR\n";
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::PAM))));
+
+    // XV thumbnails are not PAM files
+    assert!(matches!(imsz(&b"P7 332\n#XVVERSION:Version 2.28\n"[..]), Err(ImError::UnknownFormat)));
+}
+
+#[test]
+fn netpbm_broken() {
+    // invalid maximum values
+    assert!(matches!(imsz(&b"P5 32 16 0\n"[..]), Err(ImError::ParserError(ImFormat::PGM))));
+    assert!(matches!(imsz(&b"P6 32 16 65536\n"[..]), Err(ImError::ParserError(ImFormat::PPM))));
+
+    // not a number
+    assert!(matches!(imsz(&b"P6 32 x16 255\n"[..]), Err(ImError::ParserError(ImFormat::PPM))));
+
+    // overly long token
+    let mut data = b"P5 ".to_vec();
+    data.resize(1000, b'1');
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::PGM))));
+
+    // endless whitespace and comments
+    let mut data = b"P6\n".to_vec();
+    while data.len() < 1024 * 1024 {
+        data.extend_from_slice(b"  # comment\n");
+    }
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::PPM))));
+
+    // endless PAM header
+    let mut data = b"P7\n".to_vec();
+    while data.len() < 1024 * 1024 {
+        data.extend_from_slice(b"FOO 1\n");
+    }
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::PAM))));
+}
@@ -1,3 +1,4 @@
+#[allow(clippy::needless_return)]
 fn get_testdata(fname: &str) -> std::path::PathBuf {
     let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     path.push("testdata");
@@ -7,6 +8,43 @@ fn get_testdata(fname: &str) -> std::path::PathBuf {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
+fn ani() {
+    let info = imsz::imsz_from_path(get_testdata("image.ani"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::ANI);
+            assert_eq!(info.format.name(), "ANI");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
+fn astc() {
+    let info = imsz::imsz_from_path(get_testdata("image.astc"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::ASTC);
+            assert_eq!(info.format.name(), "ASTC");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
 fn avif() {
     let info = imsz::imsz_from_path(get_testdata("image.avif"));
     match info {
@@ -24,6 +62,7 @@ fn avif() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn bmp() {
     let info = imsz::imsz_from_path(get_testdata("image.bmp"));
     match info {
@@ -41,6 +80,25 @@ fn bmp() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
+fn cur() {
+    let info = imsz::imsz_from_path(get_testdata("image.cur"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::CUR);
+            assert_eq!(info.format.name(), "CUR");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
 fn dds() {
     let info = imsz::imsz_from_path(get_testdata("image.dds"));
     match info {
@@ -58,6 +116,7 @@ fn dds() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn dib() {
     let info = imsz::imsz_from_path(get_testdata("image.dib"));
     match info {
@@ -75,6 +134,7 @@ fn dib() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn exr() {
     let info = imsz::imsz_from_path(get_testdata("image.exr"));
     match info {
@@ -92,6 +152,7 @@ fn exr() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn gif() {
     let info = imsz::imsz_from_path(get_testdata("image.gif"));
     match info {
@@ -109,6 +170,25 @@ fn gif() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
+fn hdr() {
+    let info = imsz::imsz_from_path(get_testdata("image.hdr"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::HDR);
+            assert_eq!(info.format.name(), "HDR");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
 fn heif() {
     let info = imsz::imsz_from_path(get_testdata("image.heif"));
     match info {
@@ -126,6 +206,7 @@ fn heif() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn ico() {
     let info = imsz::imsz_from_path(get_testdata("image.ico"));
     match info {
@@ -143,6 +224,7 @@ fn ico() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn ilbm() {
     let info = imsz::imsz_from_path(get_testdata("image.ilbm"));
     match info {
@@ -160,6 +242,25 @@ fn ilbm() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
+fn j2k() {
+    let info = imsz::imsz_from_path(get_testdata("image.j2k"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::JP2K);
+            assert_eq!(info.format.name(), "JPEG 2000");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
 fn jp2() {
     let info = imsz::imsz_from_path(get_testdata("image.jp2"));
     match info {
@@ -177,6 +278,7 @@ fn jp2() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn jpeg() {
     let info = imsz::imsz_from_path(get_testdata("image.jpeg"));
     match info {
@@ -194,6 +296,115 @@ fn jpeg() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
+fn jpx() {
+    let info = imsz::imsz_from_path(get_testdata("image.jpx"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::JP2K);
+            assert_eq!(info.format.name(), "JPEG 2000");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
+fn ktx() {
+    let info = imsz::imsz_from_path(get_testdata("image.ktx"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::KTX);
+            assert_eq!(info.format.name(), "KTX");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
+fn ktx2() {
+    let info = imsz::imsz_from_path(get_testdata("image.ktx2"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::KTX2);
+            assert_eq!(info.format.name(), "KTX2");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
+fn pam() {
+    let info = imsz::imsz_from_path(get_testdata("image.pam"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::PAM);
+            assert_eq!(info.format.name(), "PAM");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
+fn pbm() {
+    let info = imsz::imsz_from_path(get_testdata("image.pbm"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::PBM);
+            assert_eq!(info.format.name(), "PBM");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
+fn pbm_ascii() {
+    let info = imsz::imsz_from_path(get_testdata("image_ascii.pbm"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::PBM);
+            assert_eq!(info.format.name(), "PBM");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
 fn pcx() {
     let info = imsz::imsz_from_path(get_testdata("image.pcx"));
     match info {
@@ -211,6 +422,61 @@ fn pcx() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
+fn pfm() {
+    let info = imsz::imsz_from_path(get_testdata("image.pfm"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::PFM);
+            assert_eq!(info.format.name(), "PFM");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
+fn pgm() {
+    let info = imsz::imsz_from_path(get_testdata("image.pgm"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::PGM);
+            assert_eq!(info.format.name(), "PGM");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
+fn pgm_ascii() {
+    let info = imsz::imsz_from_path(get_testdata("image_ascii.pgm"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::PGM);
+            assert_eq!(info.format.name(), "PGM");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
 fn png() {
     let info = imsz::imsz_from_path(get_testdata("image.png"));
     match info {
@@ -228,6 +494,61 @@ fn png() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
+fn png_cgbi() {
+    let info = imsz::imsz_from_path(get_testdata("image_cgbi.png"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::PNG);
+            assert_eq!(info.format.name(), "PNG");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
+fn ppm() {
+    let info = imsz::imsz_from_path(get_testdata("image.ppm"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::PPM);
+            assert_eq!(info.format.name(), "PPM");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
+fn ppm_ascii() {
+    let info = imsz::imsz_from_path(get_testdata("image_ascii.ppm"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::PPM);
+            assert_eq!(info.format.name(), "PPM");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
 fn psd() {
     let info = imsz::imsz_from_path(get_testdata("image.psd"));
     match info {
@@ -245,6 +566,25 @@ fn psd() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
+fn pvr() {
+    let info = imsz::imsz_from_path(get_testdata("image.pvr"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::PVR);
+            assert_eq!(info.format.name(), "PVR");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
 fn qoi() {
     let info = imsz::imsz_from_path(get_testdata("image.qoi"));
     match info {
@@ -262,6 +602,25 @@ fn qoi() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
+fn svg() {
+    let info = imsz::imsz_from_path(get_testdata("image.svg"));
+    match info {
+        Ok(info) => {
+            assert_eq!(info.format, imsz::ImFormat::SVG);
+            assert_eq!(info.format.name(), "SVG");
+            assert_eq!(info.width,  32);
+            assert_eq!(info.height, 16);
+        }
+        Err(error) => {
+            assert!(false, "{}", error);
+        }
+    }
+}
+
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
 fn tga() {
     let info = imsz::imsz_from_path(get_testdata("image.tga"));
     match info {
@@ -279,6 +638,7 @@ fn tga() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn tiff_be() {
     let info = imsz::imsz_from_path(get_testdata("image_be.tiff"));
     match info {
@@ -296,6 +656,7 @@ fn tiff_be() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn tiff_le() {
     let info = imsz::imsz_from_path(get_testdata("image_le.tiff"));
     match info {
@@ -313,6 +674,7 @@ fn tiff_le() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn webp_lossless() {
     let info = imsz::imsz_from_path(get_testdata("image_lossless.webp"));
     match info {
@@ -330,6 +692,7 @@ fn webp_lossless() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn webp_lossless_vp8x() {
     let info = imsz::imsz_from_path(get_testdata("image_lossless_vp8x.webp"));
     match info {
@@ -347,6 +710,7 @@ fn webp_lossless_vp8x() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn webp_lossy() {
     let info = imsz::imsz_from_path(get_testdata("image_lossy.webp"));
     match info {
@@ -364,6 +728,7 @@ fn webp_lossy() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn webp_lossy_vp8x() {
     let info = imsz::imsz_from_path(get_testdata("image_lossy_vp8x.webp"));
     match info {
@@ -381,6 +746,7 @@ fn webp_lossy_vp8x() {
 
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn xcf() {
     let info = imsz::imsz_from_path(get_testdata("image.xcf"));
     match info {
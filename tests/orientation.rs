@@ -0,0 +1,154 @@
+use imsz::{imsz, imsz_with_options, ImOptions, ImOrientation};
+
+fn options() -> ImOptions {
+    ImOptions::new().orientation(true)
+}
+
+fn iso_box(name: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&(payload.len() as u32 + 8).to_be_bytes());
+    data.extend_from_slice(name);
+    data.extend_from_slice(payload);
+    data
+}
+
+// big endian EXIF data with a single IFD0 entry: Orientation
+fn exif(orientation: u16) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(b"MM\x00*\x00\x00\x00\x08\x00\x01");
+    data.extend_from_slice(b"\x01\x12\x00\x03\x00\x00\x00\x01");
+    data.extend_from_slice(&orientation.to_be_bytes());
+    data.extend_from_slice(b"\x00\x00\x00\x00\x00\x00");
+    data
+}
+
+fn jpeg(orientation: u16) -> Vec<u8> {
+    let mut app1 = b"Exif\x00\x00".to_vec();
+    app1.extend_from_slice(&exif(orientation));
+
+    let mut data = b"\xff\xd8\xff\xe1".to_vec();
+    data.extend_from_slice(&(app1.len() as u16 + 2).to_be_bytes());
+    data.extend_from_slice(&app1);
+    // precision 8, height 16, width 32, 1 component
+    data.extend_from_slice(b"\xff\xc0\x00\x0b\x08\x00\x10\x00\x20\x01\x01\x11\x00\xff\xda");
+    data
+}
+
+#[test]
+fn orientation_opt_in() {
+    let info = imsz(&jpeg(6)[..]).unwrap();
+    assert_eq!(info.orientation, None);
+    assert_eq!(info.display_size(), (32, 16));
+}
+
+#[test]
+fn orientation_jpeg() {
+    for value in 1..=8 {
+        let info = imsz_with_options(&jpeg(value)[..], &options()).unwrap();
+        let orientation = ImOrientation::from_exif(value).unwrap();
+        assert_eq!(info.orientation, Some(orientation));
+        assert_eq!(info.orientation.unwrap() as u16, value);
+        assert_eq!((info.width, info.height), (32, 16));
+        if value >= 5 {
+            assert_eq!(info.display_size(), (16, 32), "orientation: {value}");
+        } else {
+            assert_eq!(info.display_size(), (32, 16), "orientation: {value}");
+        }
+    }
+}
+
+#[test]
+fn orientation_jpeg_broken_exif() {
+    let mut data = jpeg(6);
+    // IFD0 offset points past the end of the EXIF data
+    data[17] = 0xff;
+    let info = imsz_with_options(&data[..], &options()).unwrap();
+    assert_eq!(info.orientation, None);
+    assert_eq!(info.width, 32);
+}
+
+#[test]
+fn orientation_tiff() {
+    let mut data = Vec::new();
+    data.extend_from_slice(b"II*\x00\x08\x00\x00\x00\x03\x00");
+    data.extend_from_slice(b"\x00\x01\x03\x00\x01\x00\x00\x00\x20\x00\x00\x00");
+    data.extend_from_slice(b"\x01\x01\x03\x00\x01\x00\x00\x00\x10\x00\x00\x00");
+    data.extend_from_slice(b"\x12\x01\x03\x00\x01\x00\x00\x00\x08\x00\x00\x00");
+    data.extend_from_slice(b"\x00\x00\x00\x00");
+
+    let info = imsz_with_options(&data[..], &options()).unwrap();
+    assert_eq!(info.orientation, Some(ImOrientation::LeftBottom));
+    assert_eq!(info.display_size(), (16, 32));
+}
+
+#[test]
+fn orientation_webp() {
+    let mut chunks = Vec::new();
+    // VP8X with EXIF flag, 32 x 16
+    chunks.extend_from_slice(b"VP8X\x0a\x00\x00\x00\x08\x00\x00\x00\x1f\x00\x00\x0f\x00\x00");
+    chunks.extend_from_slice(b"VP8L\x00\x00\x00\x00");
+    let exif = exif(3);
+    chunks.extend_from_slice(b"EXIF");
+    chunks.extend_from_slice(&(exif.len() as u32).to_le_bytes());
+    chunks.extend_from_slice(&exif);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(b"RIFF");
+    data.extend_from_slice(&(chunks.len() as u32 + 4).to_le_bytes());
+    data.extend_from_slice(b"WEBP");
+    data.extend_from_slice(&chunks);
+
+    let info = imsz_with_options(&data[..], &options()).unwrap();
+    assert_eq!(info.orientation, Some(ImOrientation::BottomRight));
+    assert_eq!(info.display_size(), (32, 16));
+}
+
+fn heif(properties: &[&[u8]], associations: &[u8]) -> Vec<u8> {
+    let mut ipco = Vec::new();
+    // ispe: 32 x 16
+    ipco.extend_from_slice(&iso_box(b"ispe", b"\x00\x00\x00\x00\x00\x00\x00\x20\x00\x00\x00\x10"));
+    for property in properties {
+        ipco.extend_from_slice(property);
+    }
+    let mut ipma = b"\x00\x00\x00\x00\x00\x00\x00\x01\x00\x01".to_vec();
+    ipma.push(associations.len() as u8);
+    ipma.extend_from_slice(associations);
+
+    let mut iprp = iso_box(b"ipco", &ipco);
+    iprp.extend_from_slice(&iso_box(b"ipma", &ipma));
+
+    let mut meta = b"\x00\x00\x00\x00".to_vec();
+    meta.extend_from_slice(&iso_box(b"pitm", b"\x00\x00\x00\x00\x00\x01"));
+    meta.extend_from_slice(&iso_box(b"iprp", &iprp));
+
+    let mut data = iso_box(b"ftyp", b"heic\x00\x00\x00\x00mif1heic");
+    data.extend_from_slice(&iso_box(b"meta", &meta));
+    data
+}
+
+#[test]
+fn orientation_heif() {
+    let irot = iso_box(b"irot", b"\x01");
+    let imir = iso_box(b"imir", b"\x00");
+
+    let info = imsz_with_options(&heif(&[], b"\x01")[..], &options()).unwrap();
+    assert_eq!(info.orientation, None);
+
+    // 90 degrees anti-clockwise
+    let info = imsz_with_options(&heif(&[&irot], b"\x01\x82")[..], &options()).unwrap();
+    assert_eq!(info.orientation, Some(ImOrientation::LeftBottom));
+    assert_eq!(info.display_size(), (16, 32));
+
+    // rotated first, then mirrored
+    let info = imsz_with_options(&heif(&[&irot, &imir], b"\x01\x82\x83")[..], &options()).unwrap();
+    assert_eq!(info.orientation, Some(ImOrientation::RightBottom));
+
+    // mirrored first, then rotated
+    let info = imsz_with_options(&heif(&[&irot, &imir], b"\x01\x83\x82")[..], &options()).unwrap();
+    assert_eq!(info.orientation, Some(ImOrientation::LeftTop));
+
+    // mirrored about the horizontal axis
+    let imir = iso_box(b"imir", b"\x01");
+    let info = imsz_with_options(&heif(&[&imir], b"\x01\x82")[..], &options()).unwrap();
+    assert_eq!(info.orientation, Some(ImOrientation::BottomLeft));
+}
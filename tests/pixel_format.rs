@@ -0,0 +1,79 @@
+use imsz::{imsz, imsz_from_path, imsz_from_path_with_options, ImColorModel, ImOptions, ImPixelFormat};
+
+fn get_testdata(fname: &str) -> std::path::PathBuf {
+    let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("testdata");
+    path.push(fname);
+    path
+}
+
+#[test]
+fn pixel_format_testdata() {
+    let rgb8 = Some(ImPixelFormat::new(ImColorModel::RGB, 3, 8, false));
+    let data = [
+        ("image.dds",           rgb8),
+        ("image.dib",           rgb8),
+        ("image.exr",           Some(ImPixelFormat::new(ImColorModel::RGB, 3, 32, false))),
+        ("image.gif",           Some(ImPixelFormat::new(ImColorModel::Indexed, 1, 4, false))),
+        ("image.ico",           Some(ImPixelFormat::new(ImColorModel::Indexed, 1, 4, false))),
+        ("image.ilbm",          Some(ImPixelFormat::new(ImColorModel::Indexed, 1, 4, false))),
+        ("image.jp2",           rgb8),
+        ("image.jpeg",          Some(ImPixelFormat::new(ImColorModel::YCbCr, 3, 8, false))),
+        ("image.png",           rgb8),
+        ("image.psd",           rgb8),
+        ("image.qoi",           rgb8),
+        ("image.tga",           rgb8),
+        ("image.xcf",           rgb8),
+        ("image_be.tiff",       rgb8),
+        ("image_le.tiff",       rgb8),
+        ("image_lossless.webp", rgb8),
+        ("image_lossy.webp",    Some(ImPixelFormat::new(ImColorModel::YCbCr, 3, 8, false))),
+    ];
+    for (fname, expected) in data {
+        let info = imsz_from_path(get_testdata(fname)).unwrap();
+        assert_eq!(info.pixel_format, expected, "{fname}");
+    }
+}
+
+#[test]
+fn pixel_format_option() {
+    let rgb8 = Some(ImPixelFormat::new(ImColorModel::RGB, 3, 8, false));
+    let options = ImOptions::new().pixel_format(true);
+    for fname in ["image.bmp", "image.pcx"] {
+        let info = imsz_from_path(get_testdata(fname)).unwrap();
+        assert_eq!(info.pixel_format, None, "{fname}");
+
+        let info = imsz_from_path_with_options(get_testdata(fname), &options).unwrap();
+        assert_eq!(info.pixel_format, rgb8, "{fname}");
+    }
+}
+
+#[test]
+fn pixel_format_png() {
+    let info = imsz(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x00\x20\x00\x00\x00\x10\x10\x06\x00\x00\x00").unwrap();
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::RGB, 4, 16, true)));
+
+    let info = imsz(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x00\x20\x00\x00\x00\x10\x01\x00\x00\x00\x00").unwrap();
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::Gray, 1, 1, false)));
+
+    // unknown color type
+    let info = imsz(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x00\x20\x00\x00\x00\x10\x08\x05\x00\x00\x00").unwrap();
+    assert_eq!((info.width, info.height, info.pixel_format), (32, 16, None));
+}
+
+#[test]
+fn pixel_format_psd() {
+    // 5 channels, 16 bit, CMYK
+    let info = imsz(b"8BPS\x00\x01\x00\x00\x00\x00\x00\x00\x00\x05\x00\x00\x00\x10\x00\x00\x00\x20\x00\x10\x00\x04").unwrap();
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::CMYK, 5, 16, true)));
+}
+
+#[test]
+fn pixel_format_jpeg_adobe() {
+    let data = b"\xff\xd8\
+        \xff\xee\x00\x0eAdobe\x00\x64\x00\x00\x00\x00\x02\
+        \xff\xc0\x00\x14\x08\x00\x10\x00\x20\x04\x01\x11\x00\x02\x11\x00\x03\x11\x00\x04\x11\x00\
+        \xff\xda";
+    let info = imsz(data).unwrap();
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::YCCK, 4, 8, false)));
+}
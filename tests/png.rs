@@ -0,0 +1,116 @@
+use imsz::{imsz, imsz_with_options, ImAnimation, ImDetails, ImError, ImFormat, ImInfo, ImOptions, ImPngChunk, ImPngInfo};
+
+fn get_testdata(fname: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join(fname)
+}
+
+fn png_info(info: &ImInfo) -> ImPngInfo {
+    match &info.details {
+        ImDetails::PNG(png_info) => png_info.clone(),
+        _ => panic!("no PNG details: {:?}", info.details),
+    }
+}
+
+fn strict() -> ImOptions {
+    ImOptions::new().strict(true)
+}
+
+fn chunks() -> ImOptions {
+    ImOptions::new().chunks(true)
+}
+
+// 32 x 16 RGBA, interlaced, with a valid CRC
+const IHDR: &[u8] = b"\x00\x00\x00\x0dIHDR\x00\x00\x00\x20\x00\x00\x00\x10\x08\x06\x00\x00\x01\x00\x07\x4d\xcf";
+
+fn png(chunks: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+    let mut data = b"\x89PNG\r\n\x1a\n".to_vec();
+    data.extend_from_slice(IHDR);
+    for (chunk_type, payload) in chunks {
+        data.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        data.extend_from_slice(*chunk_type);
+        data.extend_from_slice(payload);
+        // the CRC isn't checked
+        data.extend_from_slice(&[0; 4]);
+    }
+    data
+}
+
+#[test]
+fn png_strict_crc() {
+    let data = png(&[(b"IEND", b"")]);
+    let info = imsz_with_options(&data[..], &strict()).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+    assert!(png_info(&info).interlaced);
+
+    // corrupted width, only detected in strict mode
+    let mut data = data;
+    data[18] = 1;
+    assert_eq!(imsz(&data[..]).unwrap().width, 288);
+    assert!(matches!(imsz_with_options(&data[..], &strict()), Err(ImError::ParserError(ImFormat::PNG))));
+
+    // truncated CRC
+    let data = &png(&[])[..30];
+    assert_eq!(imsz(data).unwrap().width, 32);
+    assert!(matches!(imsz_with_options(data, &strict()), Err(ImError::ParserError(ImFormat::PNG))));
+
+    let info = imsz_with_options(get_testdata("image.png"), &strict()).unwrap();
+    assert!(!png_info(&info).interlaced);
+}
+
+#[test]
+fn png_cgbi() {
+    let options = ImOptions::new().strict(true).chunks(true);
+    let info = imsz_with_options(get_testdata("image_cgbi.png"), &options).unwrap();
+    assert_eq!(info.format, ImFormat::PNG);
+    assert_eq!((info.width, info.height), (32, 16));
+    let details = png_info(&info);
+    assert!(details.cgbi);
+    // CgBI is a critical chunk
+    assert_eq!(details.chunks.len(), 4);
+
+    let info = imsz(get_testdata("image_cgbi.png")).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+    assert!(png_info(&info).cgbi);
+}
+
+#[test]
+fn png_chunk_list() {
+    let info = imsz_with_options(get_testdata("image.png"), &chunks()).unwrap();
+    let types: Vec<_> = png_info(&info).chunks.iter().map(|chunk| chunk.chunk_type).collect();
+    assert_eq!(types, vec![*b"iCCP", *b"pHYs", *b"tIME", *b"tEXt"]);
+
+    // chunks after IDAT are listed too, and the list isn't requested by default
+    let data = png(&[
+        (b"acTL", b"\x00\x00\x00\x02\x00\x00\x00\x00"),
+        (b"sRGB", b"\x00"),
+        (b"IDAT", b"\x00\x00"),
+        (b"eXIf", b"MM\x00\x2a\x00\x00\x00\x08\x00\x00"),
+        (b"IEND", b""),
+        (b"tEXt", b"after IEND"),
+    ]);
+    assert_eq!(png_info(&imsz(&data[..]).unwrap()).chunks, vec![]);
+
+    let options = ImOptions::new().animation(true).chunks(true);
+    let info = imsz_with_options(&data[..], &options).unwrap();
+    assert_eq!(info.animation, Some(ImAnimation { frames: 2, loop_count: Some(0) }));
+    assert_eq!(png_info(&info).chunks, vec![
+        ImPngChunk { chunk_type: *b"acTL", size: 8 },
+        ImPngChunk { chunk_type: *b"sRGB", size: 1 },
+        ImPngChunk { chunk_type: *b"eXIf", size: 10 },
+    ]);
+}
+
+#[test]
+fn png_broken() {
+    // invalid interlace method, only an error in strict mode
+    let mut data = png(&[]);
+    data[28] = 2;
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+    assert!(!png_info(&info).interlaced);
+    assert!(matches!(imsz_with_options(&data[..], &strict()), Err(ImError::ParserError(ImFormat::PNG))));
+
+    // CgBI without IHDR
+    let data = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x04CgBI\x50\x00\x20\x06\x00\x00\x00\x00\x00\x00\x00\x00IEND\x00\x00\x00\x00";
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::PNG))));
+}
@@ -0,0 +1,50 @@
+use imsz::{imsz, ImColorModel, ImDetails, ImFormat, ImPixelFormat, ImPsdColorMode, ImPsdInfo};
+
+fn psd_header(version: u16, channels: u16, width: u32, height: u32, depth: u16, mode: u16) -> Vec<u8> {
+    let mut data = b"8BPS".to_vec();
+    data.extend_from_slice(&version.to_be_bytes());
+    data.extend_from_slice(&[0; 6]);
+    data.extend_from_slice(&channels.to_be_bytes());
+    data.extend_from_slice(&height.to_be_bytes());
+    data.extend_from_slice(&width.to_be_bytes());
+    data.extend_from_slice(&depth.to_be_bytes());
+    data.extend_from_slice(&mode.to_be_bytes());
+    data
+}
+
+#[test]
+fn psd_header_fields() {
+    let info = imsz(&psd_header(1, 2, 32, 16, 8, 8)[..]).unwrap();
+    assert_eq!(info.format, ImFormat::PSD);
+    assert_eq!(info.details, ImDetails::PSD(ImPsdInfo {
+        version: 1,
+        channels: 2,
+        depth: 8,
+        color_mode: Some(ImPsdColorMode::Duotone),
+    }));
+
+    // unknown color mode
+    let info = imsz(&psd_header(1, 3, 32, 16, 8, 5)[..]).unwrap();
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::Other, 3, 8, false)));
+    match info.details {
+        ImDetails::PSD(psd_info) => assert_eq!(psd_info.color_mode, None),
+        _ => panic!("no PSD details: {:?}", info.details),
+    }
+}
+
+#[test]
+fn psd_large_document() {
+    let info = imsz(&psd_header(2, 4, 300_000, 40_000, 16, 3)[..]).unwrap();
+    assert_eq!(info.format, ImFormat::PSD);
+    assert_eq!((info.width, info.height), (300_000, 40_000));
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::RGB, 4, 16, true)));
+    assert_eq!(info.details, ImDetails::PSD(ImPsdInfo {
+        version: 2,
+        channels: 4,
+        depth: 16,
+        color_mode: Some(ImPsdColorMode::RGB),
+    }));
+
+    // unknown versions
+    assert!(imsz(&psd_header(3, 4, 32, 16, 8, 3)[..]).is_err());
+}
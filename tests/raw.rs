@@ -0,0 +1,313 @@
+use imsz::{imsz, imsz_with_options, ImDetails, ImError, ImFormat, ImImage, ImOptions, ImOrientation, ImRawInfo, ImRole};
+
+// (tag, type, count, value)
+type Entry = (u16, u16, u32, u32);
+
+// Little endian TIFF based file with the given magic. `header` follows the IFD0 offset, the IFDs follow the
+// header one after another and are chained in order. `extra` is appended after the IFDs.
+fn tiff(magic: &[u8; 4], header: &[u8], ifds: &[&[Entry]], extra: &[u8]) -> Vec<u8> {
+    let mut data = magic.to_vec();
+    let mut offset = 8 + header.len() as u32;
+    data.extend_from_slice(&offset.to_le_bytes());
+    data.extend_from_slice(header);
+    for (index, entries) in ifds.iter().enumerate() {
+        offset += 2 + 12 * entries.len() as u32 + 4;
+        data.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for &(tag, ftype, count, value) in entries.iter() {
+            data.extend_from_slice(&tag.to_le_bytes());
+            data.extend_from_slice(&ftype.to_le_bytes());
+            data.extend_from_slice(&count.to_le_bytes());
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        let next = if index + 1 < ifds.len() { offset } else { 0 };
+        data.extend_from_slice(&next.to_le_bytes());
+    }
+    data.extend_from_slice(extra);
+    data
+}
+
+// offset of the data after the IFDs of a file created by `tiff()`
+fn extra_offset(header_size: u32, entry_counts: &[u32]) -> u32 {
+    8 + header_size + entry_counts.iter().map(|count| 2 + 12 * count + 4).sum::<u32>()
+}
+
+// JPEG with the given start-of-frame marker, the frame header and nothing else
+fn jpeg(sof: u8, width: u16, height: u16, components: u8) -> Vec<u8> {
+    let mut data = vec![0xff, 0xd8, 0xff, sof, 0x00, 8 + 3 * components, 8];
+    data.extend_from_slice(&height.to_be_bytes());
+    data.extend_from_slice(&width.to_be_bytes());
+    data.push(components);
+    for index in 0..components {
+        data.extend_from_slice(&[index + 1, 0x11, 0]);
+    }
+    data.extend_from_slice(b"\xff\xda");
+    data
+}
+
+fn iso_box(name: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&(payload.len() as u32 + 8).to_be_bytes());
+    data.extend_from_slice(name);
+    data.extend_from_slice(payload);
+    data
+}
+
+fn all_images() -> ImOptions {
+    ImOptions::new().images(true)
+}
+
+fn preview_size(details: &ImDetails) -> Option<(u64, u64)> {
+    match details {
+        ImDetails::RAW(ImRawInfo { preview_size }) => *preview_size,
+        _ => panic!("not a RAW file: {details:?}"),
+    }
+}
+
+#[test]
+fn raw_dng() {
+    const COUNTS: [u32; 2] = [5, 4];
+    let raw_offset = extra_offset(0, &COUNTS[..1]);
+    let data = tiff(b"II*\x00", b"", &[
+        // reduced resolution preview with the DNG version and the raw image in a SubIFD
+        &[(254, 4, 1, 1), (256, 3, 1, 8), (257, 3, 1, 4), (330, 4, 1, raw_offset), (50706, 1, 4, 0x0104)],
+        &[(254, 4, 1, 0), (256, 3, 1, 32), (257, 3, 1, 16), (262, 3, 1, 32803)],
+    ], b"");
+
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::DNG);
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(preview_size(&info.details), Some((8, 4)));
+
+    let info = imsz_with_options(&data[..], &all_images()).unwrap();
+    assert_eq!(info.images, vec![
+        ImImage { index: 0, width: 32, height: 16, format: ImFormat::DNG, role: ImRole::Primary },
+        ImImage { index: 1, width:  8, height:  4, format: ImFormat::DNG, role: ImRole::Thumbnail },
+    ]);
+}
+
+fn nef(make: &[u8], photometric: u32) -> Vec<u8> {
+    const COUNTS: [u32; 3] = [5, 2, 4];
+    let preview_offset = extra_offset(0, &COUNTS[..1]);
+    let raw_offset     = extra_offset(0, &COUNTS[..2]);
+    let extra = extra_offset(0, &COUNTS[..3]);
+
+    // SubIFD offsets, make, JPEG preview
+    let mut data = Vec::new();
+    data.extend_from_slice(&preview_offset.to_le_bytes());
+    data.extend_from_slice(&raw_offset.to_le_bytes());
+    data.extend_from_slice(make);
+    data.extend_from_slice(&jpeg(0xc0, 24, 12, 3));
+
+    let mut file = tiff(b"II*\x00", b"", &[
+        &[(254, 4, 1, 1), (256, 3, 1, 8), (257, 3, 1, 4), (271, 2, make.len() as u32, extra + 8), (330, 4, 2, extra)],
+        &[(254, 4, 1, 1), (513, 4, 1, extra + 8 + make.len() as u32)],
+        &[(254, 4, 1, 0), (256, 3, 1, 32), (257, 3, 1, 16), (262, 3, 1, photometric)],
+    ], &data);
+    // the SubIFDs must not be chained to IFD0
+    let next_offset = (8 + 2 + 12 * COUNTS[0]) as usize;
+    file[next_offset..next_offset + 4].copy_from_slice(&[0; 4]);
+    let next_offset = (preview_offset + 2 + 12 * COUNTS[1]) as usize;
+    file[next_offset..next_offset + 4].copy_from_slice(&[0; 4]);
+    file
+}
+
+#[test]
+fn raw_nef() {
+    let data = nef(b"NIKON CORPORATION\0", 32803);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::NEF);
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(preview_size(&info.details), Some((24, 12)));
+
+    let info = imsz_with_options(&data[..], &all_images()).unwrap();
+    assert_eq!(info.images, vec![
+        ImImage { index: 0, width: 32, height: 16, format: ImFormat::NEF,  role: ImRole::Primary },
+        ImImage { index: 1, width:  8, height:  4, format: ImFormat::NEF,  role: ImRole::Thumbnail },
+        ImImage { index: 2, width: 24, height: 12, format: ImFormat::JPEG, role: ImRole::Thumbnail },
+    ]);
+}
+
+#[test]
+fn raw_make_without_cfa() {
+    // e.g. a scanned image, not a RAW file
+    let info = imsz(&nef(b"Nikon\0", 2)[..]).unwrap();
+    assert_eq!(info.format, ImFormat::TIFF);
+    assert_eq!(info.details, ImDetails::None);
+
+    let info = imsz(&nef(b"SONY\0", 32803)[..]).unwrap();
+    assert_eq!(info.format, ImFormat::ARW);
+
+    let info = imsz(&nef(b"PENTAX Corporation\0", 32803)[..]).unwrap();
+    assert_eq!(info.format, ImFormat::PEF);
+}
+
+#[test]
+fn raw_pef() {
+    // the raw data is IFD0 itself
+    let make = b"PENTAX Corporation\0";
+    let extra = extra_offset(0, &[4]);
+    let entries = [(256, 3, 1, 32), (257, 3, 1, 16), (262, 3, 1, 32803), (271, 2, make.len() as u32, extra)];
+    let info = imsz(&tiff(b"II*\x00", b"", &[&entries], make)[..]).unwrap();
+    assert_eq!(info.format, ImFormat::PEF);
+    assert_eq!((info.width, info.height), (32, 16));
+
+    // RGB data, the make is not even read
+    let entries = [(256, 3, 1, 32), (257, 3, 1, 16), (262, 3, 1, 2), (271, 2, make.len() as u32, extra)];
+    let info = imsz(&tiff(b"II*\x00", b"", &[&entries], make)[..]).unwrap();
+    assert_eq!(info.format, ImFormat::TIFF);
+}
+
+#[test]
+fn raw_orf() {
+    let data = tiff(b"IIRO", b"", &[
+        &[(256, 3, 1, 32), (257, 3, 1, 16), (274, 3, 1, 6)],
+    ], b"");
+
+    let options = ImOptions::new().orientation(true);
+    let info = imsz_with_options(&data[..], &options).unwrap();
+    assert_eq!(info.format, ImFormat::ORF);
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(info.orientation, Some(ImOrientation::RightTop));
+    assert_eq!(preview_size(&info.details), None);
+}
+
+#[test]
+fn raw_rw2() {
+    let preview = jpeg(0xc0, 24, 12, 3);
+    let data = tiff(b"IIU\x00", b"", &[
+        &[(2, 3, 1, 32), (3, 3, 1, 16), (46, 7, preview.len() as u32, extra_offset(0, &[3]))],
+    ], &preview);
+
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::RW2);
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(preview_size(&info.details), Some((24, 12)));
+
+    // no sensor size
+    let data = tiff(b"IIU\x00", b"", &[&[(256, 3, 1, 32)]], b"");
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::RW2))));
+}
+
+#[test]
+fn raw_cr2() {
+    const COUNTS: [u32; 4] = [3, 1, 2, 2];
+    let extra = extra_offset(8, &COUNTS);
+    let thumbnail = jpeg(0xc0, 16, 8, 3);
+    let mut data = thumbnail.clone();
+    // lossless JPEG with two components per sample
+    data.extend_from_slice(&jpeg(0xc3, 16, 16, 2));
+
+    let mut header = b"CR\x02\x00".to_vec();
+    header.extend_from_slice(&extra_offset(8, &COUNTS[..3]).to_le_bytes());
+    let data = tiff(b"II*\x00", &header, &[
+        // full size JPEG preview
+        &[(256, 3, 1, 64), (257, 3, 1, 32), (259, 3, 1, 6)],
+        // thumbnail
+        &[(513, 4, 1, extra)],
+        // small RGB image
+        &[(256, 3, 1, 8), (257, 3, 1, 4)],
+        // raw data
+        &[(273, 4, 1, extra + thumbnail.len() as u32), (50752, 3, 3, 0)],
+    ], &data);
+
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::CR2);
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(preview_size(&info.details), Some((64, 32)));
+
+    let info = imsz_with_options(&data[..], &all_images()).unwrap();
+    assert_eq!(info.images, vec![
+        ImImage { index: 0, width: 32, height: 16, format: ImFormat::CR2,  role: ImRole::Primary },
+        ImImage { index: 1, width: 64, height: 32, format: ImFormat::JPEG, role: ImRole::Thumbnail },
+        ImImage { index: 2, width: 16, height:  8, format: ImFormat::JPEG, role: ImRole::Thumbnail },
+        ImImage { index: 3, width:  8, height:  4, format: ImFormat::CR2,  role: ImRole::Thumbnail },
+    ]);
+}
+
+fn raf(records: &[(u16, &[u8])]) -> Vec<u8> {
+    let preview = jpeg(0xc0, 24, 12, 3);
+    let mut data = b"FUJIFILMCCD-RAW 0201FF383501".to_vec();
+    data.resize(84, 0);
+    let jpeg_offset = 108u32;
+    let cfa_header_offset = jpeg_offset + preview.len() as u32;
+    data.extend_from_slice(&jpeg_offset.to_be_bytes());
+    data.extend_from_slice(&(preview.len() as u32).to_be_bytes());
+    data.extend_from_slice(&cfa_header_offset.to_be_bytes());
+    data.resize(108, 0);
+    data.extend_from_slice(&preview);
+
+    data.extend_from_slice(&(records.len() as u32).to_be_bytes());
+    for (tag, record) in records {
+        data.extend_from_slice(&tag.to_be_bytes());
+        data.extend_from_slice(&(record.len() as u16).to_be_bytes());
+        data.extend_from_slice(record);
+    }
+    data
+}
+
+#[test]
+fn raw_raf() {
+    // RawImageCroppedSize, then RawImageFullSize, both height first
+    let data = raf(&[(0x130, b"\x00\x00\x00\x00"), (0x111, b"\x00\x0e\x00\x1e"), (0x100, b"\x00\x10\x00\x20")]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::RAF);
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(preview_size(&info.details), Some((24, 12)));
+
+    let data = raf(&[(0x111, b"\x00\x0e\x00\x1e")]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (30, 14));
+
+    let data = raf(&[(0x130, b"\x00\x00")]);
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::RAF))));
+}
+
+fn cr3_track(width: u16, height: u16, child: &[u8; 4]) -> Vec<u8> {
+    // visual sample entry, extended by an image type
+    let mut craw = vec![0u8; 24];
+    craw.extend_from_slice(&width.to_be_bytes());
+    craw.extend_from_slice(&height.to_be_bytes());
+    craw.resize(80, 0);
+    craw.extend_from_slice(&iso_box(child, b""));
+
+    let mut stsd = b"\x00\x00\x00\x00\x00\x00\x00\x01".to_vec();
+    stsd.extend_from_slice(&iso_box(b"CRAW", &craw));
+    let stbl = iso_box(b"stsd", &stsd);
+    let minf = iso_box(b"stbl", &stbl);
+    let mdia = iso_box(b"minf", &minf);
+    let trak = iso_box(b"mdia", &mdia);
+    iso_box(b"trak", &trak)
+}
+
+#[test]
+fn raw_cr3() {
+    // IFD0 with the orientation
+    let cmt1 = b"II*\x00\x08\x00\x00\x00\x01\x00\x12\x01\x03\x00\x01\x00\x00\x00\x08\x00\x00\x00\x00\x00\x00\x00";
+    let mut canon = vec![
+        0x85, 0xc0, 0xb6, 0x87, 0x82, 0x0f, 0x11, 0xe0,
+        0x81, 0x11, 0xf4, 0xce, 0x46, 0x2b, 0x6a, 0x48,
+    ];
+    canon.extend_from_slice(&iso_box(b"CMT1", cmt1));
+    canon.extend_from_slice(&iso_box(b"THMB", b"\x00\x00\x00\x00\x00\x04\x00\x02"));
+
+    let mut moov = iso_box(b"uuid", &canon);
+    moov.extend_from_slice(&cr3_track(64, 32, b"JPEG"));
+    moov.extend_from_slice(&cr3_track(16,  8, b"CMP1"));
+    moov.extend_from_slice(&cr3_track(32, 16, b"CMP1"));
+
+    let mut data = iso_box(b"ftyp", b"crx \x00\x00\x00\x01crx isom");
+    data.extend_from_slice(&iso_box(b"moov", &moov));
+
+    let options = ImOptions::new().orientation(true).images(true);
+    let info = imsz_with_options(&data[..], &options).unwrap();
+    assert_eq!(info.format, ImFormat::CR3);
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(info.orientation, Some(ImOrientation::LeftBottom));
+    assert_eq!(preview_size(&info.details), Some((64, 32)));
+    assert_eq!(info.images, vec![
+        ImImage { index: 0, width: 32, height: 16, format: ImFormat::CR3,  role: ImRole::Primary },
+        ImImage { index: 1, width:  4, height:  2, format: ImFormat::JPEG, role: ImRole::Thumbnail },
+        ImImage { index: 2, width: 64, height: 32, format: ImFormat::JPEG, role: ImRole::Thumbnail },
+        ImImage { index: 3, width: 16, height:  8, format: ImFormat::CR3,  role: ImRole::Thumbnail },
+    ]);
+}
@@ -0,0 +1,112 @@
+use imsz::{imsz, imsz_from_path_with_options, imsz_with_options, ImOptions, ImResolution, ImResolutionUnit};
+
+fn get_testdata(fname: &str) -> std::path::PathBuf {
+    let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("testdata");
+    path.push(fname);
+    path
+}
+
+fn options() -> ImOptions {
+    ImOptions::new().resolution(true)
+}
+
+fn assert_dpi(resolution: Option<ImResolution>, expected: f64, fname: &str) {
+    let (x, y) = resolution.and_then(|resolution| resolution.dpi()).unwrap_or_else(|| panic!("{fname}: no resolution"));
+    assert!((x - expected).abs() < 0.01, "{fname}: {x}");
+    assert!((y - expected).abs() < 0.01, "{fname}: {y}");
+}
+
+fn iso_box(name: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&(payload.len() as u32 + 8).to_be_bytes());
+    data.extend_from_slice(name);
+    data.extend_from_slice(payload);
+    data
+}
+
+#[test]
+fn resolution_opt_in() {
+    let info = imsz(get_testdata("image.png")).unwrap();
+    assert_eq!(info.resolution, None);
+    assert_eq!(info.physical_size(ImResolutionUnit::Inch), None);
+}
+
+#[test]
+fn resolution_testdata() {
+    for fname in ["image.bmp", "image.dib", "image.jpeg", "image.pcx", "image.png", "image.psd", "image_be.tiff", "image_le.tiff"] {
+        let info = imsz_from_path_with_options(get_testdata(fname), &options()).unwrap();
+        assert_dpi(info.resolution, 300.0, fname);
+    }
+
+    let info = imsz_from_path_with_options(get_testdata("image.gif"), &options()).unwrap();
+    assert_eq!(info.resolution, None);
+}
+
+#[test]
+fn resolution_physical_size() {
+    let info = imsz_from_path_with_options(get_testdata("image_le.tiff"), &options()).unwrap();
+    let (w, h) = info.physical_size(ImResolutionUnit::Inch).unwrap();
+    assert!((w - 32.0 / 300.0).abs() < 1e-9);
+    assert!((h - 16.0 / 300.0).abs() < 1e-9);
+
+    let (w, h) = info.physical_size(ImResolutionUnit::Centimeter).unwrap();
+    assert!((w - 32.0 / 300.0 * 2.54).abs() < 1e-9);
+    assert!((h - 16.0 / 300.0 * 2.54).abs() < 1e-9);
+
+    let resolution = ImResolution::new(72.0, 72.0, ImResolutionUnit::Unknown);
+    assert_eq!(resolution.dpi(), None);
+}
+
+#[test]
+fn resolution_png_aspect_ratio() {
+    let mut data = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x00\x20\x00\x00\x00\x10\x08\x02\x00\x00\x00\x00\x00\x00\x00".to_vec();
+    data.extend_from_slice(b"\x00\x00\x00\x09pHYs\x00\x00\x00\x02\x00\x00\x00\x01\x00\x00\x00\x00\x00");
+    data.extend_from_slice(b"\x00\x00\x00\x00IDAT\x00\x00\x00\x00");
+
+    let info = imsz_with_options(&data[..], &options()).unwrap();
+    assert_eq!(info.resolution, Some(ImResolution::new(2.0, 1.0, ImResolutionUnit::Unknown)));
+    assert_eq!(info.physical_size(ImResolutionUnit::Meter), None);
+}
+
+#[test]
+fn resolution_jpeg_exif_fallback() {
+    // EXIF: XResolution = YResolution = 254/1, ResolutionUnit = centimeter
+    let mut exif = b"Exif\x00\x00II*\x00\x08\x00\x00\x00\x03\x00".to_vec();
+    exif.extend_from_slice(b"\x1a\x01\x05\x00\x01\x00\x00\x00\x32\x00\x00\x00");
+    exif.extend_from_slice(b"\x1b\x01\x05\x00\x01\x00\x00\x00\x32\x00\x00\x00");
+    exif.extend_from_slice(b"\x28\x01\x03\x00\x01\x00\x00\x00\x03\x00\x00\x00");
+    exif.extend_from_slice(b"\x00\x00\x00\x00");
+    exif.extend_from_slice(b"\xfe\x00\x00\x00\x01\x00\x00\x00");
+
+    let mut data = b"\xff\xd8".to_vec();
+    // JFIF with aspect ratio only
+    data.extend_from_slice(b"\xff\xe0\x00\x10JFIF\x00\x01\x01\x00\x00\x01\x00\x01\x00\x00");
+    data.extend_from_slice(b"\xff\xe1");
+    data.extend_from_slice(&(exif.len() as u16 + 2).to_be_bytes());
+    data.extend_from_slice(&exif);
+    data.extend_from_slice(b"\xff\xc0\x00\x0b\x08\x00\x10\x00\x20\x01\x01\x11\x00\xff\xda");
+
+    let info = imsz_with_options(&data[..], &options()).unwrap();
+    assert_eq!(info.resolution, Some(ImResolution::new(254.0, 254.0, ImResolutionUnit::Centimeter)));
+    assert_dpi(info.resolution, 645.16, "JPEG");
+}
+
+#[test]
+fn resolution_jp2() {
+    let ihdr = iso_box(b"ihdr", b"\x00\x00\x00\x10\x00\x00\x00\x20\x00\x03\x07\x07\x00\x00");
+    // capture: 11811 x 11811 per meter, display: 10 x 5 * 10^3 per meter
+    let mut res = iso_box(b"resc", b"\x2e\x23\x00\x01\x2e\x23\x00\x01\x00\x00");
+    res.extend_from_slice(&iso_box(b"resd", b"\x00\x05\x00\x01\x00\x0a\x00\x01\x03\x03"));
+    let mut jp2h = ihdr;
+    jp2h.extend_from_slice(&iso_box(b"res ", &res));
+
+    let mut data = b"\x00\x00\x00\x0cjP  \r\n\x87\n".to_vec();
+    data.extend_from_slice(&iso_box(b"ftyp", b"jp2 \x00\x00\x00\x00jp2 "));
+    data.extend_from_slice(&iso_box(b"jp2h", &jp2h));
+
+    let info = imsz_with_options(&data[..], &options()).unwrap();
+    assert_eq!(info.width,  32);
+    assert_eq!(info.height, 16);
+    assert_eq!(info.resolution, Some(ImResolution::new(10000.0, 5000.0, ImResolutionUnit::Meter)));
+}
@@ -0,0 +1,116 @@
+use imsz::{imsz, ImDetails, ImError, ImFormat, ImInfo, ImSvgInfo, ImSvgLength, ImSvgUnit, ImSvgViewBox};
+
+fn svg(data: &str) -> ImInfo {
+    let info = imsz(data.as_bytes()).unwrap();
+    assert_eq!(info.format, ImFormat::SVG);
+    info
+}
+
+fn svg_info(info: &ImInfo) -> ImSvgInfo {
+    match info.details {
+        ImDetails::SVG(svg_info) => svg_info,
+        _ => panic!("no SVG details: {:?}", info.details),
+    }
+}
+
+#[test]
+fn svg_prolog() {
+    let info = svg(concat!(
+        "\u{feff}<?xml version=\"1.0\"?>\n",
+        "<!-- <svg width=\"1\" height=\"1\"> -->\n",
+        "<?xml-stylesheet href=\"style.css\"?>\n",
+        "<!DOCTYPE svg PUBLIC \"-//W3C//DTD SVG 1.1//EN\" \"http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd\" [\n",
+        "  <!ENTITY width \"100\">\n",
+        "]>\n",
+        "<svg:svg\n  xmlns:svg=\"http://www.w3.org/2000/svg\"\n  width = '32px'\n  height=\"16\">",
+    ));
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(svg_info(&info), ImSvgInfo {
+        width:  Some(ImSvgLength::new(32.0, ImSvgUnit::Px)),
+        height: Some(ImSvgLength::new(16.0, ImSvgUnit::None)),
+        view_box: None,
+    });
+}
+
+#[test]
+fn svg_units() {
+    let info = svg("<svg width='1in' height='72pt'/>");
+    assert_eq!((info.width, info.height), (96, 96));
+
+    let info = svg("<svg width='2.54cm' height='6pc'/>");
+    assert_eq!((info.width, info.height), (96, 96));
+
+    let info = svg("<svg width='25.4mm' height='1e2'/>");
+    assert_eq!((info.width, info.height), (96, 100));
+    assert_eq!(svg_info(&info).height, Some(ImSvgLength::new(100.0, ImSvgUnit::None)));
+
+    // unknown units are ignored
+    let info = svg("<svg width='10furlong' height='20'/>");
+    assert_eq!(svg_info(&info).width, None);
+}
+
+#[test]
+fn svg_view_box() {
+    // relative sizes can't be resolved, the viewBox is used instead
+    let info = svg("<svg width=\"100%\" height=\"2em\" viewBox=\"-10 -5,320 160\">");
+    assert_eq!((info.width, info.height), (320, 160));
+    let details = svg_info(&info);
+    assert_eq!(details.width,  Some(ImSvgLength::new(100.0, ImSvgUnit::Percent)));
+    assert_eq!(details.height, Some(ImSvgLength::new(2.0, ImSvgUnit::Em)));
+    assert_eq!(details.view_box, Some(ImSvgViewBox { x: -10.0, y: -5.0, width: 320.0, height: 160.0 }));
+
+    // the aspect ratio of the viewBox
+    let info = svg("<svg width=\"64\" viewBox=\"0 0 32 16\">");
+    assert_eq!((info.width, info.height), (64, 32));
+    let info = svg("<svg height=\"8\" viewBox=\"0 0 32 16\">");
+    assert_eq!((info.width, info.height), (16, 8));
+
+    // no size at all
+    let info = svg("<svg xmlns=\"http://www.w3.org/2000/svg\">");
+    assert_eq!((info.width, info.height), (0, 0));
+    assert_eq!(svg_info(&info), ImSvgInfo { width: None, height: None, view_box: None });
+}
+
+#[test]
+fn svg_not_svg() {
+    assert!(matches!(imsz(&b"<?xml version=\"1.0\"?><html></html>"[..]), Err(ImError::UnknownFormat)));
+    assert!(matches!(imsz(&b"<!-- just a comment -->"[..]), Err(ImError::UnknownFormat)));
+}
+
+#[test]
+fn svg_broken() {
+    assert!(matches!(imsz(&b"<svg width=32 height=16>"[..]), Err(ImError::ParserError(ImFormat::SVG))));
+    assert!(matches!(imsz(&b"<svg width=\"32\" height=\"16"[..]), Err(ImError::ParserError(ImFormat::SVG))));
+
+    // the scan is bounded, without a root element it might be any XML file
+    let mut data = b"<?xml version=\"1.0\"?><!--".to_vec();
+    data.resize(1024 * 1024, b'-');
+    assert!(matches!(imsz(&data[..]), Err(ImError::UnknownFormat)));
+
+    let mut data = b"<?xml version=\"1.0\"?><svg width=\"32\" height=\"16\" data-x=\"".to_vec();
+    data.resize(1024 * 1024, b'x');
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::SVG))));
+}
+
+#[test]
+fn svg_long_prolog() {
+    // the markup ends at different positions relative to the read chunks
+    for pad in 1000..1040 {
+        let data = format!(
+            "<?xml version=\"1.0\"?>\n<!--{}-->\n<?pi {}?>\n<!DOCTYPE svg [ <!ENTITY a \"{}>\"> ]>\n<svg data-x=\"{}>\" width=\"32\" height=\"16\">",
+            "x".repeat(pad), "x".repeat(pad), "x".repeat(pad), "x".repeat(pad),
+        );
+        let info = svg(&data);
+        assert_eq!((info.width, info.height), (32, 16), "{pad}");
+    }
+}
+
+#[test]
+#[cfg(feature = "svgz")]
+fn svg_svgz() {
+    // <svg xmlns="http://www.w3.org/2000/svg" width="2in" height="1in"/>
+    let data = b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x02\x03\xb3\x29\x2e\x4b\x57\xa8\xc8\xcd\xc9\x2b\xb6\x55\xca\x28\x29\x29\xb0\xd2\xd7\x2f\x2f\x2f\xd7\x2b\x37\xd6\xcb\x2f\x4a\xd7\x37\x32\x30\x30\xd0\x07\xaa\x50\x52\x28\xcf\x4c\x29\xc9\xb0\x55\x32\xca\xcc\x53\x52\xc8\x48\xcd\x4c\xcf\x28\xb1\x55\x32\x04\x72\xf4\xed\xb8\x00\x96\xf3\x7b\xa3\x43\x00\x00\x00";
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::SVG);
+    assert_eq!((info.width, info.height), (192, 96));
+}
@@ -0,0 +1,176 @@
+use imsz::{imsz, imsz_all, ImColorModel, ImDetails, ImError, ImFormat, ImInfo, ImPixelFormat, ImRole, ImTextureInfo};
+
+fn texture(info: &ImInfo) -> ImTextureInfo {
+    match info.details {
+        ImDetails::Texture(texture) => texture,
+        _ => panic!("no texture details: {:?}", info.details),
+    }
+}
+
+fn ktx_header(big_endian: bool, fields: [u32; 12]) -> Vec<u8> {
+    let mut data = b"\xabKTX 11\xbb\r\n\x1a\n".to_vec();
+    for value in [0x04030201].iter().chain(fields.iter()) {
+        if big_endian {
+            data.extend_from_slice(&value.to_be_bytes());
+        } else {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    data
+}
+
+fn le_u32s(data: &mut Vec<u8>, values: &[u32]) {
+    for value in values {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+#[test]
+fn texture_testdata() {
+    let rgba8 = ImPixelFormat::new(ImColorModel::RGB, 4, 8, true);
+    for (fname, format) in [
+        ("image.ktx",  ImFormat::KTX),
+        ("image.ktx2", ImFormat::KTX2),
+        ("image.pvr",  ImFormat::PVR),
+    ] {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join(fname);
+        let info = imsz(path).unwrap();
+        assert_eq!(info.format, format);
+        assert_eq!(info.pixel_format, Some(rgba8));
+        assert_eq!(texture(&info), ImTextureInfo::new(1, 1, 1, 1));
+    }
+}
+
+#[test]
+fn texture_ktx() {
+    // big endian RGB cube map array with 2 layers and 0 mipmap levels (to be generated)
+    let data = ktx_header(true, [0x1401, 1, 0x1907, 0x8051, 0x1907, 32, 16, 0, 2, 6, 0, 0]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::KTX);
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::RGB, 3, 8, false)));
+    assert_eq!(texture(&info), ImTextureInfo::new(1, 2, 6, 1));
+
+    // compressed 1D texture
+    let data = ktx_header(false, [0, 1, 0, 0x83F0, 0x1907, 32, 0, 0, 0, 1, 6, 0]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 1));
+    assert_eq!(info.pixel_format, None);
+    assert_eq!(texture(&info), ImTextureInfo::new(1, 1, 1, 6));
+
+    // invalid endianness
+    let mut data = ktx_header(false, [0; 12]);
+    data[12] = 0;
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::KTX))));
+}
+
+#[test]
+fn texture_mip_levels() {
+    // 2 layers with 3 mipmap levels each
+    let data = ktx_header(false, [0x1406, 4, 0x1908, 0x8814, 0x1908, 32, 16, 0, 2, 1, 3, 0]);
+    let images = imsz_all(&data[..]).unwrap();
+    let actual: Vec<_> = images.iter().map(|image| (image.role, image.width, image.height)).collect();
+    assert_eq!(actual, vec![
+        (ImRole::MipLevel { layer: 0, level: 0 }, 32, 16),
+        (ImRole::MipLevel { layer: 0, level: 1 }, 16,  8),
+        (ImRole::MipLevel { layer: 0, level: 2 },  8,  4),
+        (ImRole::MipLevel { layer: 1, level: 0 }, 32, 16),
+        (ImRole::MipLevel { layer: 1, level: 1 }, 16,  8),
+        (ImRole::MipLevel { layer: 1, level: 2 },  8,  4),
+    ]);
+
+    // the listing is bounded
+    let data = ktx_header(false, [0x1401, 1, 0x1908, 0x8058, 0x1908, 1 << 20, 1, 0, 1 << 20, 6, 64, 0]);
+    assert_eq!(imsz_all(&data[..]).unwrap().len(), 4096);
+}
+
+#[test]
+fn texture_ktx2() {
+    // RGBA16F 3D texture with 5 mipmap levels
+    let mut data = b"\xabKTX 20\xbb\r\n\x1a\n".to_vec();
+    le_u32s(&mut data, &[97, 2, 32, 16, 8, 0, 1, 5, 0]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::KTX2);
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::RGB, 4, 16, true)));
+    assert_eq!(texture(&info), ImTextureInfo::new(8, 1, 1, 5));
+
+    // Basis Universal supercompressed cube map
+    let mut data = b"\xabKTX 20\xbb\r\n\x1a\n".to_vec();
+    le_u32s(&mut data, &[0, 1, 32, 32, 0, 0, 6, 0, 1]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.pixel_format, None);
+    assert_eq!(texture(&info), ImTextureInfo::new(1, 1, 6, 1));
+}
+
+#[test]
+fn texture_pvr() {
+    // PVRTC 4bpp RGBA, 3 surfaces, 4 mipmap levels
+    let mut data = b"PVR\x03".to_vec();
+    le_u32s(&mut data, &[0, 3, 0, 0, 0, 16, 32, 1, 3, 1, 4, 0]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::PVR);
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(info.pixel_format, None);
+    assert_eq!(texture(&info), ImTextureInfo::new(1, 3, 1, 4));
+
+    // 16 bit luminance alpha
+    let mut data = b"PVR\x03\x00\x00\x00\x00la\x00\x00\x10\x10\x00\x00".to_vec();
+    le_u32s(&mut data, &[0, 0, 16, 32, 1, 1, 1, 1, 0]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::Gray, 2, 16, true)));
+}
+
+#[test]
+fn texture_astc() {
+    let data = b"\x13\xab\xa1\x5c\x06\x06\x06\x20\x00\x00\x10\x00\x00\x08\x00\x00";
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::ASTC);
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(texture(&info), ImTextureInfo::new(8, 1, 1, 1));
+}
+
+fn basis(texture_type: u8, total_images: u32, slices: &[(u32, u8, u16, u16)]) -> Vec<u8> {
+    let mut data = vec![0u8; 77];
+    data[0..2].copy_from_slice(b"sB");
+    data[4..6].copy_from_slice(&77u16.to_le_bytes());
+    data[14..17].copy_from_slice(&(slices.len() as u32).to_le_bytes()[..3]);
+    data[17..20].copy_from_slice(&total_images.to_le_bytes()[..3]);
+    data[23] = texture_type;
+    data[65..69].copy_from_slice(&77u32.to_le_bytes());
+    for &(image_index, level_index, width, height) in slices {
+        let mut slice = vec![0u8; 23];
+        slice[0..3].copy_from_slice(&image_index.to_le_bytes()[..3]);
+        slice[3] = level_index;
+        slice[5..7].copy_from_slice(&width.to_le_bytes());
+        slice[7..9].copy_from_slice(&height.to_le_bytes());
+        data.extend_from_slice(&slice);
+    }
+    data
+}
+
+#[test]
+fn texture_basis() {
+    // 2D array with 2 images and 3 mipmap levels each
+    let data = basis(1, 2, &[
+        (0, 0, 32, 16), (0, 1, 16, 8), (0, 2, 8, 4),
+        (1, 0, 32, 16), (1, 1, 16, 8), (1, 2, 8, 4),
+    ]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::BASIS);
+    assert_eq!(info.format.name(), "Basis");
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(texture(&info), ImTextureInfo::new(1, 2, 1, 3));
+
+    // cube map array
+    let data = basis(2, 12, &[(0, 0, 32, 32)]);
+    assert_eq!(texture(&imsz(&data[..]).unwrap()), ImTextureInfo::new(1, 2, 6, 1));
+
+    // volume
+    let data = basis(4, 8, &[(0, 0, 32, 16)]);
+    assert_eq!(texture(&imsz(&data[..]).unwrap()), ImTextureInfo::new(8, 1, 1, 1));
+
+    // no slices
+    let data = basis(0, 1, &[]);
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::BASIS))));
+}
@@ -0,0 +1,225 @@
+use imsz::{imsz, imsz_with_options, ImColorModel, ImFormat, ImImage, ImOptions, ImPixelFormat, ImResolution, ImResolutionUnit, ImRole};
+
+struct Writer {
+    big_endian: bool,
+    data: Vec<u8>,
+}
+
+impl Writer {
+    fn u16(&mut self, value: u16) {
+        let bytes = if self.big_endian { value.to_be_bytes() } else { value.to_le_bytes() };
+        self.data.extend_from_slice(&bytes);
+    }
+
+    fn u32(&mut self, value: u32) {
+        let bytes = if self.big_endian { value.to_be_bytes() } else { value.to_le_bytes() };
+        self.data.extend_from_slice(&bytes);
+    }
+
+    fn u64(&mut self, value: u64) {
+        let bytes = if self.big_endian { value.to_be_bytes() } else { value.to_le_bytes() };
+        self.data.extend_from_slice(&bytes);
+    }
+}
+
+fn bigtiff(big_endian: bool) -> Vec<u8> {
+    let mut writer = Writer { big_endian, data: Vec::new() };
+    writer.data.extend_from_slice(if big_endian { b"MM" } else { b"II" });
+    writer.u16(43);
+    writer.u16(8);
+    writer.u16(0);
+    writer.u64(16);
+
+    // IFD at 16
+    writer.u64(6);
+
+    // ImageWidth, LONG8
+    writer.u16(256); writer.u16(16); writer.u64(1); writer.u64(32);
+
+    // ImageLength, SHORT
+    writer.u16(257); writer.u16(3); writer.u64(1);
+    writer.u16(16); writer.u16(0); writer.u32(0);
+
+    // BitsPerSample, 3 SHORTs fit into the value field
+    writer.u16(258); writer.u16(3); writer.u64(3);
+    writer.u16(8); writer.u16(8); writer.u16(8); writer.u16(0);
+
+    // PhotometricInterpretation, RGB
+    writer.u16(262); writer.u16(3); writer.u64(1);
+    writer.u16(2); writer.u16(0); writer.u32(0);
+
+    // SamplesPerPixel
+    writer.u16(277); writer.u16(3); writer.u64(1);
+    writer.u16(3); writer.u16(0); writer.u32(0);
+
+    // XResolution, a RATIONAL fits into the value field
+    writer.u16(282); writer.u16(5); writer.u64(1);
+    writer.u32(300); writer.u32(1);
+
+    // next IFD
+    writer.u64(0);
+
+    writer.data
+}
+
+#[test]
+fn bigtiff_byte_orders() {
+    let options = ImOptions::new().resolution(true);
+    for big_endian in [false, true] {
+        let info = imsz_with_options(&bigtiff(big_endian)[..], &options).unwrap();
+        assert_eq!(info.format, ImFormat::TIFF);
+        assert_eq!(info.width,  32);
+        assert_eq!(info.height, 16);
+        assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::RGB, 3, 8, false)));
+        assert_eq!(info.resolution, Some(ImResolution::new(300.0, 300.0, ImResolutionUnit::Inch)));
+    }
+}
+
+#[test]
+fn bigtiff_broken() {
+    // bad offset byte size
+    let mut data = bigtiff(false);
+    data[4] = 4;
+    assert!(imsz(&data[..]).is_err());
+
+    // truncated IFD
+    let data = bigtiff(true);
+    assert!(imsz(&data[..40]).is_err());
+}
+
+// (tag, type, count, value)
+type Entry = (u16, u16, u32, u32);
+
+// Little endian TIFF with the given IFDs. The IFDs are stored one after another, `next` gives the index of
+// the next IFD of each IFD. `extra` is appended after the IFDs.
+fn tiff(ifds: &[(&[Entry], Option<usize>)], extra: &[u8]) -> Vec<u8> {
+    let mut offsets = Vec::new();
+    let mut offset = 8;
+    for (entries, _) in ifds {
+        offsets.push(offset);
+        offset += 2 + 12 * entries.len() as u32 + 4;
+    }
+
+    let mut writer = Writer { big_endian: false, data: b"II*\x00".to_vec() };
+    writer.u32(8);
+    for (entries, next) in ifds {
+        writer.u16(entries.len() as u16);
+        for &(tag, ftype, count, value) in entries.iter() {
+            writer.u16(tag);
+            writer.u16(ftype);
+            writer.u32(count);
+            writer.u32(value);
+        }
+        writer.u32(next.map(|next| offsets[next]).unwrap_or(0));
+    }
+    writer.data.extend_from_slice(extra);
+    writer.data
+}
+
+const fn ifd_offset(entry_counts: &[u32], index: usize) -> u32 {
+    let mut offset = 8;
+    let mut i = 0;
+    while i < index {
+        offset += 2 + 12 * entry_counts[i] + 4;
+        i += 1;
+    }
+    offset
+}
+
+fn all_images() -> ImOptions {
+    ImOptions::new().images(true)
+}
+
+#[test]
+fn tiff_subfile_types() {
+    let data = tiff(&[
+        // reduced resolution
+        (&[(254, 4, 1, 1), (256, 3, 1,  8), (257, 3, 1,  4)], Some(1)),
+        // full resolution
+        (&[                (256, 3, 1, 32), (257, 3, 1, 16)], Some(2)),
+        // page
+        (&[(254, 4, 1, 2), (256, 3, 1, 32), (257, 3, 1, 16)], Some(3)),
+        // transparency mask
+        (&[(254, 4, 1, 4), (256, 3, 1, 32), (257, 3, 1, 16)], None),
+    ], b"");
+
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+    assert!(info.images.is_empty());
+
+    let info = imsz_with_options(&data[..], &all_images()).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(info.images, vec![
+        ImImage { index: 0, width:  8, height:  4, format: ImFormat::TIFF, role: ImRole::Thumbnail },
+        ImImage { index: 1, width: 32, height: 16, format: ImFormat::TIFF, role: ImRole::Primary },
+        ImImage { index: 2, width: 32, height: 16, format: ImFormat::TIFF, role: ImRole::Page },
+        ImImage { index: 3, width: 32, height: 16, format: ImFormat::TIFF, role: ImRole::Auxiliary },
+    ]);
+}
+
+#[test]
+fn tiff_old_subfile_type() {
+    let data = tiff(&[
+        (&[(255, 3, 1, 2), (256, 3, 1,  8), (257, 3, 1,  4)], Some(1)),
+        (&[(255, 3, 1, 1), (256, 3, 1, 32), (257, 3, 1, 16)], None),
+    ], b"");
+
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+}
+
+#[test]
+fn tiff_sub_ifds() {
+    // like DNG: IFD0 is a preview, the main image is in a SubIFD
+    const COUNTS: [u32; 4] = [4, 2, 3, 2];
+    let extra_offset = ifd_offset(&COUNTS, 4);
+    let mut extra = Vec::new();
+    extra.extend_from_slice(&ifd_offset(&COUNTS, 1).to_le_bytes());
+    extra.extend_from_slice(&ifd_offset(&COUNTS, 2).to_le_bytes());
+
+    let data = tiff(&[
+        (&[(254, 4, 1, 1), (256, 3, 1,  8), (257, 3, 1,  4), (330, 4, 2, extra_offset)], Some(3)),
+        (&[                (256, 3, 1, 32), (257, 3, 1, 16)], None),
+        (&[(254, 4, 1, 1), (256, 3, 1, 16), (257, 3, 1,  8)], None),
+        (&[                (256, 3, 1, 24), (257, 3, 1, 12)], None),
+    ], &extra);
+
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+
+    let info = imsz_with_options(&data[..], &all_images()).unwrap();
+    assert_eq!(info.images, vec![
+        ImImage { index: 0, width:  8, height:  4, format: ImFormat::TIFF, role: ImRole::Thumbnail },
+        ImImage { index: 1, width: 32, height: 16, format: ImFormat::TIFF, role: ImRole::Primary },
+        ImImage { index: 2, width: 16, height:  8, format: ImFormat::TIFF, role: ImRole::Thumbnail },
+        ImImage { index: 3, width: 24, height: 12, format: ImFormat::TIFF, role: ImRole::Page },
+    ]);
+}
+
+#[test]
+fn tiff_ifd_loops() {
+    // the SubIFD points back to IFD0 and the next IFD points to itself
+    let data = tiff(&[
+        (&[(256, 3, 1, 32), (257, 3, 1, 16), (330, 4, 1, 8)], Some(1)),
+        (&[(256, 3, 1, 16), (257, 3, 1,  8)], Some(1)),
+    ], b"");
+
+    let info = imsz_with_options(&data[..], &all_images()).unwrap();
+    assert_eq!(info.images, vec![
+        ImImage { index: 0, width: 32, height: 16, format: ImFormat::TIFF, role: ImRole::Primary },
+        ImImage { index: 1, width: 16, height:  8, format: ImFormat::TIFF, role: ImRole::Page },
+    ]);
+}
+
+#[test]
+fn tiff_broken_next_ifd() {
+    let mut data = tiff(&[
+        (&[(256, 3, 1, 32), (257, 3, 1, 16)], None),
+    ], b"");
+    // next IFD offset points past the end of the file
+    let len = data.len();
+    data[len - 4..].copy_from_slice(&0xFFFFu32.to_le_bytes());
+
+    let info = imsz_with_options(&data[..], &all_images()).unwrap();
+    assert_eq!(info.images.len(), 1);
+}
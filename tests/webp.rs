@@ -0,0 +1,131 @@
+use imsz::{imsz, imsz_with_options, ImAnimation, ImColorModel, ImDetails, ImError, ImFormat, ImInfo, ImOptions, ImPixelFormat, ImWebpFrame, ImWebpInfo};
+
+fn webp_info(info: &ImInfo) -> ImWebpInfo {
+    match &info.details {
+        ImDetails::WebP(webp_info) => webp_info.clone(),
+        _ => panic!("no WebP details: {:?}", info.details),
+    }
+}
+
+fn chunk(name: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut data = name.to_vec();
+    data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    data.extend_from_slice(payload);
+    if payload.len() % 2 != 0 {
+        data.push(0);
+    }
+    data
+}
+
+fn riff(chunks: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = b"RIFF\0\0\0\0WEBP".to_vec();
+    for item in chunks {
+        data.extend_from_slice(item);
+    }
+    let riff_size = (data.len() - 8) as u32;
+    data[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    data
+}
+
+fn u24s(values: &[u32]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for value in values {
+        data.extend_from_slice(&value.to_le_bytes()[..3]);
+    }
+    data
+}
+
+fn vp8x(flags: u8, width: u32, height: u32) -> Vec<u8> {
+    let mut payload = vec![flags, 0, 0, 0];
+    payload.extend_from_slice(&u24s(&[width - 1, height - 1]));
+    chunk(b"VP8X", &payload)
+}
+
+#[test]
+fn webp_testdata() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join("image_lossy_vp8x.webp");
+    let info = imsz(path).unwrap();
+    assert_eq!(webp_info(&info), ImWebpInfo { icc: true, ..ImWebpInfo::default() });
+}
+
+#[test]
+fn webp_vp8_frame_header() {
+    // keyframe, version 0, show frame; 32 x 16 upscaled by 5/4 and 2
+    let data = riff(&[chunk(b"VP8 ", b"\x10\x02\x00\x9d\x01\x2a\x20\x40\x10\xc0")]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(info.format, ImFormat::WEBP);
+    assert_eq!((info.width, info.height), (32, 16));
+    let details = webp_info(&info);
+    assert_eq!((details.horizontal_scale, details.vertical_scale), (1, 3));
+
+    // interframe
+    let data = riff(&[chunk(b"VP8 ", b"\x11\x02\x00\x9d\x01\x2a\x20\x00\x10\x00")]);
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::WEBP))));
+}
+
+#[test]
+fn webp_vp8x_flags() {
+    // alpha, EXIF and XMP
+    let data = riff(&[vp8x(0x1c, 32, 16), chunk(b"VP8L", b"\x2f\x1f\xc0\x03\x10\x00\x00\x00\x00\x00")]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(webp_info(&info), ImWebpInfo { alpha: true, exif: true, xmp: true, ..ImWebpInfo::default() });
+
+    // the alpha hint of VP8L images
+    let data = riff(&[chunk(b"VP8L", b"\x2f\x1f\xc0\x03\x10\x00\x00\x00\x00\x00")]);
+    assert_eq!(webp_info(&imsz(&data[..]).unwrap()), ImWebpInfo { alpha: true, ..ImWebpInfo::default() });
+}
+
+#[test]
+fn webp_animation_frames() {
+    let mut anim = vec![0; 4];
+    anim.extend_from_slice(&2u16.to_le_bytes());
+    let mut first = u24s(&[0, 0, 31, 15, 100]);
+    first.push(0);
+    let mut second = u24s(&[4, 2, 15, 7, 100]);
+    second.push(0);
+    let data = riff(&[
+        vp8x(0x12, 64, 32),
+        chunk(b"ANIM", &anim),
+        chunk(b"ANMF", &first),
+        chunk(b"ANMF", &second),
+    ]);
+
+    // the frames are only listed when requested
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!(webp_info(&info), ImWebpInfo { alpha: true, animation: true, ..ImWebpInfo::default() });
+
+    let options = ImOptions::new().animation(true);
+    let info = imsz_with_options(&data[..], &options).unwrap();
+    assert_eq!((info.width, info.height), (64, 32));
+    assert_eq!(info.animation, Some(ImAnimation { frames: 2, loop_count: Some(2) }));
+    assert_eq!(webp_info(&info).frames, vec![
+        ImWebpFrame { x: 0, y: 0, width: 32, height: 16 },
+        ImWebpFrame { x: 8, y: 4, width: 16, height:  8 },
+    ]);
+}
+
+#[test]
+fn webp_chunk_order() {
+    // ICCP and unknown chunks before the bitstream, the VP8 header is only
+    // needed for the pixel format
+    let data = riff(&[
+        vp8x(0x20, 32, 16),
+        chunk(b"ICCP", &[0; 7]),
+        chunk(b"XYZW", &[0; 3]),
+        chunk(b"VP8 ", b"\x10\x02\x00\x9d\x01\x2a\x20\x00\x10\x00"),
+    ]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::YCbCr, 3, 8, false)));
+
+    // simple format files with a leading unknown chunk
+    let data = riff(&[chunk(b"XYZW", &[0; 3]), chunk(b"VP8L", b"\x2f\x1f\xc0\x03\x00")]);
+    let info = imsz(&data[..]).unwrap();
+    assert_eq!((info.width, info.height), (32, 16));
+    assert_eq!(info.pixel_format, Some(ImPixelFormat::new(ImColorModel::RGB, 3, 8, false)));
+
+    // no bitstream at all
+    let data = riff(&[chunk(b"ICCP", &[0; 32])]);
+    assert!(matches!(imsz(&data[..]), Err(ImError::ParserError(ImFormat::WEBP))));
+}